@@ -1,7 +1,7 @@
 use actix_web::{post, web::Data, App, HttpRequest, HttpResponse, HttpServer, Responder};
 use actix_web_eventsub::{
-    types::channel::ChannelPointsCustomRewardRedemptionAddV1, Config, EventsubPayload,
-    Verification, VerifyDecodeError,
+    types::channel::ChannelPointsCustomRewardRedemptionAddV1, Config, RedemptionEvent,
+    VerifyDecodeError,
 };
 use std::{
     future::{ready, Ready},
@@ -37,18 +37,21 @@ impl Config for EventsubConfig {
 async fn event_handler(
     event: actix_web_eventsub::Data<ChannelPointsCustomRewardRedemptionAddV1, EventsubConfig>,
 ) -> impl Responder {
-    match event.payload {
-        EventsubPayload::Verification(Verification { challenge, .. }) => {
-            println!("Verification: {}", challenge);
-            HttpResponse::Ok()
-                .content_type(mime::TEXT_PLAIN_UTF_8)
-                .body(challenge)
-        }
-        x => {
-            println!("{:?}", x);
-            HttpResponse::NoContent().finish()
-        }
+    match event.event() {
+        Some(redemption) => println!(
+            "{} redeemed {} ({} points): {}",
+            redemption.redeemer_login(),
+            redemption.reward_title(),
+            redemption.reward_cost(),
+            redemption.user_input().unwrap_or("<no input>")
+        ),
+        // verifications are answered by `handle`
+        None => println!("{:?}", event.payload()),
     }
+    event.handle(
+        |_| HttpResponse::NoContent().finish(),
+        |_| HttpResponse::NoContent().finish(),
+    )
 }
 
 /// Run the example with
@@ -0,0 +1,130 @@
+use actix_web::{post, App, HttpRequest, HttpResponse, HttpServer, Responder};
+use actix_web_eventsub::{
+    subscription::build_create_subscription,
+    types::{channel::ChannelPointsCustomRewardRedemptionAddV1, Status},
+    Config, HmacKey, Revocation, VerifyDecodeError,
+};
+use rand::{distr::Alphanumeric, Rng};
+use std::{
+    future::{ready, Ready},
+    io,
+    sync::RwLock,
+};
+use twitch_api::twitch_oauth2::{AppAccessToken, ClientId, ClientSecret, TwitchToken};
+
+/// The public URL of this server.
+const CALLBACK: &str = "https://example.com/eventsub";
+
+/// The secret passed to the twitch-cli.
+const INITIAL_SECRET: &str = "5f5f121fc807a21bab4209b2f34e90932778f12c099ca3ca17ee00afd0b328ba";
+
+/// The secret of the current subscription.
+///
+/// It's replaced with a fresh one when subscribing again.
+static SECRET: RwLock<String> = RwLock::new(String::new());
+
+struct EventsubConfig;
+
+impl Config for EventsubConfig {
+    type Error = VerifyDecodeError;
+    type CheckEventIdFut = Ready<bool>;
+
+    fn get_secret(_req: &HttpRequest) -> Result<&[u8], Self::Error> {
+        // The secret changes, so it can't be borrowed - it's copied in `get_key` instead.
+        Err(VerifyDecodeError::NoHmacKey)
+    }
+
+    fn get_key(_req: &HttpRequest) -> Result<impl HmacKey + '_, Self::Error> {
+        Ok(SECRET.read().unwrap().clone().into_bytes())
+    }
+
+    fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+        ready(true)
+    }
+
+    fn convert_error(error: VerifyDecodeError) -> Self::Error {
+        error
+    }
+}
+
+#[post("/eventsub")]
+async fn event_handler(
+    event: actix_web_eventsub::Data<ChannelPointsCustomRewardRedemptionAddV1, EventsubConfig>,
+) -> impl Responder {
+    event.handle(
+        |notification| {
+            println!("Notification: {:?}", notification.event);
+            HttpResponse::NoContent().finish()
+        },
+        |revocation| {
+            println!("Revoked: {:?}", revocation.subscription.status);
+            // Respond to Twitch first, the new subscription is created in the background.
+            actix_web::rt::spawn(async move {
+                if let Err(e) = resubscribe(&revocation).await {
+                    eprintln!("Failed to subscribe again: {e}");
+                }
+            });
+            HttpResponse::NoContent().finish()
+        },
+    )
+}
+
+async fn resubscribe(revocation: &Revocation) -> anyhow::Result<()> {
+    // For the other reasons (e.g. the user revoked the authorization),
+    // Twitch would reject the new subscription.
+    if !matches!(
+        revocation.subscription.status,
+        Status::NotificationFailuresExceeded
+    ) {
+        return Ok(());
+    }
+
+    let client = reqwest::Client::new();
+    let token = AppAccessToken::get_app_access_token(
+        &client,
+        ClientId::new(std::env::var("TWITCH_CLIENT_ID")?),
+        ClientSecret::new(std::env::var("TWITCH_CLIENT_SECRET")?),
+        vec![],
+    )
+    .await?;
+
+    let secret: String = rand::rng()
+        .sample_iter(Alphanumeric)
+        .take(64)
+        .map(char::from)
+        .collect();
+    // Type, version and condition are taken from the revoked subscription.
+    let request = build_create_subscription(&revocation.subscription, CALLBACK, &secret);
+
+    // Twitch sends the verification of the new subscription before it responds,
+    // so the fresh secret has to be in place already.
+    let previous = std::mem::replace(&mut *SECRET.write().unwrap(), secret.clone());
+    let response = client
+        .post("https://api.twitch.tv/helix/eventsub/subscriptions")
+        .bearer_auth(token.token().secret())
+        .header("Client-Id", token.client_id().as_str())
+        .json(&request)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
+    if let Err(e) = response {
+        // No subscription uses the fresh secret.
+        *SECRET.write().unwrap() = previous;
+        return Err(e.into());
+    }
+    println!("Subscribed again");
+    Ok(())
+}
+
+/// Run the example with
+/// TWITCH_CLIENT_ID=... TWITCH_CLIENT_SECRET=... cargo r --example resubscribe-actix
+/// To test, use the twitch-cli to send a revocation:
+/// twitch event trigger add-redemption --subscription-status notification_failures_exceeded -F http://127.0.0.1:8080/eventsub -s 5f5f121fc807a21bab4209b2f34e90932778f12c099ca3ca17ee00afd0b328ba
+#[actix_web::main]
+async fn main() -> io::Result<()> {
+    *SECRET.write().unwrap() = INITIAL_SECRET.to_owned();
+    HttpServer::new(|| App::new().service(event_handler))
+        .bind(("127.0.0.1", 8080))?
+        .run()
+        .await
+}
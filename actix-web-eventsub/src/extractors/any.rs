@@ -0,0 +1,154 @@
+//! This module contains the catch-all `EventSub` extractor [`crate::AnyData`].
+
+use crate::extractors::{
+    eventsub::{Config, Responses, VerifyDecodeError},
+    verify,
+};
+use actix_web::{dev, http::header::ContentType, FromRequest, HttpRequest, HttpResponse};
+use bytes::Bytes;
+use eventsub_common::{decode, headers::EventMeta, EventsubPayload};
+use std::{future::Future, marker::PhantomData, pin::Pin};
+
+/// Extractor for an eventsub event of any subscription type and version.
+///
+/// This is meant for endpoints that only log or forward whatever arrives.
+/// The subscription type and version aren't checked, the actual ones are available
+/// in the [metadata](Self::meta). Since the concrete type of the event isn't known,
+/// notifications are decoded into a [`serde_json::Value`].
+///
+/// Skipping these checks doesn't weaken the verification: the signature and the age of
+/// the message are still checked like in [`Data`](crate::Data), so only Twitch can send events.
+/// [`Config::get_key`] provides the key, as there's no subscription to pass to
+/// [`Config::get_key_for`].
+///
+/// ```
+/// # use actix_web::{HttpRequest, HttpResponse, Responder};
+/// # use actix_web_eventsub::{AnyData, VerifyDecodeError};
+/// # struct EventsubConfig;
+/// #
+/// # impl actix_web_eventsub::Config for EventsubConfig {
+/// #     type Error = VerifyDecodeError;
+/// #     type CheckEventIdFut = std::future::Ready<bool>;
+/// #
+/// #     fn get_secret(_req: &HttpRequest) -> Result<&[u8], VerifyDecodeError> {
+/// #         Ok(b"secretsecret")
+/// #     }
+/// #
+/// #     fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+/// #         std::future::ready(true)
+/// #     }
+/// #
+/// #     fn convert_error(error: VerifyDecodeError) -> Self::Error {
+/// #         error
+/// #     }
+/// # }
+/// async fn log_everything(event: AnyData<EventsubConfig>) -> impl Responder {
+///     let meta = event.meta();
+///     println!(
+///         "{} v{}: {:?}",
+///         meta.subscription_type, meta.subscription_version, event.payload
+///     );
+///     event.handle()
+/// }
+/// # fn main() {}
+/// ```
+pub struct AnyData<T> {
+    /// The extracted payload.
+    pub payload: EventsubPayload<serde_json::Value>,
+    meta: EventMeta,
+    body: Bytes,
+    responses: Responses,
+    #[cfg(feature = "opentelemetry")]
+    context: opentelemetry::Context,
+    _config: PhantomData<T>,
+}
+
+impl<T> AnyData<T> {
+    /// The verified body of the request, see [`decode`](eventsub_common::decode#json-backend)
+    /// for how it changes with the `simd-json` feature.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// The extracted payload.
+    pub fn payload(&self) -> &EventsubPayload<serde_json::Value> {
+        &self.payload
+    }
+
+    /// Take the extracted payload, dropping the body and metadata.
+    pub fn into_payload(self) -> EventsubPayload<serde_json::Value> {
+        self.payload
+    }
+
+    /// Metadata of the message, including the actual subscription type and version.
+    pub fn meta(&self) -> &EventMeta {
+        &self.meta
+    }
+
+    /// Split the extracted data into the payload and its metadata.
+    pub fn into_parts(self) -> (EventsubPayload<serde_json::Value>, EventMeta) {
+        (self.payload, self.meta)
+    }
+
+    /// The OpenTelemetry context of the delivery, with its span as the active span.
+    ///
+    /// See [`Data::otel_context`](crate::Data::otel_context).
+    #[cfg(feature = "opentelemetry")]
+    pub fn otel_context(&self) -> &opentelemetry::Context {
+        &self.context
+    }
+
+    /// Make the OpenTelemetry context of the delivery current while `future` is polled.
+    ///
+    /// See [`Data::in_otel_context`](crate::Data::in_otel_context).
+    #[cfg(feature = "opentelemetry")]
+    pub fn in_otel_context<F: Future>(&self, future: F) -> opentelemetry::trace::WithContext<F> {
+        opentelemetry::trace::FutureExt::with_context(future, self.context.clone())
+    }
+
+    /// Respond to the payload: verifications are answered with their challenge,
+    /// notifications with [`Config::notification_response`] and revocations
+    /// with `204 No Content`.
+    pub fn handle(self) -> HttpResponse {
+        match self.payload {
+            EventsubPayload::Verification(verification) => HttpResponse::Ok()
+                .content_type(ContentType::plaintext())
+                .body(verification.challenge),
+            EventsubPayload::Notification(_) => self.responses.notification(),
+            EventsubPayload::Revocation(_) => HttpResponse::NoContent().finish(),
+        }
+    }
+}
+
+impl<T> FromRequest for AnyData<T>
+where
+    T: Config + 'static,
+    T::Error: 'static,
+{
+    type Error = T::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut dev::Payload) -> Self::Future {
+        let pending = verify::start_any::<T>(req, payload);
+        let req = req.clone();
+        let payload = payload.take();
+        Box::pin(async move {
+            let (meta, mut body) = verify::read_verified::<T>(&req, payload, pending?).await?;
+            let payload =
+                decode::decode_any_in_place(meta.message_type, &mut body, T::max_json_depth())
+                    .map_err(|e| verify::in_context::<T>(&meta, VerifyDecodeError::Serde(e)))?;
+            verify::accept::<T>(&req, &meta, payload.subscription())?;
+            verify::check_id::<T>(&req, &meta).await?;
+
+            Ok(Self {
+                payload,
+                #[cfg(feature = "opentelemetry")]
+                context: eventsub_common::otel::delivery_context(&meta),
+                meta,
+                body: body.freeze(),
+                responses: Responses::of::<T>(),
+                _config: PhantomData,
+            })
+        })
+    }
+}
@@ -0,0 +1,177 @@
+//! This module contains the batching `EventSub` extractor [`crate::BatchData`].
+
+use crate::{
+    extractors::{
+        eventsub::{Config, Responses, VerifyDecodeError},
+        verify,
+    },
+    types::EventSubscription,
+};
+use actix_web::{dev, http::header::ContentType, FromRequest, HttpRequest, HttpResponse};
+use bytes::Bytes;
+use eventsub_common::{decode, headers::EventMeta, EventsubPayload, MessageType, Verification};
+use std::{future::Future, marker::PhantomData, pin::Pin};
+
+/// Extractor for a request with one or more eventsub payloads.
+///
+/// Twitch sends one payload per request - use [`Data`](crate::Data) for that. This is meant
+/// for tools replaying recorded deliveries, which may send a JSON array of payloads
+/// (see [`decode_batch`](eventsub_common::decode::decode_batch)).
+/// A single payload is extracted as a batch of one.
+///
+/// The signature covers the whole body and the headers are shared by all payloads,
+/// so [`Config::check_event_id`] is called once for the batch.
+/// [`Config::is_subscription_allowed`], [`Config::accept_condition`] and [`Config::on_verified`]
+/// are called for every payload.
+///
+/// ```
+/// # use actix_web::{HttpRequest, HttpResponse, Responder};
+/// # use actix_web_eventsub::{BatchData, EventsubPayload, VerifyDecodeError, types::channel::ChannelPointsCustomRewardRedemptionAddV1};
+/// # struct EventsubConfig;
+/// #
+/// # impl actix_web_eventsub::Config for EventsubConfig {
+/// #     type Error = VerifyDecodeError;
+/// #     type CheckEventIdFut = std::future::Ready<bool>;
+/// #
+/// #     fn get_secret(_req: &HttpRequest) -> Result<&[u8], VerifyDecodeError> {
+/// #         Ok(b"secretsecret")
+/// #     }
+/// #
+/// #     fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+/// #         std::future::ready(true)
+/// #     }
+/// #
+/// #     fn convert_error(error: VerifyDecodeError) -> Self::Error {
+/// #         error
+/// #     }
+/// # }
+/// async fn replay(
+///     batch: BatchData<ChannelPointsCustomRewardRedemptionAddV1, EventsubConfig>,
+/// ) -> impl Responder {
+///     for payload in batch.payloads() {
+///         if let EventsubPayload::Notification(notification) = payload {
+///             println!("Notification: {:?}", notification.event);
+///         }
+///     }
+///     batch.response()
+/// }
+/// # fn main() {}
+/// ```
+pub struct BatchData<P: EventSubscription, T> {
+    /// The extracted payloads.
+    pub payloads: Vec<EventsubPayload<P::Payload>>,
+    meta: EventMeta,
+    body: Bytes,
+    responses: Responses,
+    #[cfg(feature = "opentelemetry")]
+    context: opentelemetry::Context,
+    _config: PhantomData<T>,
+}
+
+impl<P: EventSubscription, T> BatchData<P, T> {
+    /// The verified body of the request, see [`decode`](eventsub_common::decode#json-backend)
+    /// for how it changes with the `simd-json` feature.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// The extracted payloads.
+    pub fn payloads(&self) -> &[EventsubPayload<P::Payload>] {
+        &self.payloads
+    }
+
+    /// Take the extracted payloads, dropping the body and metadata.
+    pub fn into_payloads(self) -> Vec<EventsubPayload<P::Payload>> {
+        self.payloads
+    }
+
+    /// Metadata of the request, shared by all payloads.
+    pub fn meta(&self) -> &EventMeta {
+        &self.meta
+    }
+
+    /// Split the extracted data into the payloads and their metadata.
+    pub fn into_parts(self) -> (Vec<EventsubPayload<P::Payload>>, EventMeta) {
+        (self.payloads, self.meta)
+    }
+
+    /// The OpenTelemetry context of the delivery, with its span as the active span.
+    ///
+    /// See [`Data::otel_context`](crate::Data::otel_context).
+    #[cfg(feature = "opentelemetry")]
+    pub fn otel_context(&self) -> &opentelemetry::Context {
+        &self.context
+    }
+
+    /// Make the OpenTelemetry context of the delivery current while `future` is polled.
+    ///
+    /// See [`Data::in_otel_context`](crate::Data::in_otel_context).
+    #[cfg(feature = "opentelemetry")]
+    pub fn in_otel_context<F: Future>(&self, future: F) -> opentelemetry::trace::WithContext<F> {
+        opentelemetry::trace::FutureExt::with_context(future, self.context.clone())
+    }
+
+    /// The response to the batch: the challenge of the first verification,
+    /// [`Config::notification_response`] if the batch holds notifications,
+    /// or `204 No Content` otherwise.
+    pub fn response(&self) -> HttpResponse {
+        self.payloads
+            .iter()
+            .find_map(|payload| match payload {
+                EventsubPayload::Verification(Verification { challenge, .. }) => Some(
+                    HttpResponse::Ok()
+                        .content_type(ContentType::plaintext())
+                        .body(challenge.clone()),
+                ),
+                _ => None,
+            })
+            .unwrap_or_else(|| match self.meta.message_type {
+                MessageType::Notification => self.responses.notification(),
+                _ => HttpResponse::NoContent().finish(),
+            })
+    }
+}
+
+impl<P, T> FromRequest for BatchData<P, T>
+where
+    P: EventSubscription + 'static,
+    T: Config + 'static,
+    T::Error: 'static,
+{
+    type Error = T::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut dev::Payload) -> Self::Future {
+        let pending = verify::start_for::<P, T>(req, payload);
+        let req = req.clone();
+        let payload = payload.take();
+        Box::pin(async move {
+            let (meta, mut body) = verify::read_verified::<T>(&req, payload, pending?).await?;
+            let payloads = decode::decode_batch_in_place::<P>(
+                meta.message_type,
+                &mut body,
+                T::max_json_depth(),
+            )
+            .map_err(|e| verify::in_context::<T>(&meta, VerifyDecodeError::Serde(e)))?;
+            // the whole batch is rejected before any of it is observed
+            for payload in &payloads {
+                verify::check::<T>(&req, payload.subscription())
+                    .map_err(|e| verify::in_context::<T>(&meta, e))?;
+            }
+            for payload in &payloads {
+                verify::observe::<T>(&req, payload.subscription(), meta.message_type);
+            }
+            verify::check_id::<T>(&req, &meta).await?;
+
+            Ok(Self {
+                payloads,
+                #[cfg(feature = "opentelemetry")]
+                context: eventsub_common::otel::delivery_context(&meta),
+                meta,
+                body: body.freeze(),
+                responses: Responses::of::<T>(),
+                _config: PhantomData,
+            })
+        })
+    }
+}
@@ -0,0 +1,58 @@
+//! Reading request bodies through [`eventsub_common::body`].
+
+use crate::extractors::eventsub::VerifyDecodeError;
+use actix_web::{dev, error::PayloadError, rt::time::timeout};
+use bytes::{Bytes, BytesMut};
+use eventsub_common::body::{self, BodyError};
+use futures_util::Stream;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// Adapts the [`dev::Payload`] of a request (or a reference to it) to `http-body` 1.0.
+pub(crate) struct PayloadBody<P>(pub(crate) P);
+
+impl<P> http_body::Body for PayloadBody<P>
+where
+    P: Stream<Item = Result<Bytes, PayloadError>> + Unpin,
+{
+    type Data = Bytes;
+    type Error = PayloadError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Bytes>, PayloadError>>> {
+        Pin::new(&mut self.0)
+            .poll_next(cx)
+            .map(|chunk| chunk.map(|chunk| chunk.map(http_body::Frame::data)))
+    }
+}
+
+impl From<BodyError<PayloadError>> for VerifyDecodeError {
+    fn from(error: BodyError<PayloadError>) -> Self {
+        match error {
+            BodyError::TooLarge(_) => VerifyDecodeError::RequestTooLarge,
+            BodyError::Body(e) => VerifyDecodeError::PayloadError(e),
+        }
+    }
+}
+
+/// Read the complete payload of at most `limit` bytes (see [`Config::max_body_size`](crate::Config::max_body_size)),
+/// giving up after `read_timeout` (see [`Config::read_timeout`](crate::Config::read_timeout)).
+pub(crate) async fn read(
+    payload: dev::Payload,
+    limit: usize,
+    read_timeout: Option<Duration>,
+) -> Result<BytesMut, VerifyDecodeError> {
+    let read = body::read_body(PayloadBody(payload), limit);
+    match read_timeout {
+        Some(duration) => timeout(duration, read)
+            .await
+            .map_err(|_| VerifyDecodeError::ReadTimeout)?,
+        None => read.await,
+    }
+    .map_err(VerifyDecodeError::from)
+}
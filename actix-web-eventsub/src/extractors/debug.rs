@@ -0,0 +1,100 @@
+//! This module contains the diagnostic extractor [`crate::DebugReport`].
+
+use crate::extractors::{
+    body,
+    eventsub::{Config, VerifyDecodeError},
+    verify::is_consumed,
+};
+use actix_web::{body::BoxBody, dev, FromRequest, HttpRequest, HttpResponse, Responder};
+use eventsub_common::debug::{self, Report};
+use std::{future::Future, marker::PhantomData, pin::Pin};
+
+/// Extractor reporting how a request is read and verified, without rejecting it.
+///
+/// **Never expose this in production.** The report reveals the start of the expected
+/// signature, which helps forging requests (see [`eventsub_common::debug`]).
+/// Mount it temporarily on a separate route to find out why a subscription fails.
+///
+/// Any POST is accepted, no matter the subscription type. The key comes from
+/// [`Config::get_key`] - if it fails, the signature isn't computed.
+/// The extractor responds with the report as JSON.
+///
+/// ```
+/// # use actix_web::{post, HttpRequest};
+/// # use actix_web_eventsub::{DebugReport, VerifyDecodeError};
+/// # struct EventsubConfig;
+/// #
+/// # impl actix_web_eventsub::Config for EventsubConfig {
+/// #     type Error = VerifyDecodeError;
+/// #     type CheckEventIdFut = std::future::Ready<bool>;
+/// #
+/// #     fn get_secret(_req: &HttpRequest) -> Result<&[u8], VerifyDecodeError> {
+/// #         Ok(b"secretsecret")
+/// #     }
+/// #
+/// #     fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+/// #         std::future::ready(true)
+/// #     }
+/// #
+/// #     fn convert_error(error: VerifyDecodeError) -> Self::Error {
+/// #         error
+/// #     }
+/// # }
+/// #[post("/eventsub/debug")]
+/// async fn debug_handler(report: DebugReport<EventsubConfig>) -> DebugReport<EventsubConfig> {
+///     eprintln!("{:?}", report.report);
+///     report
+/// }
+/// # fn main() {}
+/// ```
+pub struct DebugReport<T> {
+    /// The report of the request.
+    pub report: Report,
+    _config: PhantomData<T>,
+}
+
+impl<T> DebugReport<T> {
+    /// Take the report.
+    #[must_use]
+    pub fn into_inner(self) -> Report {
+        self.report
+    }
+}
+
+impl<T> FromRequest for DebugReport<T>
+where
+    T: Config + 'static,
+    T::Error: 'static,
+{
+    type Error = T::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut dev::Payload) -> Self::Future {
+        if is_consumed(req, payload) {
+            return Box::pin(std::future::ready(Err(T::convert_error(
+                VerifyDecodeError::PayloadAlreadyConsumed,
+            ))));
+        }
+        let req = req.clone();
+        let payload = payload.take();
+        Box::pin(async move {
+            let body = body::read(payload, T::max_body_size(), T::read_timeout())
+                .await
+                .map_err(T::convert_error)?;
+
+            let key = T::get_key(&req).ok();
+            Ok(Self {
+                report: debug::report(req.headers(), key.as_ref(), &body),
+                _config: PhantomData,
+            })
+        })
+    }
+}
+
+impl<T> Responder for DebugReport<T> {
+    type Body = BoxBody;
+
+    fn respond_to(self, _req: &HttpRequest) -> HttpResponse<Self::Body> {
+        HttpResponse::Ok().json(&self.report)
+    }
+}
@@ -1,30 +1,37 @@
 //! This module contains the main `EventSub` extractor [`crate::Data`].
 
-use crate::types::EventSubscription;
-use actix_web::{dev, error::PayloadError, FromRequest, HttpRequest, ResponseError};
-use bytes::BytesMut;
-pub use eventsub_common::headers::{HeaderType, InvalidHeaders};
-use eventsub_common::{
-    headers,
-    headers::{HeaderMapExt, PayloadHeaders},
-    EventsubPayload, MessageType,
+use crate::{
+    extractors::{
+        body::PayloadBody,
+        verify::{self, Pending},
+    },
+    types::{EventSubSubscription, EventSubscription},
 };
-use futures_util::{future::Either, StreamExt};
-use hmac::{
-    digest::{generic_array::GenericArray, InvalidLength},
-    Hmac, Mac,
+use actix_web::{
+    dev,
+    error::PayloadError,
+    http::{header::ContentType, StatusCode},
+    rt::time::{sleep, Sleep},
+    Either as ResponseEither, FromRequest, HttpRequest, HttpResponse, Responder, ResponseError,
+};
+use bytes::{Bytes, BytesMut};
+pub use eventsub_common::headers::{EventMeta, HeaderType, InvalidHeaderValue, InvalidHeaders};
+use eventsub_common::{
+    body, decode, error::ErrorContext, secret::HmacKey, verify::StreamingVerifier, Condition,
+    EventsubPayload, MessageType, Notification, Revocation, Verification,
 };
+use futures_util::future::Either;
+use hmac::digest::InvalidLength;
 use pin_project::pin_project;
-use sha2::Sha256;
 use std::{
+    fmt,
     future::{ready, Future, Ready},
     marker::PhantomData,
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
 };
 
-type HmacSha256 = Hmac<Sha256>;
-
 /// Extractor for an eventsub event.
 ///
 /// This will verify (hash, subscription-type, time, duplicate ids) and deserialize the event for you.
@@ -35,13 +42,17 @@ type HmacSha256 = Hmac<Sha256>;
 /// You need to provide a [`EventSubscription`] as the type of event you want to receive and a
 /// [`Config`] that provides the secret and converts potential errors to your preferred error type.
 ///
+/// This extractor reads the body, so it must be the only extractor consuming the payload.
+/// If another extractor (e.g. [`web::Bytes`](actix_web::web::Bytes)) read it first,
+/// the request is rejected with [`VerifyDecodeError::PayloadAlreadyConsumed`].
+///
 /// Make sure that processing the event doesn't take too long, otherwise
 /// twitch might revoke your subscription.
 /// Consider doing expensive work in [`actix_web::rt::spawn`].
 ///
 /// ```
-/// # use actix_web::{HttpRequest, HttpResponse, Responder, web::{self, Data}};
-/// # use actix_web_eventsub::{EventsubPayload, Verification, VerifyDecodeError, types::channel::ChannelPointsCustomRewardRedemptionAddV1};
+/// # use actix_web::{HttpRequest, Responder, web::{self, Data}};
+/// # use actix_web_eventsub::{VerifyDecodeError, types::channel::ChannelPointsCustomRewardRedemptionAddV1};
 /// # struct EventsubConfig;
 /// #
 /// # impl actix_web_eventsub::Config for EventsubConfig {
@@ -66,30 +77,304 @@ type HmacSha256 = Hmac<Sha256>;
 /// async fn event_handler(
 ///     event: actix_web_eventsub::Data<ChannelPointsCustomRewardRedemptionAddV1, EventsubConfig>,
 /// ) -> impl Responder {
-/// match event.payload {
-///         EventsubPayload::Verification(Verification { challenge, .. }) => {
-///             println!("Verification: {}", challenge);
-///             HttpResponse::Ok()
-///                 .content_type(mime::TEXT_PLAIN_UTF_8)
-///                 .body(challenge)
-///         }
-///         x => {
-///             println!("{:?}", x);
-///             HttpResponse::NoContent().finish()
+///     // Verifications are answered with the challenge automatically.
+///     event.handle(
+///         |notification| {
+///             println!("Notification: {:?}", notification.event);
+///             ""
+///         },
+///         |revocation| {
+///             println!("Revoked: {:?}", revocation.subscription);
+///             ""
+///         },
+///     )
+/// }
+/// # fn main() {}
+/// ```
+pub struct Data<P: EventSubscription, T> {
+    /// The extracted payload.
+    pub payload: EventsubPayload<P::Payload>,
+    meta: EventMeta,
+    body: Bytes,
+    responses: Responses,
+    #[cfg(feature = "opentelemetry")]
+    context: opentelemetry::Context,
+    _config: PhantomData<T>,
+}
+
+/// Extractor for an eventsub event that doesn't reject the request.
+///
+/// Verification and decoding errors are passed to the handler (as [`Config::Error`]),
+/// so it can decide how to respond.
+///
+/// ```
+/// # use actix_web::{Either, HttpRequest, HttpResponse, Responder};
+/// # use actix_web_eventsub::{TryData, VerifyDecodeError, types::channel::ChannelPointsCustomRewardRedemptionAddV1};
+/// # struct EventsubConfig;
+/// #
+/// # impl actix_web_eventsub::Config for EventsubConfig {
+/// #     type Error = VerifyDecodeError;
+/// #     type CheckEventIdFut = std::future::Ready<bool>;
+/// #
+/// #     fn get_secret(_req: &HttpRequest) -> Result<&[u8], VerifyDecodeError> {
+/// #         Ok(b"secretsecret")
+/// #     }
+/// #
+/// #     fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+/// #         std::future::ready(true)
+/// #     }
+/// #
+/// #     fn convert_error(error: VerifyDecodeError) -> Self::Error {
+/// #         error
+/// #     }
+/// # }
+/// #
+/// async fn event_handler(
+///     event: TryData<ChannelPointsCustomRewardRedemptionAddV1, EventsubConfig>,
+/// ) -> impl Responder {
+///     match event {
+///         Ok(event) => Either::Left(event.handle(|_| "", |_| "")),
+///         Err(e) => {
+///             println!("Rejected eventsub request: {e}");
+///             Either::Right(HttpResponse::Unauthorized().finish())
 ///         }
 ///     }
 /// }
 /// # fn main() {}
 /// ```
-pub struct Data<P, T> {
+pub type TryData<P, T> = Result<Data<P, T>, <T as Config>::Error>;
+
+impl<P: EventSubscription, T> Data<P, T> {
+    /// The verified body of the request, see [`decode`](eventsub_common::decode#json-backend)
+    /// for how it changes with the `simd-json` feature.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
     /// The extracted payload.
-    pub payload: EventsubPayload<P>,
-    _config: PhantomData<T>,
+    ///
+    /// [`EventsubPayload`] is the same type for actix-web and axum (it's defined in
+    /// `eventsub_common`), so handler logic taking it can be shared between both.
+    pub fn payload(&self) -> &EventsubPayload<P::Payload> {
+        &self.payload
+    }
+
+    /// The event of a notification, or [`None`] for verifications and revocations.
+    ///
+    /// For redemptions, read its fields through [`RedemptionEvent`](crate::RedemptionEvent):
+    /// `data.event().map(|redemption| redemption.reward_title())`.
+    pub fn event(&self) -> Option<&P::Payload> {
+        self.payload.event()
+    }
+
+    /// Take the extracted payload, dropping the body and metadata.
+    pub fn into_payload(self) -> EventsubPayload<P::Payload> {
+        self.payload
+    }
+
+    /// Metadata of the message, such as its id and timestamp.
+    pub fn meta(&self) -> &EventMeta {
+        &self.meta
+    }
+
+    /// Split the extracted data into the payload and its metadata.
+    ///
+    /// Use this to move the event into a spawned task without naming the config.
+    pub fn into_parts(self) -> (EventsubPayload<P::Payload>, EventMeta) {
+        (self.payload, self.meta)
+    }
+
+    /// Deserialize the condition of the subscription (e.g. `broadcaster_user_id`).
+    ///
+    /// See [`EventsubPayload::condition`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the condition isn't a valid condition for `P`.
+    pub fn condition(&self) -> Result<P, serde_json::Error> {
+        self.payload.condition()
+    }
+
+    /// The OpenTelemetry context of the delivery, with its span as the active span.
+    ///
+    /// A fresh trace is started for every delivery (see [`eventsub_common::otel`]).
+    /// [`handle`](Self::handle) and [`spawn_handler`](Self::spawn_handler) make it current
+    /// for their callbacks, [`in_otel_context`](Self::in_otel_context) for any other future.
+    /// The span ends when this data and all clones of the context are dropped.
+    #[cfg(feature = "opentelemetry")]
+    pub fn otel_context(&self) -> &opentelemetry::Context {
+        &self.context
+    }
+
+    /// Make the OpenTelemetry context of the delivery current while `future` is polled.
+    ///
+    /// Spans started while processing the event become children of the delivery span.
+    /// Wrap the work of the handler that isn't done in the callbacks of [`handle`](Self::handle)
+    /// or [`spawn_handler`](Self::spawn_handler):
+    ///
+    /// ```
+    /// # use actix_web::{HttpRequest, HttpResponse, Responder};
+    /// # use actix_web_eventsub::{Data, VerifyDecodeError, types::channel::ChannelPointsCustomRewardRedemptionAddV1};
+    /// # struct EventsubConfig;
+    /// #
+    /// # impl actix_web_eventsub::Config for EventsubConfig {
+    /// #     type Error = VerifyDecodeError;
+    /// #     type CheckEventIdFut = std::future::Ready<bool>;
+    /// #
+    /// #     fn get_secret(_req: &HttpRequest) -> Result<&[u8], VerifyDecodeError> {
+    /// #         Ok(b"secretsecret")
+    /// #     }
+    /// #
+    /// #     fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+    /// #         std::future::ready(true)
+    /// #     }
+    /// #
+    /// #     fn convert_error(error: VerifyDecodeError) -> Self::Error {
+    /// #         error
+    /// #     }
+    /// # }
+    /// # async fn store(_id: &str) {}
+    /// async fn event_handler(
+    ///     event: Data<ChannelPointsCustomRewardRedemptionAddV1, EventsubConfig>,
+    /// ) -> impl Responder {
+    ///     // spans of the database write are children of the delivery span
+    ///     event.in_otel_context(store(&event.meta().message_id)).await;
+    ///     event.handle(
+    ///         |_| HttpResponse::NoContent().finish(),
+    ///         |_| HttpResponse::NoContent().finish(),
+    ///     )
+    /// }
+    /// # fn main() {}
+    /// ```
+    #[cfg(feature = "opentelemetry")]
+    pub fn in_otel_context<F: Future>(&self, future: F) -> opentelemetry::trace::WithContext<F> {
+        opentelemetry::trace::FutureExt::with_context(future, self.context.clone())
+    }
+
+    /// Respond to the payload without matching on it.
+    ///
+    /// Verifications are answered with their challenge.
+    /// Notifications and revocations are passed to `on_notification` and `on_revocation`
+    /// respectively, and their return value is used as the response - unless
+    /// [`Config::auto_ack_revocations`] is set, then revocations are answered with `204 No Content`.
+    pub fn handle<N, R>(
+        self,
+        on_notification: impl FnOnce(Notification<P::Payload>) -> N,
+        on_revocation: impl FnOnce(Revocation) -> R,
+    ) -> ResponseEither<HttpResponse, ResponseEither<N, R>>
+    where
+        N: Responder,
+        R: Responder,
+    {
+        #[cfg(feature = "opentelemetry")]
+        let _guard = self.context.clone().attach();
+        match self.payload {
+            EventsubPayload::Verification(Verification { challenge, .. }) => ResponseEither::Left(
+                HttpResponse::Ok()
+                    .content_type(ContentType::plaintext())
+                    .body(challenge),
+            ),
+            EventsubPayload::Notification(notification) => {
+                ResponseEither::Right(ResponseEither::Left(on_notification(notification)))
+            }
+            EventsubPayload::Revocation(revocation) if self.responses.auto_ack_revocations => {
+                let _ = on_revocation(revocation);
+                ResponseEither::Left(HttpResponse::NoContent().finish())
+            }
+            EventsubPayload::Revocation(revocation) => {
+                ResponseEither::Right(ResponseEither::Right(on_revocation(revocation)))
+            }
+        }
+    }
+}
+
+impl<P: EventSubscription + 'static, T: 'static> Data<P, T> {
+    /// Respond right away and process the event on a separate task.
+    ///
+    /// Twitch expects a response within a few seconds, so slow handlers risk retries
+    /// and revoked subscriptions. Verifications are answered with their challenge and
+    /// aren't passed to `handler`. Notifications are answered with
+    /// [`Config::notification_response`] and revocations with `204 No Content` without
+    /// waiting for `handler`, which runs on the actix runtime ([`actix_web::rt::spawn`]).
+    ///
+    /// The response is already sent when `handler` runs, so it can't report errors to Twitch.
+    /// If it panics, the metadata of the message is passed to [`Config::on_handler_panic`].
+    ///
+    /// ```
+    /// # use actix_web::{HttpRequest, HttpResponse, Responder};
+    /// # use actix_web_eventsub::{Data, VerifyDecodeError, types::channel::ChannelPointsCustomRewardRedemptionAddV1};
+    /// # struct EventsubConfig;
+    /// #
+    /// # impl actix_web_eventsub::Config for EventsubConfig {
+    /// #     type Error = VerifyDecodeError;
+    /// #     type CheckEventIdFut = std::future::Ready<bool>;
+    /// #
+    /// #     fn get_secret(_req: &HttpRequest) -> Result<&[u8], VerifyDecodeError> {
+    /// #         Ok(b"secretsecret")
+    /// #     }
+    /// #
+    /// #     fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+    /// #         std::future::ready(true)
+    /// #     }
+    /// #
+    /// #     fn convert_error(error: VerifyDecodeError) -> Self::Error {
+    /// #         error
+    /// #     }
+    /// # }
+    /// async fn event_handler(
+    ///     event: Data<ChannelPointsCustomRewardRedemptionAddV1, EventsubConfig>,
+    /// ) -> impl Responder {
+    ///     event.spawn_handler(|event| async move {
+    ///         // something slow, like writing to a database
+    ///         println!("Handled {}", event.meta().message_id);
+    ///     })
+    /// }
+    /// # fn main() {}
+    /// ```
+    pub fn spawn_handler<F, Fut>(self, handler: F) -> HttpResponse
+    where
+        F: FnOnce(Self) -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        if let EventsubPayload::Verification(Verification { challenge, .. }) = self.payload {
+            return HttpResponse::Ok()
+                .content_type(ContentType::plaintext())
+                .body(challenge);
+        }
+        let meta = self.meta.clone();
+        let responses = self.responses;
+        let response = match self.payload {
+            EventsubPayload::Notification(_) => responses.notification(),
+            _ => HttpResponse::NoContent().finish(),
+        };
+        #[cfg(feature = "opentelemetry")]
+        let context = self.context.clone();
+        let handler = async move { handler(self).await };
+        #[cfg(feature = "opentelemetry")]
+        let handler = opentelemetry::trace::FutureExt::with_context(handler, context);
+        let task = actix_web::rt::spawn(handler);
+        actix_web::rt::spawn(async move {
+            if task.await.is_err_and(|e| e.is_panic()) {
+                (responses.on_handler_panic)(&meta);
+            }
+        });
+        response
+    }
 }
 
 /// Errors when verifying and decoding the eventsub payload.
-#[derive(Debug, thiserror::Error, actix_web_error::Json)]
-#[status(BAD_REQUEST)]
+///
+/// Each error responds with a fixed status (see [`ConfiguredError`] to change it):
+///
+/// | Error | Status |
+/// |-------|--------|
+/// | [`RetryLimitExceeded`](VerifyDecodeError::RetryLimitExceeded), [`ConditionNotAccepted`](VerifyDecodeError::ConditionNotAccepted) | `204 No Content` |
+/// | [`SubscriptionNotAllowed`](VerifyDecodeError::SubscriptionNotAllowed) | `403 Forbidden` |
+/// | [`ReadTimeout`](VerifyDecodeError::ReadTimeout) | `408 Request Timeout` |
+/// | [`BadContentType`](VerifyDecodeError::BadContentType) | `415 Unsupported Media Type` |
+/// | [`PayloadAlreadyConsumed`](VerifyDecodeError::PayloadAlreadyConsumed), [`NoHmacKey`](VerifyDecodeError::NoHmacKey), [`HmacInit`](VerifyDecodeError::HmacInit) | `500 Internal Server Error` |
+/// | Everything else | `400 Bad Request` |
+#[derive(Debug, thiserror::Error)]
 pub enum VerifyDecodeError {
     /// An issue with the headers. See [`eventsub_common::headers::InvalidHeaders`] for more detail.
     #[error("Invalid headers: {0}")]
@@ -97,32 +382,54 @@ pub enum VerifyDecodeError {
     /// The provided signature was incorrect - it didn't match the computed one.
     #[error("The provided signature wasn't expected")]
     SignatureMismatch,
-    /// The payload was too large (>10MB).
-    #[error("The request was too large (> 10MB)")]
+    /// The payload was larger than [`Config::max_body_size`].
+    #[error("The request was too large")]
     RequestTooLarge,
     /// actix-web couldn't parse the payload.
     #[error("Payload error: {0}")]
     PayloadError(PayloadError),
+    /// Another extractor read the payload before this one.
+    #[error("The payload was already consumed by another extractor")]
+    PayloadAlreadyConsumed,
+    /// The payload wasn't received within [`Config::read_timeout`].
+    #[error("Timed out while reading the payload")]
+    ReadTimeout,
     /// serde_json couldn't deserialize the payload.
     #[error("JSON Deserialization error: {0}")]
     Serde(serde_json::Error),
     /// No HMAC key was provided - [`Config::get_secret`] returned [`None`].
     #[error("No HMAC key provided")]
-    #[status(INTERNAL_SERVER_ERROR)]
     NoHmacKey,
     /// The HMAC key was too short - [`Config::get_secret`] returned a slice that was too short.
     #[error("Bad secret key")]
-    #[status(INTERNAL_SERVER_ERROR)]
     HmacInit(InvalidLength),
     /// The subscription version didn't match the expected one.
     #[error("Version mismatch - expected {0}")]
     VersionMismatch(&'static str),
     /// The message id wasn't valid utf8
+    ///
+    /// This is rejected while reading the headers, so the body isn't read.
     #[error("The message id wasn't valid utf8")]
     IdNotUtf8,
     /// This message won't be handled because [`Config::check_event_id`] resolved to `false`.
     #[error("Won't handle id (possible duplicate)")]
     WontHandleId,
+    /// The subscription isn't accepted - [`Config::is_subscription_allowed`] returned `false`.
+    #[error("Events of this subscription aren't accepted")]
+    SubscriptionNotAllowed,
+    /// The subscription condition isn't accepted - [`Config::accept_condition`] returned `false`.
+    ///
+    /// This is answered with `204 No Content` by default, so Twitch doesn't retry the event.
+    #[error("Events with this condition aren't accepted")]
+    ConditionNotAccepted,
+    /// The `Content-Type` wasn't `application/json` - see [`Config::require_json_content_type`].
+    #[error("Expected an application/json body")]
+    BadContentType,
+    /// Twitch retried the message more often than [`Config::max_retries`] allows.
+    ///
+    /// This responds with `204 No Content` without a body, so Twitch stops retrying.
+    #[error("The message was retried too often")]
+    RetryLimitExceeded,
 }
 
 /// Configuration for verifying and decoding eventsub payloads.
@@ -139,22 +446,502 @@ pub trait Config {
     ///
     /// This should always return [`Ok`].
     ///
+    /// If multiple endpoints use different secrets, select the secret based on the request
+    /// (see [`PathScopedSecret`](crate::PathScopedSecret)):
+    ///
+    /// ```
+    /// # use actix_web::{web, HttpRequest};
+    /// # use actix_web_eventsub::{Config, PathScopedSecret, VerifyDecodeError};
+    /// struct EventsubConfig;
+    ///
+    /// impl Config for EventsubConfig {
+    ///     type Error = VerifyDecodeError;
+    ///     type CheckEventIdFut = std::future::Ready<bool>;
+    ///
+    ///     // For routes like `/eventsub/{broadcaster}`
+    ///     fn get_secret(req: &HttpRequest) -> Result<&[u8], VerifyDecodeError> {
+    ///         req.app_data::<web::Data<PathScopedSecret>>()
+    ///             .zip(req.match_info().get("broadcaster"))
+    ///             .and_then(|(secrets, broadcaster)| secrets.get(broadcaster))
+    ///             .ok_or(VerifyDecodeError::NoHmacKey)
+    ///     }
+    ///
+    ///     fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+    ///         std::future::ready(true)
+    ///     }
+    ///
+    ///     fn convert_error(error: VerifyDecodeError) -> Self::Error {
+    ///         error
+    ///     }
+    /// }
+    /// ```
+    ///
     /// ## Errors
     ///
     /// If you can't get the secret, return an error instead of panicking.
     fn get_secret(req: &HttpRequest) -> Result<&[u8], Self::Error>;
 
+    /// Get the key to verify messages of any subscription with.
+    ///
+    /// This is used by the extractors that accept any subscription
+    /// ([`AnyData`](crate::AnyData) and [`VerifyingStream`](crate::VerifyingStream)),
+    /// and by [`Config::get_key_for`] unless that's overridden.
+    ///
+    /// Defaults to [`Config::get_secret`].
+    ///
+    /// ## Errors
+    ///
+    /// See [`Config::get_secret`].
+    fn get_key(req: &HttpRequest) -> Result<impl HmacKey + '_, Self::Error> {
+        Self::get_secret(req)
+    }
+
+    /// Get the key to verify messages for the subscription `P` with.
+    ///
+    /// Override this if subscriptions were created with different secrets (select the secret
+    /// by `P::EVENT_TYPE`), or if you don't store the raw secret, but derive it when needed
+    /// (e.g. with HKDF from a master key). The key material is only used to initialize
+    /// the HMAC and dropped right after.
+    ///
+    /// Defaults to [`Config::get_key`].
+    ///
+    /// ```
+    /// # use actix_web::{web, HttpRequest};
+    /// # use actix_web_eventsub::{Config, HmacKey, VerifyDecodeError, types::EventSubscription};
+    /// # use std::borrow::Cow;
+    /// # fn hkdf_expand(master: &[u8], info: &[u8]) -> Vec<u8> { [master, info].concat() }
+    /// struct MasterKey(Vec<u8>);
+    ///
+    /// struct DerivedKey<'a>(&'a MasterKey, &'static str);
+    ///
+    /// impl HmacKey for DerivedKey<'_> {
+    ///     fn key_material(&self) -> Cow<'_, [u8]> {
+    ///         Cow::Owned(hkdf_expand(&self.0 .0, self.1.as_bytes()))
+    ///     }
+    /// }
+    ///
+    /// struct EventsubConfig;
+    ///
+    /// impl Config for EventsubConfig {
+    ///     type Error = VerifyDecodeError;
+    ///     type CheckEventIdFut = std::future::Ready<bool>;
+    ///
+    ///     fn get_secret(_req: &HttpRequest) -> Result<&[u8], VerifyDecodeError> {
+    ///         Err(VerifyDecodeError::NoHmacKey)
+    ///     }
+    ///
+    ///     fn get_key_for<P: EventSubscription>(
+    ///         req: &HttpRequest,
+    ///     ) -> Result<impl HmacKey + '_, VerifyDecodeError> {
+    ///         let master = req
+    ///             .app_data::<web::Data<MasterKey>>()
+    ///             .ok_or(VerifyDecodeError::NoHmacKey)?;
+    ///         // one key per subscription type
+    ///         Ok(DerivedKey(master, P::EVENT_TYPE.to_str()))
+    ///     }
+    ///
+    ///     fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+    ///         std::future::ready(true)
+    ///     }
+    ///
+    ///     fn convert_error(error: VerifyDecodeError) -> Self::Error {
+    ///         error
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// See [`Config::get_secret`].
+    fn get_key_for<P: EventSubscription>(
+        req: &HttpRequest,
+    ) -> Result<impl HmacKey + '_, Self::Error> {
+        Self::get_key(req)
+    }
+
     /// Check if you've already seen this id.
     ///
     /// The returned [`Future`] should resolve to `true` if you want to handle this event
     /// (i.e. you haven't seen the id in the last ≈10min).
     fn check_event_id(req: &HttpRequest, id: &str) -> Self::CheckEventIdFut;
 
+    /// Check if events of the subscription with the id `subscription_id` should be accepted.
+    ///
+    /// This is checked after the payload is verified and decoded, but before
+    /// [`Config::check_event_id`] - for all message types, so add the id of a new subscription
+    /// before Twitch sends the verification.
+    /// Rejected requests fail with [`VerifyDecodeError::SubscriptionNotAllowed`].
+    ///
+    /// An allow-list of the subscriptions you created defends against someone reusing
+    /// your callback URL and secret for their own subscriptions.
+    /// It doesn't help if the secret is compromised, as the attacker can forge any payload then.
+    ///
+    /// Defaults to accepting all subscriptions.
+    #[must_use]
+    fn is_subscription_allowed(req: &HttpRequest, subscription_id: &str) -> bool {
+        let _ = (req, subscription_id);
+        true
+    }
+
+    /// Check if events with the subscription condition `condition` should be accepted.
+    ///
+    /// The condition holds fields like `broadcaster_user_id`, which aren't sent as headers.
+    /// That's why this can't be checked before the body is read: it runs after the payload
+    /// is verified and decoded, right after [`Config::is_subscription_allowed`].
+    /// Rejected requests fail with [`VerifyDecodeError::ConditionNotAccepted`], which is
+    /// answered with `204 No Content` and never reaches the handler. So are requests whose
+    /// condition isn't an object of strings, without calling this.
+    ///
+    /// Twitch retries deliveries that aren't answered with a `2xx` status and revokes the
+    /// subscription once too many failed, so rejected events are acknowledged and dropped.
+    /// Verifications are dropped the same way, without their challenge, so subscriptions
+    /// with a rejected condition are never confirmed. If [`Config::convert_error`] answers
+    /// with another status, expect the subscription to be revoked.
+    ///
+    /// Use it to give each broadcaster its own config on a shared endpoint.
+    /// The body is consumed by then, so a rejected request doesn't fall through
+    /// to another handler - to dispatch on the broadcaster in a single handler,
+    /// use [`Data::condition`] instead.
+    ///
+    /// ```
+    /// # use actix_web::HttpRequest;
+    /// # use actix_web_eventsub::{Condition, Config, VerifyDecodeError};
+    /// struct ChannelConfig;
+    ///
+    /// impl Config for ChannelConfig {
+    ///     type Error = VerifyDecodeError;
+    ///     type CheckEventIdFut = std::future::Ready<bool>;
+    ///
+    ///     fn get_secret(_req: &HttpRequest) -> Result<&[u8], VerifyDecodeError> {
+    ///         Ok(b"secretsecret")
+    ///     }
+    ///
+    ///     fn accept_condition(_req: &HttpRequest, condition: &Condition<'_>) -> bool {
+    ///         condition.broadcaster_user_id == Some("53946574")
+    ///     }
+    ///
+    ///     fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+    ///         std::future::ready(true)
+    ///     }
+    ///
+    ///     fn convert_error(error: VerifyDecodeError) -> Self::Error {
+    ///         error
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Defaults to accepting all conditions.
+    #[must_use]
+    fn accept_condition(req: &HttpRequest, condition: &Condition<'_>) -> bool {
+        let _ = (req, condition);
+        true
+    }
+
+    /// Called with the subscription of every request that was verified and decoded,
+    /// before the handler runs.
+    ///
+    /// This runs for verifications, notifications and revocations - after
+    /// [`Config::is_subscription_allowed`] and before [`Config::check_event_id`],
+    /// so retries are observed too. Use it to record metrics or the last time a
+    /// subscription was seen in a single place.
+    ///
+    /// Defaults to doing nothing.
+    fn on_verified(
+        req: &HttpRequest,
+        subscription: &EventSubSubscription,
+        message_type: MessageType,
+    ) {
+        let _ = (req, subscription, message_type);
+    }
+
+    /// Called with every revocation that was verified and decoded, before the handler runs.
+    ///
+    /// Use it to clean up after a revoked subscription (e.g. resubscribe or remove it
+    /// from the allow-list) in a single place. It runs right after [`Config::on_verified`],
+    /// so it's called again if Twitch retries the revocation.
+    ///
+    /// Defaults to doing nothing.
+    fn on_revocation(req: &HttpRequest, revocation: &Revocation) {
+        let _ = (req, revocation);
+    }
+
+    /// Respond to revocations with `204 No Content` in [`Data::handle`], ignoring
+    /// the response of `on_revocation`.
+    ///
+    /// The revocation handler still runs, but it can't make Twitch retry the revocation
+    /// by failing. Handle failures in [`Config::on_revocation`] or the handler instead.
+    ///
+    /// Defaults to `false` - the handler controls the response.
+    #[must_use]
+    fn auto_ack_revocations() -> bool {
+        false
+    }
+
+    /// The response to notifications that are answered without the handler.
+    ///
+    /// This is used by [`Data::spawn_handler`], [`AnyData::handle`](crate::AnyData::handle)
+    /// and [`BatchData::response`](crate::BatchData::response), for example to send a small
+    /// JSON acknowledgement to a monitoring proxy. Twitch retries notifications that aren't
+    /// answered with a `2xx` status, so responses outside of that range are replaced with
+    /// `204 No Content`. Verifications are still answered with their challenge.
+    ///
+    /// Defaults to `204 No Content`.
+    #[must_use]
+    fn notification_response() -> HttpResponse {
+        HttpResponse::NoContent().finish()
+    }
+
+    /// Called when a handler started by [`Data::spawn_handler`] panicked.
+    ///
+    /// The response was already sent at that point, so this is the place to log or count
+    /// the failure with the subscription type and message id of `meta`. The panic message
+    /// itself is reported by the panic hook.
+    ///
+    /// Defaults to doing nothing.
+    fn on_handler_panic(meta: &EventMeta) {
+        let _ = meta;
+    }
+
+    /// Maximum size of the body in bytes.
+    ///
+    /// Larger bodies are rejected with [`VerifyDecodeError::RequestTooLarge`]. The body is
+    /// read by the extractors themselves, so actix-web's `PayloadConfig` doesn't apply to it -
+    /// override this instead.
+    ///
+    /// Defaults to [`MAX_BODY_SIZE`](eventsub_common::body::MAX_BODY_SIZE) (10MB).
+    #[must_use]
+    fn max_body_size() -> usize {
+        body::MAX_BODY_SIZE
+    }
+
+    /// Maximum time to wait for the full payload to arrive.
+    ///
+    /// If the body isn't received in time, the request is rejected with
+    /// [`VerifyDecodeError::ReadTimeout`]. This protects workers from clients
+    /// trickling in the body. Return [`None`] to disable the timeout.
+    ///
+    /// Defaults to 30 seconds.
+    #[must_use]
+    fn read_timeout() -> Option<Duration> {
+        Some(Duration::from_secs(30))
+    }
+
+    /// Maximum number of retries after which a message is still handled.
+    ///
+    /// Messages Twitch already retried more often (`Twitch-Eventsub-Message-Retry`) likely
+    /// fail again. They're rejected with [`VerifyDecodeError::RetryLimitExceeded`] once the
+    /// signature is checked, which responds with `204 No Content`, so Twitch stops retrying.
+    /// Verifications are never skipped, as the subscription wouldn't be confirmed otherwise.
+    ///
+    /// Defaults to [`None`] (no limit).
+    #[must_use]
+    fn max_retries() -> Option<u32> {
+        None
+    }
+
+    /// Reject requests whose `Content-Type` isn't `application/json` with
+    /// [`VerifyDecodeError::BadContentType`].
+    ///
+    /// Twitch always sends JSON, so this catches misrouted requests before the body is hashed.
+    /// Disabled by default, as some proxies change the content type.
+    #[must_use]
+    fn require_json_content_type() -> bool {
+        false
+    }
+
+    /// Maximum nesting depth of arrays and objects in the payload.
+    ///
+    /// The depth is counted while the payload is deserialized, and payloads nested deeper
+    /// than this are rejected with [`VerifyDecodeError::Serde`]. `serde_json` always rejects
+    /// payloads nested deeper than 128 levels, so larger values have no effect.
+    ///
+    /// Defaults to [`None`] (only `serde_json`'s limit applies).
+    #[must_use]
+    fn max_json_depth() -> Option<usize> {
+        None
+    }
+
     /// Convert the [`VerifyDecodeError`] into a custom error.
     ///
     /// If you want to return a custom error (for example an error wrapped in JSON),
     /// then you should construct it here. Otherwise, return the given error.
+    /// To only change the status codes, return a [`ConfiguredError`].
     fn convert_error(error: VerifyDecodeError) -> Self::Error;
+
+    /// Convert a [`VerifyDecodeError`] that happened after the headers were read.
+    ///
+    /// The [`ErrorContext`] carries the subscription type and message id of the request,
+    /// override this to include them in logs:
+    ///
+    /// ```
+    /// # use actix_web::HttpRequest;
+    /// # use actix_web_eventsub::{Config, ErrorContext, VerifyDecodeError};
+    /// struct EventsubConfig;
+    ///
+    /// impl Config for EventsubConfig {
+    ///     type Error = VerifyDecodeError;
+    ///     type CheckEventIdFut = std::future::Ready<bool>;
+    ///
+    ///     fn get_secret(_req: &HttpRequest) -> Result<&[u8], VerifyDecodeError> {
+    ///         Ok(b"secretsecret")
+    ///     }
+    ///
+    ///     fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+    ///         std::future::ready(true)
+    ///     }
+    ///
+    ///     fn convert_error(error: VerifyDecodeError) -> Self::Error {
+    ///         error
+    ///     }
+    ///
+    ///     fn convert_error_with_context(error: ErrorContext<VerifyDecodeError>) -> Self::Error {
+    ///         // e.g. "The provided signature wasn't expected (channel.follow id=abc)"
+    ///         eprintln!("Rejected eventsub request: {error}");
+    ///         Self::convert_error(error.into_inner())
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Defaults to [`Config::convert_error`], dropping the context.
+    fn convert_error_with_context(error: ErrorContext<VerifyDecodeError>) -> Self::Error {
+        Self::convert_error(error.into_inner())
+    }
+}
+
+/// A [`VerifyDecodeError`] responding with a custom status.
+///
+/// Every rejection goes through [`Config::convert_error`], so returning this from there
+/// changes the status of all of them. The body is the same JSON as the one of
+/// [`VerifyDecodeError`].
+///
+/// ```
+/// # use actix_web::{http::StatusCode, HttpRequest, ResponseError};
+/// # use actix_web_eventsub::{Config, ConfiguredError, VerifyDecodeError};
+/// struct EventsubConfig;
+///
+/// impl Config for EventsubConfig {
+///     type Error = ConfiguredError;
+///     type CheckEventIdFut = std::future::Ready<bool>;
+///
+///     fn get_secret(_req: &HttpRequest) -> Result<&[u8], Self::Error> {
+///         Ok(b"secretsecret")
+///     }
+///
+///     fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+///         std::future::ready(true)
+///     }
+///
+///     fn convert_error(error: VerifyDecodeError) -> Self::Error {
+///         let status = match error {
+///             VerifyDecodeError::SignatureMismatch => StatusCode::UNAUTHORIZED,
+///             VerifyDecodeError::Serde(_) => StatusCode::UNPROCESSABLE_ENTITY,
+///             _ => error.status_code(),
+///         };
+///         ConfiguredError::new(error, status)
+///     }
+/// }
+/// ```
+pub struct ConfiguredError {
+    error: VerifyDecodeError,
+    status: StatusCode,
+}
+
+impl ConfiguredError {
+    /// Wrap the error, responding with `status`.
+    ///
+    /// Success statuses respond without a body.
+    #[must_use]
+    pub fn new(error: VerifyDecodeError, status: StatusCode) -> Self {
+        Self { error, status }
+    }
+
+    /// The wrapped error.
+    #[must_use]
+    pub fn error(&self) -> &VerifyDecodeError {
+        &self.error
+    }
+
+    /// Unwrap the error.
+    #[must_use]
+    pub fn into_inner(self) -> VerifyDecodeError {
+        self.error
+    }
+}
+
+impl From<VerifyDecodeError> for ConfiguredError {
+    /// Wrap the error, keeping its default status.
+    fn from(error: VerifyDecodeError) -> Self {
+        let status = error.status_code();
+        Self::new(error, status)
+    }
+}
+
+impl fmt::Debug for ConfiguredError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ConfiguredError")
+            .field(&self.error)
+            .field(&self.status)
+            .finish()
+    }
+}
+
+impl fmt::Display for ConfiguredError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl std::error::Error for ConfiguredError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
+impl ResponseError for ConfiguredError {
+    fn status_code(&self) -> StatusCode {
+        self.status
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        error_response(self.status_code(), &self.error)
+    }
+}
+
+impl ResponseError for VerifyDecodeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            VerifyDecodeError::Headers(_)
+            | VerifyDecodeError::SignatureMismatch
+            | VerifyDecodeError::RequestTooLarge
+            | VerifyDecodeError::PayloadError(_)
+            | VerifyDecodeError::Serde(_)
+            | VerifyDecodeError::VersionMismatch(_)
+            | VerifyDecodeError::IdNotUtf8
+            | VerifyDecodeError::WontHandleId => StatusCode::BAD_REQUEST,
+            VerifyDecodeError::RetryLimitExceeded | VerifyDecodeError::ConditionNotAccepted => {
+                StatusCode::NO_CONTENT
+            }
+            VerifyDecodeError::SubscriptionNotAllowed => StatusCode::FORBIDDEN,
+            VerifyDecodeError::ReadTimeout => StatusCode::REQUEST_TIMEOUT,
+            VerifyDecodeError::BadContentType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            VerifyDecodeError::PayloadAlreadyConsumed
+            | VerifyDecodeError::NoHmacKey
+            | VerifyDecodeError::HmacInit(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        error_response(self.status_code(), self)
+    }
+}
+
+/// Respond with the error as JSON, unless the status is a success.
+fn error_response(status: StatusCode, error: &VerifyDecodeError) -> HttpResponse {
+    if status.is_success() {
+        HttpResponse::new(status)
+    } else {
+        HttpResponse::build(status).json(serde_json::json!({ "error": error.to_string() }))
+    }
 }
 
 impl<P, T> FromRequest for Data<P, T>
@@ -167,55 +954,71 @@ where
     type Future = Either<Ready<Result<Self, Self::Error>>, VerifyDecodeFut<P, T>>;
 
     fn from_request(req: &HttpRequest, payload: &mut dev::Payload) -> Self::Future {
-        let parsed = match headers::read_eventsub_headers::<_, P>(req.headers())
-            .map_err(VerifyDecodeError::Headers)
-            .map_err(T::convert_error)
-        {
-            Ok(h) => h,
-            Err(e) => return Either::Left(ready(Err(e))),
-        };
-        match init_mac::<T>(req, parsed.id_bytes, parsed.timestamp_bytes) {
-            Ok(mac) => Either::Right(VerifyDecodeFut::DecodingResponse {
+        match verify::start_for::<P, T>(req, payload) {
+            Ok(Pending { meta, verifier }) => Either::Right(VerifyDecodeFut::DecodingResponse {
                 payload: dev::Payload::take(payload),
-                mac,
+                verifier: Some(verifier),
                 bytes: BytesMut::new(),
-                headers: parsed.payload,
+                meta,
                 req: req.clone(),
+                timeout: T::read_timeout().map(sleep),
             }),
             Err(e) => Either::Left(ready(Err(e))),
         }
     }
 }
 
-fn init_mac<T: Config>(
-    req: &HttpRequest,
-    id_bytes: &[u8],
-    timestamp_bytes: &[u8],
-) -> Result<HmacSha256, T::Error> {
-    let mut mac = HmacSha256::new_from_slice(T::get_secret(req)?)
-        .map_err(VerifyDecodeError::HmacInit)
-        .map_err(T::convert_error)?;
-    mac.update(id_bytes);
-    mac.update(timestamp_bytes);
-
-    Ok(mac)
+/// The settings of a [`Config`] for answering deliveries, read when the request is extracted.
+///
+/// The extractors keep these instead of bounds on the config, like in `axum-eventsub`,
+/// where the state of the config isn't known once the handler responds.
+#[derive(Clone, Copy)]
+pub(crate) struct Responses {
+    /// [`Config::auto_ack_revocations`]
+    pub(crate) auto_ack_revocations: bool,
+    /// [`Config::on_handler_panic`]
+    pub(crate) on_handler_panic: fn(&EventMeta),
+    notification_response: fn() -> HttpResponse,
+}
+
+impl Responses {
+    pub(crate) fn of<T: Config>() -> Self {
+        Self {
+            auto_ack_revocations: T::auto_ack_revocations(),
+            on_handler_panic: T::on_handler_panic,
+            notification_response: T::notification_response,
+        }
+    }
+
+    /// [`Config::notification_response`], if it's in the `2xx` range.
+    pub(crate) fn notification(&self) -> HttpResponse {
+        let response = (self.notification_response)();
+        if response.status().is_success() {
+            response
+        } else {
+            HttpResponse::NoContent().finish()
+        }
+    }
 }
 
 /// A future for verifying an EventSub payload.
 #[pin_project(project = VerifyDecodeProj)]
-pub enum VerifyDecodeFut<P, T: Config> {
+pub enum VerifyDecodeFut<P: EventSubscription, T: Config> {
     /// Step 1: decoding/reading the response
     DecodingResponse {
         /// Payload(-stream)
         payload: dev::Payload,
-        /// Hmac state
-        mac: HmacSha256,
+        /// Verifier of the signature, taken once the body is complete
+        verifier: Option<StreamingVerifier>,
         /// Decoded data
         bytes: BytesMut,
-        /// Initial header information
-        headers: PayloadHeaders,
+        /// Metadata of the message
+        meta: EventMeta,
         /// Reference to HttpRequest (an Rc internally, but we drop it after decoding)
         req: HttpRequest,
+        /// Timer for [`Config::read_timeout`]
+        #[pin]
+        timeout: Option<Sleep>,
     },
     /// Step 2: checking the id of this payload
     CheckingId {
@@ -227,7 +1030,32 @@ pub enum VerifyDecodeFut<P, T: Config> {
     },
 }
 
-const EMPTY_KEY: [u8; 64] = [0u8; 64];
+/// Verify and decode the complete body, then start checking the event id.
+fn decode_verified<P: EventSubscription, T: Config>(
+    verifier: StreamingVerifier,
+    mut body: BytesMut,
+    meta: &EventMeta,
+    req: &HttpRequest,
+) -> Result<VerifyDecodeFut<P, T>, T::Error> {
+    verify::finish::<T>(req, verifier, meta)?;
+    let payload =
+        decode::decode_payload_in_place::<P>(meta.message_type, &mut body, T::max_json_depth())
+            .map_err(|e| verify::in_context::<T>(meta, VerifyDecodeError::Serde(e)))?;
+    verify::accept::<T>(req, meta, payload.subscription())?;
+    let data = Data {
+        payload,
+        meta: meta.clone(),
+        body: body.freeze(),
+        responses: Responses::of::<T>(),
+        #[cfg(feature = "opentelemetry")]
+        context: eventsub_common::otel::delivery_context(meta),
+        _config: PhantomData,
+    };
+    Ok(VerifyDecodeFut::CheckingId {
+        payload: Some(data),
+        inner: T::check_event_id(req, &meta.message_id),
+    })
+}
 
 impl<P, T> Future for VerifyDecodeFut<P, T>
 where
@@ -242,79 +1070,52 @@ where
                 VerifyDecodeProj::DecodingResponse {
                     payload,
                     bytes,
-                    mac,
-                    headers,
+                    verifier,
+                    meta,
                     req,
-                } => loop {
-                    match Pin::new(&mut payload.next()).poll(cx) {
-                        Poll::Ready(Some(Ok(ref chunk))) => {
-                            if bytes.len() >= 10_000_000 {
-                                break 'outer Poll::Ready(Err(T::convert_error(
-                                    VerifyDecodeError::RequestTooLarge,
-                                )));
+                    mut timeout,
+                } => {
+                    let read = body::poll_read_body(
+                        Pin::new(&mut PayloadBody(payload)),
+                        cx,
+                        bytes,
+                        T::max_body_size(),
+                    );
+                    match read {
+                        Poll::Ready(Ok(())) => {
+                            let mut verifier = verifier
+                                .take()
+                                .expect("VerifyDecodeFut polled after completion");
+                            verifier.update(bytes);
+                            let body = std::mem::take(bytes);
+                            match decode_verified(verifier, body, meta, req) {
+                                Ok(checking) => self.set(checking),
+                                Err(e) => break 'outer Poll::Ready(Err(e)),
                             }
-                            bytes.extend_from_slice(chunk);
-                            mac.update(chunk);
                         }
-                        Poll::Ready(Some(Err(e))) => {
-                            break 'outer Poll::Ready(Err(T::convert_error(
-                                VerifyDecodeError::PayloadError(e),
-                            )))
+                        Poll::Ready(Err(e)) => {
+                            break 'outer Poll::Ready(Err(verify::in_context::<T>(meta, e.into())))
                         }
-                        Poll::Ready(None) => {
-                            let signature = std::mem::replace(
-                                mac,
-                                HmacSha256::new(GenericArray::from_slice(&EMPTY_KEY)),
-                            );
-
-                            if signature.verify_slice(&headers.signature).is_err() {
-                                break 'outer Poll::Ready(Err(T::convert_error(
-                                    VerifyDecodeError::SignatureMismatch,
-                                )));
-                            }
-                            let payload_result =
-                                match headers.message_type {
-                                    MessageType::Verification => serde_json::from_slice(bytes)
-                                        .map(EventsubPayload::Verification),
-                                    MessageType::Revocation => serde_json::from_slice(bytes)
-                                        .map(EventsubPayload::Revocation),
-                                    MessageType::Notification => serde_json::from_slice(bytes)
-                                        .map(EventsubPayload::Notification),
-                                }
-                                .map(|payload| Data {
-                                    payload,
-                                    _config: PhantomData,
-                                })
-                                .map_err(VerifyDecodeError::Serde);
-                            let id_header = req
-                                .headers()
-                                .get_message_id()
-                                .unwrap()
-                                .to_str()
-                                .map_err(|_| VerifyDecodeError::IdNotUtf8);
-                            match (payload_result, id_header) {
-                                (Ok(payload), Ok(id)) => {
-                                    let inner = T::check_event_id(req, id);
-                                    self.set(VerifyDecodeFut::CheckingId {
-                                        payload: Some(payload),
-                                        inner,
-                                    });
-                                    continue 'outer;
-                                }
-                                (Err(e), _) | (Ok(_), Err(e)) => {
-                                    break 'outer Poll::Ready(Err(T::convert_error(e)))
+                        Poll::Pending => {
+                            if let Some(timeout) = timeout.as_mut().as_pin_mut() {
+                                if timeout.poll(cx).is_ready() {
+                                    break 'outer Poll::Ready(Err(verify::in_context::<T>(
+                                        meta,
+                                        VerifyDecodeError::ReadTimeout,
+                                    )));
                                 }
                             }
+                            break 'outer Poll::Pending;
                         }
-                        Poll::Pending => break 'outer Poll::Pending,
                     }
-                },
+                }
                 VerifyDecodeProj::CheckingId { inner, payload } => {
                     break 'outer match inner.poll(cx) {
                         Poll::Ready(true) => Poll::Ready(Ok(payload.take().unwrap())),
-                        Poll::Ready(false) => {
-                            Poll::Ready(Err(T::convert_error(VerifyDecodeError::WontHandleId)))
-                        }
+                        Poll::Ready(false) => Poll::Ready(Err(verify::in_context::<T>(
+                            payload.as_ref().unwrap().meta(),
+                            VerifyDecodeError::WontHandleId,
+                        ))),
                         Poll::Pending => Poll::Pending,
                     }
                 }
@@ -1,3 +1,12 @@
 //! This module contains useful extractors for `EventSub`.
 
+pub mod any;
+pub mod batch;
+mod body;
+#[cfg(feature = "debug-report")]
+pub mod debug;
 pub mod eventsub;
+pub mod raw;
+pub mod state;
+pub mod stream;
+mod verify;
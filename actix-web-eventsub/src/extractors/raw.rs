@@ -0,0 +1,167 @@
+//! This module contains the `EventSub` extractor [`crate::RawData`] deferring the decoding.
+
+use crate::extractors::{
+    eventsub::{Config, VerifyDecodeError},
+    verify,
+};
+use actix_web::{dev, FromRequest, HttpRequest};
+use bytes::Bytes;
+use eventsub_common::{
+    decode,
+    headers::EventMeta,
+    types::{EventSubSubscription, EventSubscription},
+    EventsubPayload,
+};
+use serde::Deserialize;
+use std::{future::Future, marker::PhantomData, pin::Pin};
+
+/// Extractor for a verified eventsub event that isn't decoded yet.
+///
+/// This checks the request like [`Data`](crate::Data), but only reads the subscription at the
+/// start of the body, which the hooks of [`Config`] need. The event is deserialized by
+/// [`payload`](Self::payload) as any type borrowing from the body, such as the mirrors in
+/// `borrowed`, so the body is parsed once and strings aren't copied.
+///
+/// If only the [subscription id](Self::subscription_id) is needed, e.g. for deduplication
+/// or logging, the event is never read at all.
+///
+/// Verifications still have to be answered with their challenge.
+///
+/// ```
+/// # use actix_web::{HttpRequest, HttpResponse, Responder};
+/// # use actix_web_eventsub::{EventsubPayload, RawData, VerifyDecodeError, types::channel::ChannelPointsCustomRewardRedemptionAddV1};
+/// # use std::borrow::Cow;
+/// # struct EventsubConfig;
+/// #
+/// # impl actix_web_eventsub::Config for EventsubConfig {
+/// #     type Error = VerifyDecodeError;
+/// #     type CheckEventIdFut = std::future::Ready<bool>;
+/// #
+/// #     fn get_secret(_req: &HttpRequest) -> Result<&[u8], VerifyDecodeError> {
+/// #         Ok(b"secretsecret")
+/// #     }
+/// #
+/// #     fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+/// #         std::future::ready(true)
+/// #     }
+/// #
+/// #     fn convert_error(error: VerifyDecodeError) -> Self::Error {
+/// #         error
+/// #     }
+/// # }
+/// #[derive(serde::Deserialize)]
+/// struct Redemption<'a> {
+///     #[serde(borrow)]
+///     user_login: Cow<'a, str>,
+/// }
+///
+/// async fn event_handler(
+///     event: RawData<ChannelPointsCustomRewardRedemptionAddV1, EventsubConfig>,
+/// ) -> impl Responder {
+///     match event.payload::<Redemption<'_>>() {
+///         Ok(EventsubPayload::Verification(verification)) => {
+///             HttpResponse::Ok().body(verification.challenge)
+///         }
+///         Ok(EventsubPayload::Notification(notification)) => {
+///             println!("Redeemed by {}", notification.event.user_login);
+///             HttpResponse::NoContent().finish()
+///         }
+///         Ok(EventsubPayload::Revocation(_)) => HttpResponse::NoContent().finish(),
+///         Err(e) => HttpResponse::BadRequest().body(e.to_string()),
+///     }
+/// }
+/// # fn main() {}
+/// ```
+pub struct RawData<P, T> {
+    subscription: EventSubSubscription,
+    meta: EventMeta,
+    body: Bytes,
+    #[cfg(feature = "opentelemetry")]
+    context: opentelemetry::Context,
+    _config: PhantomData<(P, T)>,
+}
+
+impl<P, T> RawData<P, T> {
+    /// The verified body of the request.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Metadata of the message, such as its id and timestamp.
+    pub fn meta(&self) -> &EventMeta {
+        &self.meta
+    }
+
+    /// The subscription this message was sent for.
+    pub fn subscription(&self) -> &EventSubSubscription {
+        &self.subscription
+    }
+
+    /// The id of the subscription this message was sent for.
+    pub fn subscription_id(&self) -> &str {
+        self.subscription.id.as_str()
+    }
+
+    /// The OpenTelemetry context of the delivery, with its span as the active span.
+    ///
+    /// See [`Data::otel_context`](crate::Data::otel_context).
+    #[cfg(feature = "opentelemetry")]
+    pub fn otel_context(&self) -> &opentelemetry::Context {
+        &self.context
+    }
+
+    /// Make the OpenTelemetry context of the delivery current while `future` is polled.
+    ///
+    /// See [`Data::in_otel_context`](crate::Data::in_otel_context).
+    #[cfg(feature = "opentelemetry")]
+    pub fn in_otel_context<F: Future>(&self, future: F) -> opentelemetry::trace::WithContext<F> {
+        opentelemetry::trace::FutureExt::with_context(future, self.context.clone())
+    }
+}
+
+impl<P, T: Config> RawData<P, T> {
+    /// Deserialize the payload, borrowing the event from the [body](Self::body).
+    ///
+    /// The nesting is limited by [`Config::max_json_depth`] like in [`Data`](crate::Data).
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the body isn't a valid payload with an event of type `E`.
+    pub fn payload<'a, E: Deserialize<'a>>(
+        &'a self,
+    ) -> Result<EventsubPayload<E>, serde_json::Error> {
+        decode::decode_borrowed(self.meta.message_type, &self.body, T::max_json_depth())
+    }
+}
+
+impl<P, T> FromRequest for RawData<P, T>
+where
+    P: EventSubscription + 'static,
+    T: Config + 'static,
+    T::Error: 'static,
+{
+    type Error = T::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut dev::Payload) -> Self::Future {
+        let pending = verify::start_for::<P, T>(req, payload);
+        let req = req.clone();
+        let payload = payload.take();
+        Box::pin(async move {
+            let (meta, body) = verify::read_verified::<T>(&req, payload, pending?).await?;
+            let subscription = decode::leading_subscription(&body)
+                .map_err(|e| verify::in_context::<T>(&meta, VerifyDecodeError::Serde(e)))?;
+            verify::accept::<T>(&req, &meta, &subscription)?;
+            verify::check_id::<T>(&req, &meta).await?;
+
+            Ok(Self {
+                subscription,
+                #[cfg(feature = "opentelemetry")]
+                context: eventsub_common::otel::delivery_context(&meta),
+                meta,
+                body: body.freeze(),
+                _config: PhantomData,
+            })
+        })
+    }
+}
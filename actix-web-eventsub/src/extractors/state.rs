@@ -0,0 +1,313 @@
+//! This module contains [`StateConfig`], a [`Config`] generic over the application state.
+
+use crate::{
+    extractors::eventsub::{Config, VerifyDecodeError},
+    types::EventSubSubscription,
+};
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use eventsub_common::{
+    error::ErrorContext, headers::EventMeta, Condition, MessageType, Revocation,
+};
+use futures_util::future::Either;
+use std::{
+    future::{ready, Future, Ready},
+    marker::PhantomData,
+    time::Duration,
+};
+
+/// A [`Config`] that only has the defaults, for [`StateConfig`] to delegate to.
+struct Defaults;
+
+impl Config for Defaults {
+    type Error = VerifyDecodeError;
+    type CheckEventIdFut = Ready<bool>;
+
+    fn get_secret(_req: &HttpRequest) -> Result<&[u8], Self::Error> {
+        Err(VerifyDecodeError::NoHmacKey)
+    }
+
+    fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+        ready(false)
+    }
+
+    fn convert_error(error: VerifyDecodeError) -> Self::Error {
+        error
+    }
+}
+
+/// Configuration for verifying and decoding eventsub payloads with the state `S`.
+///
+/// Like in the `Config<S>` of `axum-eventsub`, the methods get the state instead of the
+/// request. It's taken from the `app_data` of the request (as [`web::Data<S>`]). Use it
+/// through [`WithState`], which implements [`Config`]. The traits still differ - the error
+/// is [`StateConfig::Error`] instead of `Rejection`, and ids are checked with
+/// [`StateConfig::check_event_id`], which `axum-eventsub` doesn't have - but the logic behind
+/// the methods can be shared between both frameworks and tested without building requests.
+///
+/// The hooks that get neither the request nor the state (e.g. [`Config::read_timeout`])
+/// default to the ones of [`Config`].
+///
+/// If the state is missing, the request is rejected with [`VerifyDecodeError::NoHmacKey`].
+///
+/// ```
+/// # use actix_web::{post, web, App, Responder};
+/// # use actix_web_eventsub::{Data, StateConfig, VerifyDecodeError, WithState, types::channel::ChannelPointsCustomRewardRedemptionAddV1};
+/// struct AppState {
+///     secret: Vec<u8>,
+/// }
+///
+/// struct EventsubConfig;
+///
+/// impl StateConfig<AppState> for EventsubConfig {
+///     type Error = VerifyDecodeError;
+///     type CheckEventIdFut = std::future::Ready<bool>;
+///
+///     fn get_secret(state: &AppState) -> &[u8] {
+///         &state.secret
+///     }
+///
+///     fn check_event_id(_state: &AppState, _id: &str) -> Self::CheckEventIdFut {
+///         std::future::ready(true)
+///     }
+///
+///     fn convert_error(error: VerifyDecodeError) -> Self::Error {
+///         error
+///     }
+/// }
+///
+/// #[post("/eventsub")]
+/// async fn event_handler(
+///     event: Data<ChannelPointsCustomRewardRedemptionAddV1, WithState<EventsubConfig, AppState>>,
+/// ) -> impl Responder {
+///     event.handle(|_| "", |_| "")
+/// }
+///
+/// # fn main() {
+/// let app = App::new()
+///     .app_data(web::Data::new(AppState {
+///         secret: b"secretsecret".to_vec(),
+///     }))
+///     .service(event_handler);
+/// # }
+/// ```
+pub trait StateConfig<S> {
+    /// Preferred error type (see [`StateConfig::convert_error`]).
+    ///
+    /// If you don't care about the error, set this to [`VerifyDecodeError`].
+    type Error: ResponseError;
+
+    /// [`Future`] returned from [`Self::check_event_id`]
+    type CheckEventIdFut: Future<Output = bool> + 'static;
+
+    /// Get the eventsub secret from the state.
+    fn get_secret(state: &S) -> &[u8];
+
+    /// Check if you've already seen this id.
+    ///
+    /// See [`Config::check_event_id`].
+    fn check_event_id(state: &S, id: &str) -> Self::CheckEventIdFut;
+
+    /// Check if events of the subscription with the id `subscription_id` should be accepted.
+    ///
+    /// See [`Config::is_subscription_allowed`]. Defaults to accepting all subscriptions.
+    #[must_use]
+    fn is_subscription_allowed(state: &S, subscription_id: &str) -> bool {
+        let _ = (state, subscription_id);
+        true
+    }
+
+    /// Check if events with the subscription condition `condition` should be accepted.
+    ///
+    /// See [`Config::accept_condition`]. Defaults to accepting all conditions.
+    #[must_use]
+    fn accept_condition(state: &S, condition: &Condition<'_>) -> bool {
+        let _ = (state, condition);
+        true
+    }
+
+    /// Called with the subscription of every request that was verified and decoded.
+    ///
+    /// See [`Config::on_verified`]. Defaults to doing nothing.
+    fn on_verified(state: &S, subscription: &EventSubSubscription, message_type: MessageType) {
+        let _ = (state, subscription, message_type);
+    }
+
+    /// Called with every revocation that was verified and decoded.
+    ///
+    /// See [`Config::on_revocation`]. Defaults to doing nothing.
+    fn on_revocation(state: &S, revocation: &Revocation) {
+        let _ = (state, revocation);
+    }
+
+    /// Respond to revocations with `204 No Content` in [`Data::handle`](crate::Data::handle).
+    ///
+    /// See [`Config::auto_ack_revocations`], which this defaults to.
+    #[must_use]
+    fn auto_ack_revocations() -> bool {
+        Defaults::auto_ack_revocations()
+    }
+
+    /// The response to notifications that are answered without the handler.
+    ///
+    /// See [`Config::notification_response`], which this defaults to.
+    #[must_use]
+    fn notification_response() -> HttpResponse {
+        Defaults::notification_response()
+    }
+
+    /// Called when a handler started by [`Data::spawn_handler`](crate::Data::spawn_handler) panicked.
+    ///
+    /// See [`Config::on_handler_panic`], which this defaults to.
+    fn on_handler_panic(meta: &EventMeta) {
+        Defaults::on_handler_panic(meta);
+    }
+
+    /// Maximum size of the body in bytes.
+    ///
+    /// See [`Config::max_body_size`], which this defaults to.
+    #[must_use]
+    fn max_body_size() -> usize {
+        Defaults::max_body_size()
+    }
+
+    /// Maximum time to wait for the full payload to arrive.
+    ///
+    /// See [`Config::read_timeout`], which this defaults to.
+    #[must_use]
+    fn read_timeout() -> Option<Duration> {
+        Defaults::read_timeout()
+    }
+
+    /// Maximum number of retries after which a message is still handled.
+    ///
+    /// See [`Config::max_retries`], which this defaults to.
+    #[must_use]
+    fn max_retries() -> Option<u32> {
+        Defaults::max_retries()
+    }
+
+    /// Reject requests whose `Content-Type` isn't `application/json`.
+    ///
+    /// See [`Config::require_json_content_type`], which this defaults to.
+    #[must_use]
+    fn require_json_content_type() -> bool {
+        Defaults::require_json_content_type()
+    }
+
+    /// Maximum nesting depth of arrays and objects in the payload.
+    ///
+    /// See [`Config::max_json_depth`], which this defaults to.
+    #[must_use]
+    fn max_json_depth() -> Option<usize> {
+        Defaults::max_json_depth()
+    }
+
+    /// Convert the [`VerifyDecodeError`] into a custom error.
+    ///
+    /// See [`Config::convert_error`].
+    fn convert_error(error: VerifyDecodeError) -> Self::Error;
+
+    /// Convert a [`VerifyDecodeError`] that happened after the headers were read.
+    ///
+    /// Defaults to [`StateConfig::convert_error`], dropping the context.
+    fn convert_error_with_context(error: ErrorContext<VerifyDecodeError>) -> Self::Error {
+        Self::convert_error(error.into_inner())
+    }
+}
+
+/// Adapter implementing [`Config`] for a [`StateConfig<S>`](StateConfig).
+///
+/// The state is taken from the `app_data` of the request as [`web::Data<S>`].
+pub struct WithState<C, S> {
+    _config: PhantomData<fn() -> (C, S)>,
+}
+
+fn state<S: 'static>(req: &HttpRequest) -> Option<&S> {
+    req.app_data::<web::Data<S>>().map(|data| &***data)
+}
+
+impl<C, S> Config for WithState<C, S>
+where
+    C: StateConfig<S>,
+    S: 'static,
+{
+    type Error = C::Error;
+    type CheckEventIdFut = Either<C::CheckEventIdFut, Ready<bool>>;
+
+    fn get_secret(req: &HttpRequest) -> Result<&[u8], Self::Error> {
+        state(req)
+            .map(C::get_secret)
+            .ok_or_else(|| C::convert_error(VerifyDecodeError::NoHmacKey))
+    }
+
+    fn check_event_id(req: &HttpRequest, id: &str) -> Self::CheckEventIdFut {
+        match state(req) {
+            Some(state) => Either::Left(C::check_event_id(state, id)),
+            None => Either::Right(ready(false)),
+        }
+    }
+
+    fn is_subscription_allowed(req: &HttpRequest, subscription_id: &str) -> bool {
+        state(req).is_some_and(|state| C::is_subscription_allowed(state, subscription_id))
+    }
+
+    fn accept_condition(req: &HttpRequest, condition: &Condition<'_>) -> bool {
+        state(req).is_some_and(|state| C::accept_condition(state, condition))
+    }
+
+    fn on_verified(
+        req: &HttpRequest,
+        subscription: &EventSubSubscription,
+        message_type: MessageType,
+    ) {
+        if let Some(state) = state(req) {
+            C::on_verified(state, subscription, message_type);
+        }
+    }
+
+    fn on_revocation(req: &HttpRequest, revocation: &Revocation) {
+        if let Some(state) = state(req) {
+            C::on_revocation(state, revocation);
+        }
+    }
+
+    fn auto_ack_revocations() -> bool {
+        C::auto_ack_revocations()
+    }
+
+    fn notification_response() -> HttpResponse {
+        C::notification_response()
+    }
+
+    fn on_handler_panic(meta: &EventMeta) {
+        C::on_handler_panic(meta);
+    }
+
+    fn max_body_size() -> usize {
+        C::max_body_size()
+    }
+
+    fn read_timeout() -> Option<Duration> {
+        C::read_timeout()
+    }
+
+    fn max_retries() -> Option<u32> {
+        C::max_retries()
+    }
+
+    fn require_json_content_type() -> bool {
+        C::require_json_content_type()
+    }
+
+    fn max_json_depth() -> Option<usize> {
+        C::max_json_depth()
+    }
+
+    fn convert_error(error: VerifyDecodeError) -> Self::Error {
+        C::convert_error(error)
+    }
+
+    fn convert_error_with_context(error: ErrorContext<VerifyDecodeError>) -> Self::Error {
+        C::convert_error_with_context(error)
+    }
+}
@@ -0,0 +1,218 @@
+//! This module contains the streaming `EventSub` extractor [`crate::VerifyingStream`].
+
+use crate::{
+    extractors::{
+        eventsub::{Config, VerifyDecodeError},
+        verify::{self, Pending},
+    },
+    MessageType,
+};
+use actix_web::{
+    dev,
+    error::PayloadError,
+    rt::time::{sleep, Sleep},
+    FromRequest, HttpRequest,
+};
+use bytes::Bytes;
+use eventsub_common::{decode, headers::EventMeta, verify::StreamingVerifier};
+use futures_util::Stream;
+use std::{
+    future::{ready, Future, Ready},
+    io,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// How much of the body is kept to read the subscription from.
+const SUBSCRIPTION_PREFIX: usize = 8 * 1024;
+
+/// Extractor streaming the body of an eventsub request while verifying it.
+///
+/// This is meant for proxies that forward requests without buffering them.
+/// The chunks are yielded as they arrive, so they're **not verified yet**.
+/// After the stream ended, call [`finish`](Self::finish) to check the signature,
+/// and only then commit to the forwarded body. Because the body isn't buffered,
+/// it can't be deserialized in this mode - use [`Data`](crate::Data) for that.
+///
+/// Requests for any subscription type are accepted. [`Config::get_key`] provides the
+/// key. Twitch sends the subscription before the event, so it's read from the first
+/// few kilobytes of the body: [`finish`](Self::finish) runs [`Config::is_subscription_allowed`],
+/// [`Config::accept_condition`], [`Config::on_verified`] and [`Config::on_revocation`] with it
+/// after the signature was checked, then asks [`Config::check_event_id`].
+///
+/// [`Config::read_timeout`] limits how long the whole stream may take, including the time
+/// spent forwarding the chunks. Once it passed, the stream yields an
+/// [`io::ErrorKind::TimedOut`] error and ends, and [`finish`](Self::finish) fails with
+/// [`VerifyDecodeError::ReadTimeout`].
+///
+/// There's no streaming extractor for axum.
+///
+/// ```
+/// # use actix_web::{HttpRequest, HttpResponse};
+/// # use actix_web_eventsub::{VerifyDecodeError, VerifyingStream};
+/// # use futures_util::StreamExt;
+/// # struct EventsubConfig;
+/// #
+/// # impl actix_web_eventsub::Config for EventsubConfig {
+/// #     type Error = VerifyDecodeError;
+/// #     type CheckEventIdFut = std::future::Ready<bool>;
+/// #
+/// #     fn get_secret(_req: &HttpRequest) -> Result<&[u8], VerifyDecodeError> {
+/// #         Ok(b"secretsecret")
+/// #     }
+/// #
+/// #     fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+/// #         std::future::ready(true)
+/// #     }
+/// #
+/// #     fn convert_error(error: VerifyDecodeError) -> Self::Error {
+/// #         error
+/// #     }
+/// # }
+/// # async fn forward(_chunk: &[u8]) {}
+/// # async fn commit() {}
+/// async fn proxy(
+///     mut stream: VerifyingStream<EventsubConfig>,
+/// ) -> Result<HttpResponse, VerifyDecodeError> {
+///     while let Some(chunk) = stream.next().await {
+///         forward(&chunk.map_err(VerifyDecodeError::PayloadError)?).await;
+///     }
+///     stream.finish().await?;
+///     commit().await;
+///     Ok(HttpResponse::NoContent().finish())
+/// }
+/// # fn main() {}
+/// ```
+pub struct VerifyingStream<T> {
+    payload: dev::Payload,
+    verifier: StreamingVerifier,
+    meta: EventMeta,
+    req: HttpRequest,
+    /// The start of the body, holding the subscription.
+    prefix: Vec<u8>,
+    read: usize,
+    done: bool,
+    /// Timer for [`Config::read_timeout`]
+    timeout: Option<Pin<Box<Sleep>>>,
+    /// Why the stream ended early, reported by [`VerifyingStream::finish`].
+    failure: Option<VerifyDecodeError>,
+    _config: PhantomData<fn() -> T>,
+}
+
+impl<T: Config> VerifyingStream<T> {
+    /// The type of the message (from the headers).
+    #[must_use]
+    pub fn message_type(&self) -> MessageType {
+        self.meta.message_type
+    }
+
+    /// Metadata of the message, including the actual subscription type and version.
+    #[must_use]
+    pub fn meta(&self) -> &EventMeta {
+        &self.meta
+    }
+
+    /// Check the signature after the stream ended, then run the hooks of [`Config`]
+    /// for the subscription of the message and [`Config::check_event_id`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`VerifyDecodeError::SignatureMismatch`] if the signature doesn't match,
+    /// [`VerifyDecodeError::ReadTimeout`] if the stream took longer than [`Config::read_timeout`],
+    /// [`VerifyDecodeError::RequestTooLarge`] if it was larger than [`Config::max_body_size`],
+    /// [`VerifyDecodeError::PayloadError`] if the stream failed or wasn't read until its end and
+    /// [`VerifyDecodeError::Serde`] if the body doesn't start with a subscription.
+    /// The subscription and the message id are rejected like in [`Data`](crate::Data).
+    pub async fn finish(self) -> Result<MessageType, T::Error> {
+        if let Some(failure) = self.failure {
+            return Err(verify::in_context::<T>(&self.meta, failure));
+        }
+        if !self.done {
+            return Err(verify::in_context::<T>(
+                &self.meta,
+                VerifyDecodeError::PayloadError(PayloadError::Incomplete(None)),
+            ));
+        }
+        verify::finish::<T>(&self.req, self.verifier, &self.meta)?;
+        let subscription = decode::leading_subscription(&self.prefix)
+            .map_err(|e| verify::in_context::<T>(&self.meta, VerifyDecodeError::Serde(e)))?;
+        verify::accept::<T>(&self.req, &self.meta, &subscription)?;
+        verify::check_id::<T>(&self.req, &self.meta).await?;
+        Ok(self.meta.message_type)
+    }
+}
+
+impl<T: Config> Stream for VerifyingStream<T> {
+    type Item = Result<Bytes, PayloadError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if this.done {
+            return Poll::Ready(None);
+        }
+        match Pin::new(&mut this.payload).poll_next(cx) {
+            Poll::Ready(Some(Ok(chunk))) => {
+                this.read += chunk.len();
+                if this.read > T::max_body_size() {
+                    this.failure = Some(VerifyDecodeError::RequestTooLarge);
+                    this.done = true;
+                    return Poll::Ready(Some(Err(PayloadError::Overflow)));
+                }
+                this.verifier.update(&chunk);
+                let keep = SUBSCRIPTION_PREFIX.saturating_sub(this.prefix.len());
+                this.prefix
+                    .extend_from_slice(&chunk[..keep.min(chunk.len())]);
+                Poll::Ready(Some(Ok(chunk)))
+            }
+            Poll::Ready(None) => {
+                this.done = true;
+                Poll::Ready(None)
+            }
+            Poll::Ready(Some(Err(e))) => {
+                this.failure = Some(VerifyDecodeError::PayloadError(PayloadError::Incomplete(
+                    None,
+                )));
+                this.done = true;
+                Poll::Ready(Some(Err(e)))
+            }
+            Poll::Pending => {
+                if let Some(timeout) = &mut this.timeout {
+                    if timeout.as_mut().poll(cx).is_ready() {
+                        this.failure = Some(VerifyDecodeError::ReadTimeout);
+                        this.done = true;
+                        return Poll::Ready(Some(Err(PayloadError::Io(
+                            io::ErrorKind::TimedOut.into(),
+                        ))));
+                    }
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T: Config> FromRequest for VerifyingStream<T>
+where
+    T::Error: 'static,
+{
+    type Error = T::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut dev::Payload) -> Self::Future {
+        ready(
+            verify::start_any::<T>(req, payload).map(|Pending { meta, verifier }| Self {
+                payload: payload.take(),
+                verifier,
+                meta,
+                req: req.clone(),
+                prefix: Vec::new(),
+                read: 0,
+                done: false,
+                timeout: T::read_timeout().map(|duration| Box::pin(sleep(duration))),
+                failure: None,
+                _config: PhantomData,
+            }),
+        )
+    }
+}
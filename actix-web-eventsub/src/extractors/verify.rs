@@ -0,0 +1,195 @@
+//! The verification steps shared by all extractors.
+//!
+//! Every extractor runs the same pipeline: [`start`] reads the headers and sets up the
+//! HMAC before the body is touched, [`finish`] checks the signature over the body and then
+//! the retries of the message, [`accept`] runs the hooks of [`Config`] for the subscription
+//! of the verified payload, and [`check_id`] asks [`Config::check_event_id`]. New checks
+//! go here, so they apply to every extractor.
+
+use crate::extractors::{
+    body,
+    eventsub::{Config, VerifyDecodeError},
+};
+use actix_web::{
+    dev,
+    http::header::{HeaderMap, CONTENT_LENGTH, TRANSFER_ENCODING},
+    HttpRequest,
+};
+use bytes::BytesMut;
+use eventsub_common::{
+    error::ErrorContext,
+    headers::{self, EventMeta, InvalidHeaders, ParsedHeaders},
+    secret::HmacKey,
+    types::{EventSubSubscription, EventSubscription},
+    verify::StreamingVerifier,
+    Condition, MessageType, Revocation,
+};
+
+/// A request whose headers were read, with the body still to be verified.
+pub(crate) struct Pending {
+    pub(crate) meta: EventMeta,
+    pub(crate) verifier: StreamingVerifier,
+}
+
+/// Start verifying a request for the subscription `P`.
+pub(crate) fn start_for<P: EventSubscription, T: Config>(
+    req: &HttpRequest,
+    payload: &dev::Payload,
+) -> Result<Pending, T::Error> {
+    start::<T, _>(
+        req,
+        payload,
+        headers::read_eventsub_headers::<_, P>,
+        T::get_key_for::<P>,
+    )
+}
+
+/// Start verifying a request for any subscription.
+pub(crate) fn start_any<T: Config>(
+    req: &HttpRequest,
+    payload: &dev::Payload,
+) -> Result<Pending, T::Error> {
+    start::<T, _>(
+        req,
+        payload,
+        headers::read_eventsub_headers_untyped,
+        T::get_key,
+    )
+}
+
+fn start<'r, T: Config, K: HmacKey>(
+    req: &'r HttpRequest,
+    payload: &dev::Payload,
+    read_headers: fn(&HeaderMap) -> Result<ParsedHeaders<'_>, InvalidHeaders>,
+    get_key: impl FnOnce(&'r HttpRequest) -> Result<K, T::Error>,
+) -> Result<Pending, T::Error> {
+    if is_consumed(req, payload) {
+        return Err(T::convert_error(VerifyDecodeError::PayloadAlreadyConsumed));
+    }
+    if T::require_json_content_type() && !headers::is_json_content_type(req.headers()) {
+        return Err(T::convert_error(VerifyDecodeError::BadContentType));
+    }
+    let parsed = read_headers(req.headers()).map_err(|e| T::convert_error(header_error(e)))?;
+    let meta = parsed.meta();
+    let verifier = StreamingVerifier::new(&get_key(req)?, &parsed)
+        .map_err(|e| in_context::<T>(&meta, VerifyDecodeError::HmacInit(e)))?;
+    Ok(Pending { meta, verifier })
+}
+
+/// Invalid message ids keep their own [`VerifyDecodeError::IdNotUtf8`].
+fn header_error(error: InvalidHeaders) -> VerifyDecodeError {
+    match error {
+        InvalidHeaders::IdNotUtf8 => VerifyDecodeError::IdNotUtf8,
+        error => VerifyDecodeError::Headers(error),
+    }
+}
+
+/// Read the complete body and [`finish`] verifying it.
+pub(crate) async fn read_verified<T: Config>(
+    req: &HttpRequest,
+    payload: dev::Payload,
+    Pending { meta, mut verifier }: Pending,
+) -> Result<(EventMeta, BytesMut), T::Error> {
+    let body = body::read(payload, T::max_body_size(), T::read_timeout())
+        .await
+        .map_err(|e| in_context::<T>(&meta, e))?;
+    verifier.update(&body);
+    finish::<T>(req, verifier, &meta)?;
+    Ok((meta, body))
+}
+
+/// Check the signature after the whole body was passed to the verifier,
+/// then [`Config::max_retries`].
+///
+/// The retry header isn't signed, so it's only trusted once the signature matched.
+/// Verifications are never skipped, otherwise the subscription wouldn't be confirmed.
+pub(crate) fn finish<T: Config>(
+    req: &HttpRequest,
+    verifier: StreamingVerifier,
+    meta: &EventMeta,
+) -> Result<(), T::Error> {
+    verifier
+        .finish()
+        .map_err(|_| in_context::<T>(meta, VerifyDecodeError::SignatureMismatch))?;
+    if meta.message_type != MessageType::Verification
+        && headers::retries_exceeded(req.headers(), T::max_retries())
+    {
+        return Err(in_context::<T>(meta, VerifyDecodeError::RetryLimitExceeded));
+    }
+    Ok(())
+}
+
+/// Check if the subscription of a verified payload is accepted
+/// ([`Config::is_subscription_allowed`] and [`Config::accept_condition`]).
+pub(crate) fn check<T: Config>(
+    req: &HttpRequest,
+    subscription: &EventSubSubscription,
+) -> Result<(), VerifyDecodeError> {
+    if !T::is_subscription_allowed(req, &subscription.id) {
+        return Err(VerifyDecodeError::SubscriptionNotAllowed);
+    }
+    // A condition that can't be read can't be accepted either.
+    if !Condition::of(subscription).is_ok_and(|condition| T::accept_condition(req, &condition)) {
+        return Err(VerifyDecodeError::ConditionNotAccepted);
+    }
+    Ok(())
+}
+
+/// Pass an accepted subscription to [`Config::on_verified`] and [`Config::on_revocation`].
+pub(crate) fn observe<T: Config>(
+    req: &HttpRequest,
+    subscription: &EventSubSubscription,
+    message_type: MessageType,
+) {
+    T::on_verified(req, subscription, message_type);
+    if message_type == MessageType::Revocation {
+        T::on_revocation(
+            req,
+            &Revocation {
+                subscription: subscription.clone(),
+            },
+        );
+    }
+}
+
+/// [`check`] and [`observe`] the subscription of a verified payload.
+pub(crate) fn accept<T: Config>(
+    req: &HttpRequest,
+    meta: &EventMeta,
+    subscription: &EventSubSubscription,
+) -> Result<(), T::Error> {
+    check::<T>(req, subscription).map_err(|e| in_context::<T>(meta, e))?;
+    observe::<T>(req, subscription, meta.message_type);
+    Ok(())
+}
+
+/// Ask [`Config::check_event_id`] if the message should be handled.
+pub(crate) async fn check_id<T: Config>(
+    req: &HttpRequest,
+    meta: &EventMeta,
+) -> Result<(), T::Error> {
+    if T::check_event_id(req, &meta.message_id).await {
+        Ok(())
+    } else {
+        Err(in_context::<T>(meta, VerifyDecodeError::WontHandleId))
+    }
+}
+
+/// Convert an error that happened after the headers were read.
+pub(crate) fn in_context<T: Config>(meta: &EventMeta, error: VerifyDecodeError) -> T::Error {
+    T::convert_error_with_context(ErrorContext::new(
+        error,
+        meta.subscription_type.clone(),
+        meta.message_id.as_str(),
+    ))
+}
+
+/// Check if the request has a body, but the payload was taken by another extractor.
+pub(crate) fn is_consumed(req: &HttpRequest, payload: &dev::Payload) -> bool {
+    matches!(payload, dev::Payload::None)
+        && (req.headers().contains_key(TRANSFER_ENCODING)
+            || req
+                .headers()
+                .get(CONTENT_LENGTH)
+                .is_some_and(|length| length != "0"))
+}
@@ -4,7 +4,7 @@
 //!
 //! ```no_run
 //! # use actix_web::{web, web::Data, App, HttpRequest, HttpResponse, HttpServer, Responder, post};
-//! # use actix_web_eventsub::{guards, Config, EventsubPayload, Verification, VerifyDecodeError, types::channel::ChannelPointsCustomRewardRedemptionAddV1};
+//! # use actix_web_eventsub::{guards, Config, VerifyDecodeError, types::channel::ChannelPointsCustomRewardRedemptionAddV1};
 //! # use std::io;
 //! struct EventsubConfig;
 //!
@@ -38,18 +38,16 @@
 //! async fn event_handler(
 //!     event: actix_web_eventsub::Data<ChannelPointsCustomRewardRedemptionAddV1, EventsubConfig>,
 //! ) -> impl Responder {
-//!     match event.payload {
-//!         EventsubPayload::Verification(Verification { challenge, .. }) => {
-//!             println!("Verification: {}", challenge);
-//!             HttpResponse::Ok()
-//!                 .content_type(mime::TEXT_PLAIN_UTF_8)
-//!                 .body(challenge)
-//!         }
-//!         x => {
-//!             println!("{:?}", x);
+//!     event.handle(
+//!         |notification| {
+//!             println!("{:?}", notification);
 //!             HttpResponse::NoContent().finish()
-//!         }
-//!     }
+//!         },
+//!         |revocation| {
+//!             println!("{:?}", revocation);
+//!             HttpResponse::NoContent().finish()
+//!         },
+//!     )
 //! }
 //!
 //! #[actix_web::main]
@@ -71,13 +69,27 @@
 #![warn(missing_docs)]
 #![warn(clippy::pedantic)]
 #![allow(clippy::module_name_repetitions)]
+#![allow(clippy::doc_markdown)]
 
 mod extractors;
 pub mod guards;
 
-pub use extractors::eventsub::*;
+pub use extractors::{any::*, batch::*, eventsub::*, raw::*, state::*, stream::*};
 pub mod types {
     //! Types for eventsub.
     pub use eventsub_common::types::*;
 }
-pub use eventsub_common::{EventsubPayload, Notification, Revocation, Verification};
+#[cfg(feature = "borrowed")]
+pub use eventsub_common::borrowed;
+#[cfg(feature = "zeroize")]
+pub use eventsub_common::secret::{secrecy, zeroize};
+#[cfg(feature = "memory-store")]
+pub use eventsub_common::store;
+pub use eventsub_common::{
+    error::ErrorContext,
+    redemption::RedemptionEvent,
+    secret::{HmacKey, PathScopedSecret},
+    subscription, Condition, EventsubPayload, MessageType, Notification, Revocation, Verification,
+};
+#[cfg(feature = "debug-report")]
+pub use extractors::debug::DebugReport;
@@ -0,0 +1,335 @@
+//! Rejected requests and how errors are reported.
+
+use std::{
+    future::{ready, Ready},
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::Poll,
+};
+
+use actix_web::{
+    body::to_bytes,
+    dev::Payload,
+    error::{InternalError, PayloadError},
+    http::{header::HeaderValue, StatusCode},
+    post, test, web, App, FromRequest, HttpRequest, HttpResponse, Responder, ResponseError,
+};
+use actix_web_eventsub::{
+    types::channel::ChannelPointsCustomRewardRedemptionAddV1, Config, ConfiguredError, Data,
+    ErrorContext, TryData, VerifyDecodeError, VerifyingStream,
+};
+
+use futures_util::{stream, StreamExt};
+
+use crate::util::{Settings, TestConfig};
+mod util;
+
+struct StatusConfig;
+
+impl Config for StatusConfig {
+    type Error = ConfiguredError;
+
+    type CheckEventIdFut = Ready<bool>;
+
+    fn get_secret(_: &HttpRequest) -> Result<&[u8], Self::Error> {
+        Ok(util::SECRET)
+    }
+
+    fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+        ready(true)
+    }
+
+    fn convert_error(error: VerifyDecodeError) -> Self::Error {
+        let status = match error {
+            VerifyDecodeError::SignatureMismatch => StatusCode::UNAUTHORIZED,
+            _ => error.status_code(),
+        };
+        ConfiguredError::new(error, status)
+    }
+}
+
+struct ContextConfig;
+
+impl Config for ContextConfig {
+    type Error = InternalError<String>;
+
+    type CheckEventIdFut = Ready<bool>;
+
+    fn get_secret(_: &HttpRequest) -> Result<&[u8], Self::Error> {
+        Ok(util::SECRET)
+    }
+
+    fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+        ready(true)
+    }
+
+    fn convert_error(error: VerifyDecodeError) -> Self::Error {
+        InternalError::new(error.to_string(), error.status_code())
+    }
+
+    fn convert_error_with_context(error: ErrorContext<VerifyDecodeError>) -> Self::Error {
+        InternalError::new(error.to_string(), error.kind().status_code())
+    }
+}
+
+struct JsonOnly;
+
+impl Settings for JsonOnly {
+    const JSON_ONLY: bool = true;
+}
+
+struct SmallBodies;
+
+impl Settings for SmallBodies {
+    const MAX_BODY_SIZE: usize = 512;
+}
+
+#[post("/eventsub")]
+async fn try_handler(
+    event: TryData<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+) -> impl Responder {
+    match event {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(VerifyDecodeError::SignatureMismatch) => HttpResponse::ImATeapot().body("mismatch"),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+    }
+}
+
+#[post("/eventsub")]
+async fn consuming_handler(
+    _body: web::Bytes,
+    event: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+) -> impl Responder {
+    event.handle(
+        |_| HttpResponse::NoContent().finish(),
+        |_| HttpResponse::NoContent().finish(),
+    )
+}
+
+#[actix_web::test]
+async fn custom_status() {
+    let app = test::init_service(
+        App::new().route("/eventsub", web::post().to(util::handle::<StatusConfig>)),
+    )
+    .await;
+    let fixture = util::fixture("notification");
+    let body = String::from_utf8(fixture.body().to_vec())
+        .unwrap()
+        .replace("Test Input", "Fake Input");
+
+    let res =
+        test::call_service(&app, util::request(&fixture).set_payload(body).to_request()).await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let fixture = util::stale_fixture("notification");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn error_in_handler() {
+    let app = test::init_service(App::new().service(try_handler)).await;
+    let fixture = util::fixture("notification");
+
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    let res =
+        test::call_service(&app, util::request(&fixture).set_payload("{}").to_request()).await;
+    assert_eq!(res.status(), StatusCode::IM_A_TEAPOT);
+    assert_eq!(test::read_body(res).await, "mismatch");
+}
+
+#[actix_web::test]
+async fn error_context() {
+    let app = test::init_service(
+        App::new().route("/eventsub", web::post().to(util::handle::<ContextConfig>)),
+    )
+    .await;
+    let fixture = util::fixture("notification");
+
+    let res =
+        test::call_service(&app, util::request(&fixture).set_payload("{}").to_request()).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        test::read_body(res).await,
+        "The provided signature wasn't expected \
+         (channel.channel_points_custom_reward_redemption.add id=a6e2dcd1-3e18-8d6c-7a0f-6fc8f7fd5a51)"
+    );
+}
+
+#[actix_web::test]
+async fn payload_already_consumed() {
+    let app = test::init_service(App::new().service(consuming_handler)).await;
+    let fixture = util::fixture("notification");
+
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body = test::read_body(res).await;
+    assert!(String::from_utf8_lossy(&body).contains("already consumed"));
+}
+
+#[actix_web::test]
+async fn content_type() {
+    let app = test::init_service(App::new().route(
+        "/eventsub",
+        web::post().to(util::handle::<TestConfig<JsonOnly>>),
+    ))
+    .await;
+    let fixture = util::fixture("notification");
+
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    let req = util::request(&fixture)
+        .insert_header(("Content-Type", "application/json; charset=utf-8"))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    let req = util::request(&fixture)
+        .insert_header(("Content-Type", "text/plain"))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    // the check is off by default
+    let app = test::init_service(
+        App::new().route("/eventsub", web::post().to(util::handle::<TestConfig>)),
+    )
+    .await;
+    let req = util::request(&fixture)
+        .insert_header(("Content-Type", "text/plain"))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+}
+
+#[actix_web::test]
+async fn max_body_size() {
+    let app = test::init_service(App::new().route(
+        "/eventsub",
+        web::post().to(util::handle::<TestConfig<SmallBodies>>),
+    ))
+    .await;
+
+    // the verification fits, the notification with its event doesn't
+    let fixture = util::fixture("verification");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let fixture = util::fixture("notification");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        test::read_body(res).await,
+        r#"{"error":"The request was too large"}"#
+    );
+
+    // the forwarded body is cut off at the same limit
+    let (req, _) = util::request(&fixture).to_http_parts();
+    let body = stream::iter([Ok(web::Bytes::copy_from_slice(fixture.body()))]);
+    let mut payload: Payload = Payload::Stream {
+        payload: Box::pin(body),
+    };
+    let mut stream = VerifyingStream::<TestConfig<SmallBodies>>::from_request(&req, &mut payload)
+        .await
+        .unwrap();
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(PayloadError::Overflow))
+    ));
+    assert!(stream.next().await.is_none());
+    assert!(matches!(
+        stream.finish().await,
+        Err(VerifyDecodeError::RequestTooLarge)
+    ));
+}
+
+#[actix_web::test]
+async fn id_not_utf8() {
+    let fixture = util::fixture("notification");
+    let (req, _) = util::request(&fixture)
+        .insert_header((
+            "Twitch-Eventsub-Message-Id",
+            HeaderValue::from_bytes(b"a6e2dcd1\xff").unwrap(),
+        ))
+        .to_http_parts();
+    // the headers are rejected before the body is touched
+    let polled = Arc::new(AtomicBool::new(false));
+    let body = stream::poll_fn({
+        let polled = polled.clone();
+        move |_| {
+            polled.store(true, Ordering::SeqCst);
+            Poll::Ready(Some(Ok(web::Bytes::from_static(b"{}"))))
+        }
+    });
+    let mut payload: Payload = Payload::Stream {
+        payload: Box::pin(body),
+    };
+
+    let error = Data::<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>::from_request(
+        &req,
+        &mut payload,
+    )
+    .await
+    .err()
+    .unwrap();
+    assert!(matches!(error, VerifyDecodeError::IdNotUtf8));
+    assert!(!polled.load(Ordering::SeqCst));
+
+    let res = error.error_response();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        to_bytes(res.into_body()).await.unwrap(),
+        r#"{"error":"The message id wasn't valid utf8"}"#
+    );
+}
+
+#[actix_web::test]
+async fn stalled_body() {
+    tokio::time::pause();
+    let fixture = util::fixture("notification");
+    let (req, _) = util::request(&fixture).to_http_parts();
+    // the first chunk arrives, the rest never does
+    let body = stream::iter([Ok(web::Bytes::copy_from_slice(&fixture.body()[..16]))])
+        .chain(stream::pending());
+    let mut payload: Payload = Payload::Stream {
+        payload: Box::pin(body),
+    };
+
+    let res = Data::<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>::from_request(
+        &req,
+        &mut payload,
+    )
+    .await;
+    assert!(matches!(res, Err(VerifyDecodeError::ReadTimeout)));
+}
+
+#[actix_web::test]
+async fn stalled_stream() {
+    tokio::time::pause();
+    let fixture = util::fixture("notification");
+    let (req, _) = util::request(&fixture).to_http_parts();
+    let body = stream::iter([Ok(web::Bytes::copy_from_slice(&fixture.body()[..16]))])
+        .chain(stream::pending());
+    let mut payload: Payload = Payload::Stream {
+        payload: Box::pin(body),
+    };
+
+    let mut stream = VerifyingStream::<TestConfig>::from_request(&req, &mut payload)
+        .await
+        .unwrap();
+    assert_eq!(stream.next().await.unwrap().unwrap().len(), 16);
+    // the stream ends with an error once the timeout passed
+    let error = stream.next().await.unwrap().unwrap_err();
+    assert!(matches!(error, PayloadError::Io(e) if e.kind() == io::ErrorKind::TimedOut));
+    assert!(stream.next().await.is_none());
+    assert!(matches!(
+        stream.finish().await,
+        Err(VerifyDecodeError::ReadTimeout)
+    ));
+}
@@ -0,0 +1,241 @@
+//! The extractors besides `Data`.
+
+use actix_web::{http::StatusCode, post, test, web, App, HttpResponse, Responder};
+use actix_web_eventsub::{
+    types::channel::ChannelPointsCustomRewardRedemptionAddV1, AnyData, BatchData, EventsubPayload,
+    MessageType, RawData, VerifyDecodeError, VerifyingStream,
+};
+use futures_util::StreamExt;
+use serde::Deserialize;
+use std::borrow::Cow;
+
+use crate::util::{Hooks, TestConfig};
+mod util;
+
+#[post("/eventsub")]
+async fn any_handler(event: AnyData<TestConfig>) -> impl Responder {
+    let meta = event.meta();
+    format!("{} {}", meta.subscription_type, meta.subscription_version)
+}
+
+#[post("/eventsub")]
+async fn batch_handler(
+    batch: BatchData<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+) -> impl Responder {
+    if batch.payloads().len() > 1 {
+        return HttpResponse::Ok().body(batch.payloads().len().to_string());
+    }
+    batch.response()
+}
+
+#[post("/eventsub")]
+async fn streaming_handler(
+    mut stream: VerifyingStream<TestConfig>,
+) -> Result<HttpResponse, VerifyDecodeError> {
+    let mut forwarded = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        forwarded.extend_from_slice(&chunk.map_err(VerifyDecodeError::PayloadError)?);
+    }
+    stream.finish().await?;
+    Ok(HttpResponse::Ok().body(forwarded))
+}
+
+#[derive(Deserialize)]
+struct Redemption<'a> {
+    #[serde(borrow)]
+    user_login: Cow<'a, str>,
+}
+
+#[post("/eventsub")]
+async fn raw_handler(
+    event: RawData<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+) -> impl Responder {
+    match event.payload::<Redemption<'_>>() {
+        Ok(EventsubPayload::Notification(notification)) => {
+            let borrowed = matches!(notification.event.user_login, Cow::Borrowed(_));
+            format!("{} {borrowed}", notification.event.user_login)
+        }
+        Ok(EventsubPayload::Verification(verification)) => verification.challenge,
+        Ok(EventsubPayload::Revocation(_)) => event.subscription_id().to_owned(),
+        Err(e) => e.to_string(),
+    }
+}
+
+#[actix_web::test]
+async fn any_version() {
+    let app = test::init_service(App::new().service(any_handler)).await;
+    let mut fixture = util::fixture("notification");
+    fixture.set_header("Twitch-Eventsub-Subscription-Version", "beta");
+
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        test::read_body(res).await,
+        "channel.channel_points_custom_reward_redemption.add beta"
+    );
+
+    // the signature is still checked
+    let res =
+        test::call_service(&app, util::request(&fixture).set_payload("{}").to_request()).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let app = test::init_service(
+        App::new().route("/eventsub", web::post().to(util::handle::<TestConfig>)),
+    )
+    .await;
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn batch() {
+    let app = test::init_service(App::new().service(batch_handler)).await;
+
+    let fixture = util::fixture("verification");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(
+        test::read_body(res).await,
+        "f8a6a1ba-12c4-49b6-2b6d-8e7f6a3f3e0e"
+    );
+
+    let mut fixture = util::fixture("notification");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    let single = fixture.body().to_vec();
+    fixture.set_body([&b"["[..], &single, b",", &single, b"]"].concat());
+    fixture.sign(util::SECRET);
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(test::read_body(res).await, "2");
+
+    // the signature covers the whole batch
+    let res = test::call_service(
+        &app,
+        util::request(&fixture)
+            .set_payload([&b"["[..], &single, b"]"].concat())
+            .to_request(),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn raw() {
+    let hooks = web::Data::new(Hooks::default());
+    let app = test::init_service(App::new().app_data(hooks.clone()).service(raw_handler)).await;
+
+    let fixture = util::fixture("notification");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(test::read_body(res).await, "testFromUser true");
+
+    let fixture = util::fixture("verification");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(
+        test::read_body(res).await,
+        "f8a6a1ba-12c4-49b6-2b6d-8e7f6a3f3e0e"
+    );
+
+    // only the subscription was read
+    let fixture = util::fixture("revocation");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(
+        test::read_body(res).await,
+        "2d7bb1c5-5d1d-4e0e-9c6f-4f1a7c2c3a10"
+    );
+
+    // the signature is checked before anything is read
+    let fixture = util::fixture("notification");
+    let res =
+        test::call_service(&app, util::request(&fixture).set_payload("{}").to_request()).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    // the hooks see the subscription
+    assert_eq!(hooks.verified.lock().unwrap().len(), 3);
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(Hooks::allowing(
+                "f1c2a387-161a-49f9-a165-0f21d7a4e1c4",
+            )))
+            .service(raw_handler),
+    )
+    .await;
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
+
+#[actix_web::test]
+async fn streaming() {
+    let hooks = web::Data::new(Hooks::default());
+    let app = test::init_service(
+        App::new()
+            .app_data(hooks.clone())
+            .service(streaming_handler),
+    )
+    .await;
+    let fixture = util::fixture("notification");
+
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(test::read_body(res).await, fixture.body());
+
+    let res =
+        test::call_service(&app, util::request(&fixture).set_payload("{}").to_request()).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    // the hooks run once the signature is checked
+    assert_eq!(
+        *hooks.verified.lock().unwrap(),
+        [(
+            "2d7bb1c5-5d1d-4e0e-9c6f-4f1a7c2c3a10".to_owned(),
+            MessageType::Notification
+        )]
+    );
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(Hooks::allowing(
+                "f1c2a387-161a-49f9-a165-0f21d7a4e1c4",
+            )))
+            .service(streaming_handler),
+    )
+    .await;
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+    // the message id is checked last
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(Hooks::dedupe()))
+            .service(streaming_handler),
+    )
+    .await;
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[cfg(feature = "debug-report")]
+#[actix_web::test]
+async fn debug_report() {
+    use actix_web_eventsub::DebugReport;
+
+    #[post("/eventsub")]
+    async fn debug_handler(report: DebugReport<TestConfig>) -> DebugReport<TestConfig> {
+        report
+    }
+
+    let app = test::init_service(App::new().service(debug_handler)).await;
+    let fixture = util::fixture("notification");
+    let report: serde_json::Value =
+        test::call_and_read_body_json(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(report["signature_valid"], true);
+    assert_eq!(report["message_type"], "notification");
+    assert_eq!(report["header_error"], serde_json::Value::Null);
+
+    // nothing is rejected
+    let report: serde_json::Value =
+        test::call_and_read_body_json(&app, util::request(&fixture).set_payload("{}").to_request())
+            .await;
+    assert_eq!(report["signature_valid"], false);
+    assert_eq!(report["body_len"], 2);
+}
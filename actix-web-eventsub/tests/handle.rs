@@ -0,0 +1,207 @@
+//! Answering deliveries from the handler.
+
+use std::sync::{Arc, Mutex};
+
+use actix_web::{http::StatusCode, post, test, web, App, HttpResponse, Responder};
+use actix_web_eventsub::{
+    types::channel::ChannelPointsCustomRewardRedemptionAddV1, AnyData, Data, EventMeta,
+};
+use tokio::sync::{mpsc, Notify};
+
+use crate::util::{Settings, TestConfig};
+mod util;
+
+struct JsonAck;
+
+impl Settings for JsonAck {
+    fn notification_response() -> HttpResponse {
+        HttpResponse::Ok().json(serde_json::json!({ "ack": true }))
+    }
+}
+
+#[post("/eventsub")]
+async fn json_ack_handler(
+    event: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig<JsonAck>>,
+) -> impl Responder {
+    event.spawn_handler(|_| async {})
+}
+
+static PANICKED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct RecordPanics;
+
+impl Settings for RecordPanics {
+    fn on_handler_panic(meta: &EventMeta) {
+        PANICKED.lock().unwrap().push(meta.message_id.clone());
+    }
+}
+
+#[post("/eventsub")]
+async fn panicking_handler(
+    event: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig<RecordPanics>>,
+) -> impl Responder {
+    event.spawn_handler(|_| async { panic!("the handler failed") })
+}
+
+#[post("/eventsub/any")]
+async fn json_ack_any_handler(event: AnyData<TestConfig<JsonAck>>) -> impl Responder {
+    event.handle()
+}
+
+#[actix_web::test]
+async fn spawn_handler() {
+    let release = Arc::new(Notify::new());
+    let (done_tx, mut done_rx) = mpsc::unbounded_channel();
+    let handler = {
+        let release = release.clone();
+        move |event: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>| {
+            let release = release.clone();
+            let done_tx = done_tx.clone();
+            async move {
+                event.spawn_handler(move |event| async move {
+                    release.notified().await;
+                    done_tx.send(event.meta().message_id.clone()).unwrap();
+                })
+            }
+        }
+    };
+    let app = test::init_service(App::new().route("/eventsub", web::post().to(handler))).await;
+
+    let fixture = util::fixture("verification");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(
+        test::read_body(res).await,
+        "f8a6a1ba-12c4-49b6-2b6d-8e7f6a3f3e0e"
+    );
+
+    // the response is sent while the handler still waits
+    let fixture = util::fixture("notification");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    assert!(done_rx.try_recv().is_err());
+
+    release.notify_one();
+    assert_eq!(
+        done_rx.recv().await.unwrap(),
+        "a6e2dcd1-3e18-8d6c-7a0f-6fc8f7fd5a51"
+    );
+    // verifications never reach the handler
+    assert!(done_rx.try_recv().is_err());
+}
+
+#[actix_web::test]
+async fn notification_response() {
+    let app = test::init_service(
+        App::new()
+            .service(json_ack_handler)
+            .service(json_ack_any_handler),
+    )
+    .await;
+
+    let fixture = util::fixture("verification");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        test::read_body(res).await,
+        "f8a6a1ba-12c4-49b6-2b6d-8e7f6a3f3e0e"
+    );
+
+    let fixture = util::fixture("notification");
+    for uri in ["/eventsub", "/eventsub/any"] {
+        let req = util::request(&fixture).uri(uri);
+        let res = test::call_service(&app, req.to_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(test::read_body(res).await, r#"{"ack":true}"#);
+    }
+
+    // revocations are still acknowledged with 204
+    let fixture = util::fixture("revocation");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+}
+
+#[cfg(feature = "opentelemetry")]
+#[actix_web::test]
+async fn otel_context() {
+    use opentelemetry::trace::{TraceContextExt, TraceId};
+
+    #[post("/eventsub")]
+    async fn traced_handler(
+        event: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+    ) -> impl Responder {
+        let trace_id = event.otel_context().span().span_context().trace_id();
+        assert_ne!(trace_id, TraceId::INVALID);
+        let current = event
+            .in_otel_context(async {
+                opentelemetry::Context::current()
+                    .span()
+                    .span_context()
+                    .trace_id()
+            })
+            .await;
+        assert_eq!(current, trace_id);
+        event.handle(
+            move |_| {
+                let current = opentelemetry::Context::current();
+                assert_eq!(current.span().span_context().trace_id(), trace_id);
+                trace_id.to_string()
+            },
+            |_| String::new(),
+        )
+    }
+
+    #[post("/eventsub/any")]
+    async fn traced_any_handler(event: AnyData<TestConfig>) -> impl Responder {
+        let trace_id = event.otel_context().span().span_context().trace_id();
+        let current = event
+            .in_otel_context(async {
+                opentelemetry::Context::current()
+                    .span()
+                    .span_context()
+                    .trace_id()
+            })
+            .await;
+        assert_eq!(current, trace_id);
+        trace_id.to_string()
+    }
+
+    opentelemetry::global::set_tracer_provider(
+        opentelemetry_sdk::trace::TracerProvider::builder().build(),
+    );
+    let app = test::init_service(
+        App::new()
+            .service(traced_handler)
+            .service(traced_any_handler),
+    )
+    .await;
+    let fixture = util::fixture("notification");
+    for uri in ["/eventsub", "/eventsub/any"] {
+        let req = util::request(&fixture).uri(uri);
+        let first = test::call_and_read_body(&app, req.to_request()).await;
+        let req = util::request(&fixture).uri(uri);
+        let second = test::call_and_read_body(&app, req.to_request()).await;
+        // every delivery starts a new trace
+        assert_eq!(first.len(), 32);
+        assert_ne!(first, second);
+    }
+}
+
+#[actix_web::test]
+async fn handler_panic() {
+    let app = test::init_service(App::new().service(panicking_handler)).await;
+
+    let fixture = util::fixture("notification");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    for _ in 0..100 {
+        if !PANICKED.lock().unwrap().is_empty() {
+            break;
+        }
+        tokio::task::yield_now().await;
+    }
+    assert_eq!(
+        *PANICKED.lock().unwrap(),
+        ["a6e2dcd1-3e18-8d6c-7a0f-6fc8f7fd5a51"]
+    );
+}
@@ -0,0 +1,192 @@
+//! The checks and callbacks of `Config` that run for verified requests.
+
+use actix_web::{http::StatusCode, post, test, web, App, HttpResponse, Responder};
+use actix_web_eventsub::{
+    types::channel::ChannelPointsCustomRewardRedemptionAddV1, Config, Data, MessageType,
+};
+
+use crate::util::{Hooks, Settings, TestConfig};
+mod util;
+
+struct RetryLimit;
+
+impl Settings for RetryLimit {
+    const MAX_RETRIES: Option<u32> = Some(3);
+}
+
+struct AutoAck;
+
+impl Settings for AutoAck {
+    const AUTO_ACK_REVOCATIONS: bool = true;
+}
+
+#[post("/eventsub")]
+async fn retry_handler(
+    _event: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig<RetryLimit>>,
+) -> impl Responder {
+    "handled"
+}
+
+async fn failing_revocation_handler<C: Config>(
+    event: Data<ChannelPointsCustomRewardRedemptionAddV1, C>,
+) -> impl Responder {
+    event.handle(
+        |_| HttpResponse::Ok().finish(),
+        |_| HttpResponse::InternalServerError().finish(),
+    )
+}
+
+async fn accepting_handler(
+    _event: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+) -> impl Responder {
+    "handled"
+}
+
+#[actix_web::test]
+async fn subscription_allow_list() {
+    let fixture = util::fixture("notification");
+    for (allowed, status) in [
+        (
+            "2d7bb1c5-5d1d-4e0e-9c6f-4f1a7c2c3a10",
+            StatusCode::NO_CONTENT,
+        ),
+        (
+            "f1c2a387-161a-49f9-a165-0f21d7a4e1c4",
+            StatusCode::FORBIDDEN,
+        ),
+    ] {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(Hooks::allowing(allowed)))
+                .route("/eventsub", web::post().to(util::handle::<TestConfig>)),
+        )
+        .await;
+
+        let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+        assert_eq!(res.status(), status, "{allowed}");
+    }
+}
+
+#[actix_web::test]
+async fn accept_condition() {
+    let app = test::init_service(
+        App::new()
+            .service(
+                web::resource("/eventsub/53946574")
+                    .app_data(web::Data::new(Hooks::broadcaster("53946574")))
+                    .route(web::post().to(accepting_handler)),
+            )
+            .service(
+                web::resource("/eventsub/12826")
+                    .app_data(web::Data::new(Hooks::broadcaster("12826")))
+                    .route(web::post().to(accepting_handler)),
+            ),
+    )
+    .await;
+    let fixture = util::fixture("notification");
+
+    let req = util::request(&fixture).uri("/eventsub/53946574");
+    let res = test::call_service(&app, req.to_request()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // acknowledged, so Twitch doesn't retry it, but not handled
+    let req = util::request(&fixture).uri("/eventsub/12826");
+    let res = test::call_service(&app, req.to_request()).await;
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    assert!(test::read_body(res).await.is_empty());
+}
+
+#[actix_web::test]
+async fn on_verified() {
+    let hooks = web::Data::new(Hooks::default());
+    let app = test::init_service(
+        App::new()
+            .app_data(hooks.clone())
+            .route("/eventsub", web::post().to(util::handle::<TestConfig>)),
+    )
+    .await;
+
+    for name in ["verification", "notification"] {
+        let fixture = util::fixture(name);
+        let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+        assert!(res.status().is_success(), "{name}");
+    }
+    // requests that fail verification aren't observed
+    let fixture = util::fixture("notification");
+    let res =
+        test::call_service(&app, util::request(&fixture).set_payload("{}").to_request()).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let verified = hooks.verified.lock().unwrap();
+    assert_eq!(verified.len(), 2);
+    assert_eq!(verified[0].1, MessageType::Verification);
+    assert_eq!(
+        verified[1],
+        (
+            "2d7bb1c5-5d1d-4e0e-9c6f-4f1a7c2c3a10".to_owned(),
+            MessageType::Notification
+        )
+    );
+}
+
+#[actix_web::test]
+async fn auto_ack_revocations() {
+    let hooks = web::Data::new(Hooks::default());
+    let app = test::init_service(App::new().app_data(hooks.clone()).route(
+        "/eventsub",
+        web::post().to(failing_revocation_handler::<TestConfig<AutoAck>>),
+    ))
+    .await;
+
+    let fixture = util::fixture("revocation");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        *hooks.revoked.lock().unwrap(),
+        ["2d7bb1c5-5d1d-4e0e-9c6f-4f1a7c2c3a10"]
+    );
+    // only revocations are acknowledged automatically
+    let fixture = util::fixture("notification");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(hooks.revoked.lock().unwrap().len(), 1);
+
+    // by default, the handler controls the response
+    let app = test::init_service(App::new().route(
+        "/eventsub",
+        web::post().to(failing_revocation_handler::<TestConfig>),
+    ))
+    .await;
+    let fixture = util::fixture("revocation");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[actix_web::test]
+async fn retry_limit() {
+    let app = test::init_service(App::new().service(retry_handler)).await;
+    let mut fixture = util::fixture("notification");
+
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    fixture.set_header("Twitch-Eventsub-Message-Retry", "3");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    fixture.set_header("Twitch-Eventsub-Message-Retry", "10");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    assert!(test::read_body(res).await.is_empty());
+
+    // the header isn't signed, so the signature is checked first
+    let res =
+        test::call_service(&app, util::request(&fixture).set_payload("{}").to_request()).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    // verifications are always handled
+    let mut fixture = util::fixture("verification");
+    fixture.set_header("Twitch-Eventsub-Message-Retry", "10");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(test::read_body(res).await, "handled");
+}
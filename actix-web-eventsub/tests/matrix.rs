@@ -0,0 +1,202 @@
+//! Every message type and failure for each subscription type, without the twitch-cli.
+//!
+//! Add a subscription type with one line in the `matrix!` invocation at the bottom.
+
+use actix_web::{http::StatusCode, test, web, App, HttpResponse};
+use actix_web_eventsub::{
+    types::{
+        channel::{
+            ChannelChatMessageV1, ChannelPointsCustomRewardRedemptionAddV1,
+            ChannelPointsCustomRewardRedemptionUpdateV1,
+        },
+        EventSubscription,
+    },
+    MessageType, TryData,
+};
+use eventsub_common::{fixture::Fixture, headers::SUBSCRIPTION_VERSION};
+use serde_json::json;
+
+use crate::{
+    matrix::Case,
+    util::{secret_for, Hooks, TestConfig},
+};
+#[path = "../../fixtures/matrix.rs"]
+mod matrix;
+#[path = "../../fixtures/process.rs"]
+mod process;
+mod util;
+
+async fn eventsub<P: EventSubscription + 'static>(event: TryData<P, TestConfig>) -> HttpResponse {
+    match event {
+        Ok(event) => HttpResponse::Ok().body(process::process(event.payload())),
+        Err(e) => HttpResponse::BadRequest().body(e.to_string()),
+    }
+}
+
+/// Send the deliveries in order to one app.
+async fn send<P: EventSubscription + 'static>(fixtures: &[&Fixture]) -> Vec<(StatusCode, String)> {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(Hooks::dedupe()))
+            .route("/eventsub", web::post().to(eventsub::<P>)),
+    )
+    .await;
+    let mut responses = Vec::new();
+    for fixture in fixtures {
+        let res = test::call_service(&app, util::request(fixture).to_request()).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+        responses.push((status, String::from_utf8(body.to_vec()).unwrap()));
+    }
+    responses
+}
+
+async fn verification<P: EventSubscription + 'static>(case: Case) {
+    let fixture = matrix::delivery::<P>(MessageType::Verification, &case, secret_for::<P>());
+    assert_eq!(
+        send::<P>(&[&fixture]).await,
+        [(StatusCode::OK, matrix::CHALLENGE.to_owned())]
+    );
+}
+
+async fn notification<P: EventSubscription + 'static>(case: Case) {
+    let fixture = matrix::delivery::<P>(MessageType::Notification, &case, secret_for::<P>());
+    assert_eq!(
+        send::<P>(&[&fixture]).await,
+        [(
+            StatusCode::OK,
+            format!("notification for {}", matrix::SUBSCRIPTION_ID)
+        )]
+    );
+}
+
+async fn revocation<P: EventSubscription + 'static>(case: Case) {
+    let fixture = matrix::delivery::<P>(MessageType::Revocation, &case, secret_for::<P>());
+    assert_eq!(
+        send::<P>(&[&fixture]).await,
+        [(
+            StatusCode::OK,
+            format!("revocation of {}", matrix::SUBSCRIPTION_ID)
+        )]
+    );
+}
+
+async fn wrong_signature<P: EventSubscription + 'static>(case: Case) {
+    let mut fixture = matrix::delivery::<P>(MessageType::Notification, &case, secret_for::<P>());
+    fixture.sign(b"not the secret");
+    assert_eq!(
+        send::<P>(&[&fixture]).await,
+        [(
+            StatusCode::BAD_REQUEST,
+            "The provided signature wasn't expected".to_owned()
+        )]
+    );
+}
+
+async fn too_old<P: EventSubscription + 'static>(case: Case) {
+    let mut fixture = matrix::delivery::<P>(MessageType::Notification, &case, secret_for::<P>());
+    matrix::age(&mut fixture, 11, secret_for::<P>());
+    assert_eq!(
+        send::<P>(&[&fixture]).await,
+        [(
+            StatusCode::BAD_REQUEST,
+            "Invalid headers: The message is too old".to_owned()
+        )]
+    );
+}
+
+async fn version_mismatch<P: EventSubscription + 'static>(case: Case) {
+    let mut fixture = matrix::delivery::<P>(MessageType::Notification, &case, secret_for::<P>());
+    fixture.set_header(SUBSCRIPTION_VERSION, "0");
+    fixture.sign(secret_for::<P>());
+    assert_eq!(
+        send::<P>(&[&fixture]).await,
+        [(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Invalid headers: Cannot accept this version, expected: {}",
+                P::VERSION
+            )
+        )]
+    );
+}
+
+async fn duplicate_id<P: EventSubscription + 'static>(case: Case) {
+    let fixture = matrix::delivery::<P>(MessageType::Notification, &case, secret_for::<P>());
+    let mut retry = fixture.clone();
+    retry.refresh(secret_for::<P>());
+    assert_eq!(
+        send::<P>(&[&fixture, &retry]).await,
+        [
+            (
+                StatusCode::OK,
+                format!("notification for {}", matrix::SUBSCRIPTION_ID)
+            ),
+            (
+                StatusCode::BAD_REQUEST,
+                "Won't handle id (possible duplicate)".to_owned()
+            )
+        ]
+    );
+}
+
+/// Run every case for each subscription type: `name: Type => condition, event;`.
+macro_rules! matrix {
+    ($($name:ident: $sub:ty => $condition:expr, $event:expr;)*) => {$(
+        mod $name {
+            use super::*;
+
+            fn case() -> Case {
+                Case {
+                    condition: $condition,
+                    event: $event,
+                }
+            }
+
+            #[actix_web::test]
+            async fn verification() {
+                super::verification::<$sub>(case()).await;
+            }
+
+            #[actix_web::test]
+            async fn notification() {
+                super::notification::<$sub>(case()).await;
+            }
+
+            #[actix_web::test]
+            async fn revocation() {
+                super::revocation::<$sub>(case()).await;
+            }
+
+            #[actix_web::test]
+            async fn wrong_signature() {
+                super::wrong_signature::<$sub>(case()).await;
+            }
+
+            #[actix_web::test]
+            async fn too_old() {
+                super::too_old::<$sub>(case()).await;
+            }
+
+            #[actix_web::test]
+            async fn version_mismatch() {
+                super::version_mismatch::<$sub>(case()).await;
+            }
+
+            #[actix_web::test]
+            async fn duplicate_id() {
+                super::duplicate_id::<$sub>(case()).await;
+            }
+        }
+    )*};
+}
+
+matrix! {
+    redemption_add: ChannelPointsCustomRewardRedemptionAddV1 =>
+        json!({ "broadcaster_user_id": "53946574", "reward_id": "" }), matrix::redemption();
+    redemption_update: ChannelPointsCustomRewardRedemptionUpdateV1 =>
+        json!({ "broadcaster_user_id": "53946574", "reward_id": "" }), matrix::redemption();
+    chat_message: ChannelChatMessageV1 =>
+        json!({ "broadcaster_user_id": "53946574", "user_id": "81723905" }),
+        json!({ "message_id": "cc106a89-1814-919d-454c-f4f2f970aae7" });
+}
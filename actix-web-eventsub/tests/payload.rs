@@ -0,0 +1,115 @@
+//! Accessing the extracted payload.
+
+use actix_web::{http::StatusCode, post, test, App, HttpResponse, Responder};
+use actix_web_eventsub::{
+    types::channel::ChannelPointsCustomRewardRedemptionAddV1, Data, EventsubPayload,
+};
+
+use crate::{process::process, util::TestConfig};
+#[path = "../../fixtures/process.rs"]
+mod process;
+mod util;
+
+#[post("/eventsub")]
+async fn shared_handler(
+    event: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+) -> impl Responder {
+    let processed = process(event.payload());
+    assert_eq!(process(&event.into_payload()), processed);
+    HttpResponse::Ok().body(processed)
+}
+
+#[post("/eventsub")]
+async fn spawning_handler(
+    event: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+) -> impl Responder {
+    let (payload, meta) = event.into_parts();
+    let id = actix_web::rt::spawn(async move {
+        assert!(matches!(payload, EventsubPayload::Notification(_)));
+        meta.message_id
+    })
+    .await
+    .unwrap();
+    HttpResponse::Ok().body(id)
+}
+
+#[post("/eventsub")]
+async fn redemption_handler(
+    event: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+) -> impl Responder {
+    match event.payload() {
+        EventsubPayload::Notification(notification) => format!(
+            "{} {} {}",
+            notification.event.user_login,
+            notification.event.reward.title,
+            notification.event.user_input
+        ),
+        _ => "no event".to_owned(),
+    }
+}
+
+#[post("/eventsub")]
+async fn condition_handler(
+    event: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+) -> impl Responder {
+    event.condition().unwrap().broadcaster_user_id.to_string()
+}
+
+#[actix_web::test]
+async fn shared_logic() {
+    let app = test::init_service(App::new().service(shared_handler)).await;
+
+    let fixture = util::fixture("verification");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(
+        test::read_body(res).await,
+        "f8a6a1ba-12c4-49b6-2b6d-8e7f6a3f3e0e"
+    );
+
+    let fixture = util::fixture("notification");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(
+        test::read_body(res).await,
+        "notification for 2d7bb1c5-5d1d-4e0e-9c6f-4f1a7c2c3a10"
+    );
+}
+
+#[actix_web::test]
+async fn into_parts() {
+    let app = test::init_service(App::new().service(spawning_handler)).await;
+    let fixture = util::fixture("notification");
+
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        test::read_body(res).await,
+        fixture.header("Twitch-Eventsub-Message-Id").unwrap()
+    );
+}
+
+#[actix_web::test]
+async fn redemption_event() {
+    let app = test::init_service(App::new().service(redemption_handler)).await;
+
+    let fixture = util::fixture("notification");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(
+        test::read_body(res).await,
+        "testFromUser Test Reward from CLI Test Input From CLI"
+    );
+
+    let fixture = util::fixture("verification");
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(test::read_body(res).await, "no event");
+}
+
+#[actix_web::test]
+async fn typed_condition() {
+    let app = test::init_service(App::new().service(condition_handler)).await;
+
+    for name in ["notification", "verification"] {
+        let fixture = util::fixture(name);
+        let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+        assert_eq!(test::read_body(res).await, "53946574", "{name}");
+    }
+}
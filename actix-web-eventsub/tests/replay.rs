@@ -0,0 +1,98 @@
+//! Replaying the recorded deliveries in `fixtures/`.
+
+use actix_web::{http::StatusCode, post, test, web, App, HttpResponse, Responder};
+use actix_web_eventsub::{
+    types::{channel::ChannelPointsCustomRewardRedemptionAddV1, Status},
+    Data, EventsubPayload,
+};
+use chrono::DateTime;
+use eventsub_common::{headers::MESSAGE_TIMESTAMP, verify::verify_and_decode_at};
+
+use crate::util::TestConfig;
+mod util;
+
+#[post("/eventsub")]
+async fn status_handler(
+    event: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+) -> impl Responder {
+    // the subscription is pending until the challenge is answered
+    assert!(matches!(event.payload, EventsubPayload::Verification(_)));
+    assert_eq!(
+        event.payload.subscription().status,
+        Status::WebhookCallbackVerificationPending
+    );
+    event.handle(
+        |_| HttpResponse::NoContent().finish(),
+        |_| HttpResponse::NoContent().finish(),
+    )
+}
+
+#[actix_web::test]
+async fn recorded_signatures() {
+    for name in ["verification", "notification", "revocation"] {
+        let fixture = util::stale_fixture(name);
+        let recorded_at =
+            DateTime::parse_from_rfc3339(fixture.header(MESSAGE_TIMESTAMP).unwrap()).unwrap();
+
+        verify_and_decode_at::<ChannelPointsCustomRewardRedemptionAddV1, _>(
+            &fixture.header_map(),
+            fixture.body(),
+            util::SECRET,
+            recorded_at.into(),
+        )
+        .unwrap_or_else(|e| panic!("{name}: {e}"));
+    }
+}
+
+#[actix_web::test]
+async fn replay_verification() {
+    let app = test::init_service(
+        App::new().route("/eventsub", web::post().to(util::handle::<TestConfig>)),
+    )
+    .await;
+    let fixture = util::fixture("verification");
+
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        test::read_body(res).await,
+        "f8a6a1ba-12c4-49b6-2b6d-8e7f6a3f3e0e"
+    );
+}
+
+#[actix_web::test]
+async fn replay_notification() {
+    let app = test::init_service(
+        App::new().route("/eventsub", web::post().to(util::handle::<TestConfig>)),
+    )
+    .await;
+    let fixture = util::fixture("notification");
+
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+}
+
+#[actix_web::test]
+async fn rejects_stale_fixture() {
+    let app = test::init_service(
+        App::new().route("/eventsub", web::post().to(util::handle::<TestConfig>)),
+    )
+    .await;
+    let fixture = util::stale_fixture("notification");
+
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn pending_verification() {
+    let app = test::init_service(App::new().service(status_handler)).await;
+    let fixture = util::fixture("verification");
+
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        test::read_body(res).await,
+        "f8a6a1ba-12c4-49b6-2b6d-8e7f6a3f3e0e"
+    );
+}
@@ -0,0 +1,137 @@
+use std::{
+    borrow::Cow,
+    future::{ready, Ready},
+};
+
+use actix_web::{http::StatusCode, post, test, web, App, HttpRequest, HttpResponse, Responder};
+use actix_web_eventsub::{
+    types::{channel::ChannelPointsCustomRewardRedemptionAddV1, EventSubscription},
+    Config, Data, HmacKey, PathScopedSecret, VerifyDecodeError,
+};
+mod util;
+
+struct ScopedConfig;
+
+impl Config for ScopedConfig {
+    type Error = VerifyDecodeError;
+
+    type CheckEventIdFut = Ready<bool>;
+
+    fn get_secret(req: &HttpRequest) -> Result<&[u8], Self::Error> {
+        req.app_data::<web::Data<PathScopedSecret>>()
+            .zip(req.match_info().get("broadcaster"))
+            .and_then(|(secrets, broadcaster)| secrets.get(broadcaster))
+            .ok_or(VerifyDecodeError::NoHmacKey)
+    }
+
+    fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+        ready(true)
+    }
+
+    fn convert_error(error: VerifyDecodeError) -> Self::Error {
+        error
+    }
+}
+
+#[post("/eventsub/{broadcaster}")]
+async fn event_handler(
+    event: Data<ChannelPointsCustomRewardRedemptionAddV1, ScopedConfig>,
+) -> impl Responder {
+    event.handle(
+        |_| HttpResponse::NoContent().finish(),
+        |_| HttpResponse::NoContent().finish(),
+    )
+}
+
+#[actix_web::test]
+async fn secret_per_path() {
+    let secrets = PathScopedSecret::new()
+        .with("alice", util::SECRET)
+        .with("bob", "bob's secret");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(secrets))
+            .service(event_handler),
+    )
+    .await;
+    let fixture = util::fixture("notification");
+
+    for (path, status) in [
+        ("/eventsub/alice", StatusCode::NO_CONTENT),
+        ("/eventsub/bob", StatusCode::BAD_REQUEST),
+        ("/eventsub/eve", StatusCode::INTERNAL_SERVER_ERROR),
+    ] {
+        let req = util::request(&fixture).uri(path).to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), status, "{path}");
+    }
+}
+
+/// The secret, stored reversed - a stand-in for a real key derivation.
+struct MasterKey(Vec<u8>);
+
+struct DerivedKey<'a>(&'a [u8]);
+
+impl HmacKey for DerivedKey<'_> {
+    fn key_material(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(self.0.iter().rev().copied().collect())
+    }
+}
+
+struct DerivingConfig;
+
+impl Config for DerivingConfig {
+    type Error = VerifyDecodeError;
+
+    type CheckEventIdFut = Ready<bool>;
+
+    fn get_secret(_req: &HttpRequest) -> Result<&[u8], Self::Error> {
+        Err(VerifyDecodeError::NoHmacKey)
+    }
+
+    fn get_key_for<P: EventSubscription>(
+        req: &HttpRequest,
+    ) -> Result<impl HmacKey + '_, Self::Error> {
+        req.app_data::<web::Data<MasterKey>>()
+            .map(|master| DerivedKey(&master.0))
+            .ok_or(VerifyDecodeError::NoHmacKey)
+    }
+
+    fn check_event_id(_req: &HttpRequest, _id: &str) -> Self::CheckEventIdFut {
+        ready(true)
+    }
+
+    fn convert_error(error: VerifyDecodeError) -> Self::Error {
+        error
+    }
+}
+
+#[post("/eventsub")]
+async fn deriving_handler(
+    event: Data<ChannelPointsCustomRewardRedemptionAddV1, DerivingConfig>,
+) -> impl Responder {
+    event.handle(
+        |_| HttpResponse::NoContent().finish(),
+        |_| HttpResponse::NoContent().finish(),
+    )
+}
+
+#[actix_web::test]
+async fn derived_key() {
+    let fixture = util::fixture("notification");
+    let reversed = util::SECRET.iter().rev().copied().collect();
+
+    for (master, status) in [
+        (reversed, StatusCode::NO_CONTENT),
+        (util::SECRET.to_vec(), StatusCode::BAD_REQUEST),
+    ] {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(MasterKey(master)))
+                .service(deriving_handler),
+        )
+        .await;
+        let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+        assert_eq!(res.status(), status);
+    }
+}
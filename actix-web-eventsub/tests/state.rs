@@ -0,0 +1,89 @@
+//! Configs reading the app state.
+
+use std::{
+    collections::HashSet,
+    future::{ready, Ready},
+    sync::Mutex,
+    time::Duration,
+};
+
+use actix_web::{http::StatusCode, post, test, web, App, Responder};
+use actix_web_eventsub::{
+    types::channel::ChannelPointsCustomRewardRedemptionAddV1, Config, Data, StateConfig,
+    VerifyDecodeError, WithState,
+};
+
+mod util;
+
+#[derive(Default)]
+struct AppState {
+    seen: Mutex<HashSet<String>>,
+}
+
+struct AppStateConfig;
+
+impl StateConfig<AppState> for AppStateConfig {
+    type Error = VerifyDecodeError;
+
+    type CheckEventIdFut = Ready<bool>;
+
+    fn get_secret(_state: &AppState) -> &[u8] {
+        util::SECRET
+    }
+
+    fn check_event_id(state: &AppState, id: &str) -> Self::CheckEventIdFut {
+        ready(state.seen.lock().unwrap().insert(id.to_owned()))
+    }
+
+    fn read_timeout() -> Option<Duration> {
+        Some(Duration::from_secs(5))
+    }
+
+    fn convert_error(error: VerifyDecodeError) -> Self::Error {
+        error
+    }
+}
+
+#[post("/eventsub")]
+async fn state_handler(
+    event: Data<ChannelPointsCustomRewardRedemptionAddV1, WithState<AppStateConfig, AppState>>,
+) -> impl Responder {
+    event.handle(|_| "notification", |_| "revocation")
+}
+
+#[actix_web::test]
+async fn with_state() {
+    let state = web::Data::new(AppState::default());
+    let app = test::init_service(App::new().app_data(state.clone()).service(state_handler)).await;
+    let fixture = util::fixture("notification");
+
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(test::read_body(res).await, "notification");
+    // the id is remembered in the state
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert!(state
+        .seen
+        .lock()
+        .unwrap()
+        .contains("a6e2dcd1-3e18-8d6c-7a0f-6fc8f7fd5a51"));
+
+    // the logic can be used without a request
+    assert!(!AppStateConfig::check_event_id(&state, "a6e2dcd1-3e18-8d6c-7a0f-6fc8f7fd5a51").await);
+
+    let app = test::init_service(App::new().service(state_handler)).await;
+    let res = test::call_service(&app, util::request(&fixture).to_request()).await;
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[actix_web::test]
+async fn forwards_hooks() {
+    type Adapted = WithState<AppStateConfig, AppState>;
+    assert_eq!(Adapted::read_timeout(), Some(Duration::from_secs(5)));
+    // hooks that aren't overridden keep the defaults of `Config`
+    assert_eq!(Adapted::max_retries(), None);
+    assert_eq!(
+        Adapted::notification_response().status(),
+        StatusCode::NO_CONTENT
+    );
+}
@@ -1,30 +1,224 @@
+#![allow(dead_code)]
+
+use actix_web::{test::TestRequest, web, HttpRequest, HttpResponse, Responder};
+use actix_web_eventsub::{
+    types::{
+        channel::ChannelPointsCustomRewardRedemptionAddV1, EventSubSubscription, EventSubscription,
+        EventType,
+    },
+    Condition, Config, Data, EventMeta, HmacKey, MessageType, Revocation, VerifyDecodeError,
+};
+use eventsub_common::fixture::Fixture;
+use std::{
+    collections::HashSet,
+    future::{ready, Ready},
+    marker::PhantomData,
+    sync::Mutex,
+};
 use tokio::process::Command;
 
 pub const SECRET: &[u8] = b"5f5f121fc807a21bab4209b2f34e90932778f12c099ca3ca17ee00afd0b328ba";
-// Second secret used to separate different routes
+// Second secret used for redemption updates to separate different routes
 pub const SECRET2: &[u8] = b"5f5f121fc807a21bab4209b2f34e90932778f12c099ca3ca17ee00afd0b328bb";
 
-pub trait SecretConfig {
-    fn secret() -> &'static [u8];
+/// The secret [`TestConfig`] expects for subscriptions of `P`.
+pub fn secret_for<P: EventSubscription>() -> &'static [u8] {
+    match P::EVENT_TYPE {
+        EventType::ChannelPointsCustomRewardRedemptionUpdate => SECRET2,
+        _ => SECRET,
+    }
 }
 
-macro_rules! make_secret_configs {
-    ($($name:ident => $evaluate:expr,)*) => {
-        $(
-            pub struct $name;
-            impl SecretConfig for $name {
-                fn secret() -> &'static [u8] {
-                    $evaluate
-                }
-            }
-        )*
-    };
+/// Settings of [`TestConfig`] that can't be read from the request.
+///
+/// Tests override these with a unit struct: `impl Settings for RetryLimit { ... }`.
+pub trait Settings {
+    const MAX_RETRIES: Option<u32> = None;
+    const JSON_ONLY: bool = false;
+    const AUTO_ACK_REVOCATIONS: bool = false;
+    const MAX_BODY_SIZE: usize = eventsub_common::body::MAX_BODY_SIZE;
+
+    fn notification_response() -> HttpResponse {
+        HttpResponse::NoContent().finish()
+    }
+
+    fn on_handler_panic(_meta: &EventMeta) {}
 }
 
-make_secret_configs!(
-    BaseSecret => SECRET,
-    SecondSecret => SECRET2,
-);
+pub struct Defaults;
+
+impl Settings for Defaults {}
+
+/// The state the hooks of [`TestConfig`] check and record to.
+///
+/// Without it in the `app_data`, everything is accepted and nothing is recorded.
+#[derive(Default)]
+pub struct Hooks {
+    /// Only accept these subscription ids.
+    pub allowed: Option<HashSet<String>>,
+    /// Only accept events of this broadcaster.
+    pub broadcaster: Option<String>,
+    /// Handle every message id once.
+    pub dedupe: bool,
+    pub seen: Mutex<HashSet<String>>,
+    pub verified: Mutex<Vec<(String, MessageType)>>,
+    pub revoked: Mutex<Vec<String>>,
+}
+
+impl Hooks {
+    pub fn allowing(id: &str) -> Self {
+        Self {
+            allowed: Some(HashSet::from([id.to_owned()])),
+            ..Self::default()
+        }
+    }
+
+    pub fn broadcaster(id: &str) -> Self {
+        Self {
+            broadcaster: Some(id.to_owned()),
+            ..Self::default()
+        }
+    }
+
+    pub fn dedupe() -> Self {
+        Self {
+            dedupe: true,
+            ..Self::default()
+        }
+    }
+}
+
+fn hooks(req: &HttpRequest) -> Option<&Hooks> {
+    req.app_data::<web::Data<Hooks>>()
+        .map(|hooks| hooks.get_ref())
+}
+
+/// The config shared by the tests.
+pub struct TestConfig<S = Defaults>(PhantomData<S>);
+
+impl<S: Settings> Config for TestConfig<S> {
+    type Error = VerifyDecodeError;
+
+    type CheckEventIdFut = Ready<bool>;
+
+    fn get_secret(_: &HttpRequest) -> Result<&[u8], Self::Error> {
+        Ok(SECRET)
+    }
+
+    fn get_key_for<P: EventSubscription>(
+        _: &HttpRequest,
+    ) -> Result<impl HmacKey + '_, Self::Error> {
+        Ok(secret_for::<P>())
+    }
+
+    fn check_event_id(req: &HttpRequest, id: &str) -> Self::CheckEventIdFut {
+        ready(
+            hooks(req).is_none_or(|hooks| {
+                !hooks.dedupe || hooks.seen.lock().unwrap().insert(id.to_owned())
+            }),
+        )
+    }
+
+    fn is_subscription_allowed(req: &HttpRequest, subscription_id: &str) -> bool {
+        hooks(req)
+            .and_then(|hooks| hooks.allowed.as_ref())
+            .is_none_or(|allowed| allowed.contains(subscription_id))
+    }
+
+    fn accept_condition(req: &HttpRequest, condition: &Condition<'_>) -> bool {
+        hooks(req)
+            .and_then(|hooks| hooks.broadcaster.as_deref())
+            .is_none_or(|broadcaster| condition.broadcaster_user_id == Some(broadcaster))
+    }
+
+    fn on_verified(
+        req: &HttpRequest,
+        subscription: &EventSubSubscription,
+        message_type: MessageType,
+    ) {
+        if let Some(hooks) = hooks(req) {
+            hooks
+                .verified
+                .lock()
+                .unwrap()
+                .push((subscription.id.to_string(), message_type));
+        }
+    }
+
+    fn on_revocation(req: &HttpRequest, revocation: &Revocation) {
+        if let Some(hooks) = hooks(req) {
+            hooks
+                .revoked
+                .lock()
+                .unwrap()
+                .push(revocation.subscription.id.to_string());
+        }
+    }
+
+    fn auto_ack_revocations() -> bool {
+        S::AUTO_ACK_REVOCATIONS
+    }
+
+    fn notification_response() -> HttpResponse {
+        S::notification_response()
+    }
+
+    fn on_handler_panic(meta: &EventMeta) {
+        S::on_handler_panic(meta);
+    }
+
+    fn max_body_size() -> usize {
+        S::MAX_BODY_SIZE
+    }
+
+    fn max_retries() -> Option<u32> {
+        S::MAX_RETRIES
+    }
+
+    fn require_json_content_type() -> bool {
+        S::JSON_ONLY
+    }
+
+    fn convert_error(error: VerifyDecodeError) -> Self::Error {
+        error
+    }
+}
+
+/// Answer verifications with their challenge and everything else with `204 No Content`.
+pub async fn handle<C: Config>(
+    event: Data<ChannelPointsCustomRewardRedemptionAddV1, C>,
+) -> impl Responder {
+    event.handle(
+        |_| HttpResponse::NoContent().finish(),
+        |_| HttpResponse::NoContent().finish(),
+    )
+}
+
+/// Load a fixture from `fixtures/` and refresh it with [`SECRET`].
+pub fn fixture(name: &str) -> Fixture {
+    let mut fixture = stale_fixture(name);
+    fixture.refresh(SECRET);
+    fixture
+}
+
+/// Load a fixture from `fixtures/` as it was recorded.
+pub fn stale_fixture(name: &str) -> Fixture {
+    Fixture::load(format!(
+        "{}/../fixtures/{name}.http",
+        env!("CARGO_MANIFEST_DIR")
+    ))
+    .expect("fixture should load")
+}
+
+/// Build a request to `/eventsub` replaying the fixture.
+pub fn request(fixture: &Fixture) -> TestRequest {
+    fixture
+        .headers()
+        .fold(TestRequest::post().uri("/eventsub"), |req, header| {
+            req.insert_header(header)
+        })
+        .set_payload(fixture.body().to_vec())
+}
 
 pub async fn twitch_cli(args: impl FnOnce(&mut Command)) {
     let mut cmd = Command::new("twitch");
@@ -1,7 +1,6 @@
-use std::future::ready;
-
+use crate::util::TestConfig;
 use actix_web::{post, App, HttpResponse, Responder};
-use actix_web_eventsub::{guards, Config};
+use actix_web_eventsub::guards;
 use eventsub_common::{
     types::{
         channel::{
@@ -11,39 +10,11 @@ use eventsub_common::{
     },
     EventsubPayload, Verification,
 };
-use util::SecretConfig;
-
-use crate::util::{BaseSecret, SecondSecret};
 mod util;
 
-struct TestConfig<T> {
-    _marker: std::marker::PhantomData<T>,
-}
-
-impl<T: SecretConfig> Config for TestConfig<T> {
-    type Error = actix_web_eventsub::VerifyDecodeError;
-
-    type CheckEventIdFut = std::future::Ready<bool>;
-
-    fn get_secret(_: &actix_web::HttpRequest) -> Result<&[u8], Self::Error> {
-        Ok(T::secret())
-    }
-
-    fn check_event_id(_req: &actix_web::HttpRequest, _id: &str) -> Self::CheckEventIdFut {
-        ready(true)
-    }
-
-    fn convert_error(error: actix_web_eventsub::VerifyDecodeError) -> Self::Error {
-        error
-    }
-}
-
 #[post("/eventsub")]
 async fn event_handler(
-    event: actix_web_eventsub::Data<
-        ChannelPointsCustomRewardRedemptionAddV1,
-        TestConfig<BaseSecret>,
-    >,
+    event: actix_web_eventsub::Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
 ) -> impl Responder {
     match event.payload {
         EventsubPayload::Verification(Verification { challenge, .. }) => HttpResponse::Ok()
@@ -60,10 +31,7 @@ async fn event_handler(
     guard = "guards::event_type_fn::<ChannelPointsCustomRewardRedemptionAddV1>"
 )]
 async fn guarded_redemption_add(
-    event: actix_web_eventsub::Data<
-        ChannelPointsCustomRewardRedemptionAddV1,
-        TestConfig<BaseSecret>,
-    >,
+    event: actix_web_eventsub::Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
 ) -> impl Responder {
     match event.payload {
         EventsubPayload::Verification(Verification {
@@ -89,10 +57,7 @@ async fn guarded_redemption_add(
     guard = "guards::event_type_fn::<ChannelPointsCustomRewardRedemptionUpdateV1>"
 )]
 async fn guarded_redemption_update(
-    event: actix_web_eventsub::Data<
-        ChannelPointsCustomRewardRedemptionUpdateV1,
-        TestConfig<SecondSecret>,
-    >,
+    event: actix_web_eventsub::Data<ChannelPointsCustomRewardRedemptionUpdateV1, TestConfig>,
 ) -> impl Responder {
     match event.payload {
         EventsubPayload::Verification(Verification {
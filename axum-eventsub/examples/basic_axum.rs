@@ -1,9 +1,8 @@
-use axum::{routing::post, Router};
+use axum::{http::StatusCode, response::Response, routing::post, Router};
 use axum_eventsub::{
-    types::channel::ChannelPointsCustomRewardRedemptionAddV1, Verification, VerifyDecodeError,
+    types::channel::ChannelPointsCustomRewardRedemptionAddV1, RedemptionEvent, VerifyDecodeError,
 };
-use eventsub_common::EventsubPayload;
-use std::{borrow::Cow, sync::Arc};
+use std::sync::Arc;
 
 struct AppState {
     secret: &'static [u8],
@@ -25,14 +24,19 @@ impl axum_eventsub::Config<Arc<AppState>> for EventsubConfig {
 
 async fn eventsub(
     data: axum_eventsub::Data<ChannelPointsCustomRewardRedemptionAddV1, EventsubConfig>,
-) -> Cow<'static, str> {
-    match data.payload {
-        EventsubPayload::Verification(Verification { challenge, .. }) => challenge.into(),
-        x => {
-            println!("{x:?}");
-            "".into()
-        }
+) -> Response {
+    match data.event() {
+        Some(redemption) => println!(
+            "{} redeemed {} ({} points): {}",
+            redemption.redeemer_login(),
+            redemption.reward_title(),
+            redemption.reward_cost(),
+            redemption.user_input().unwrap_or("<no input>")
+        ),
+        // verifications are answered by `handle`
+        None => println!("{:?}", data.payload()),
     }
+    data.handle(|_| StatusCode::NO_CONTENT, |_| StatusCode::NO_CONTENT)
 }
 
 /// Run the example with
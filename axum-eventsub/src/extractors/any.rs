@@ -0,0 +1,153 @@
+use crate::extractors::{
+    eventsub::{Config, Responses, VerifyDecodeError},
+    verify,
+};
+use axum::{
+    body::HttpBody,
+    extract::FromRequest,
+    http::{Request, StatusCode},
+    response::{IntoResponse, Response},
+    BoxError,
+};
+use bytes::Bytes;
+use eventsub_common::{decode, headers::EventMeta, EventsubPayload, Verification};
+use std::marker::PhantomData;
+
+/// Extractor for an eventsub event of any subscription type and version.
+///
+/// This is meant for endpoints that only log or forward whatever arrives.
+/// The subscription type and version aren't checked, the actual ones are available
+/// in the [metadata](Self::meta). Since the concrete type of the event isn't known,
+/// notifications are decoded into a [`serde_json::Value`].
+///
+/// Skipping these checks doesn't weaken the verification: the signature and the age of
+/// the message are still checked like in [`Data`](crate::Data), so only Twitch can send events.
+/// [`Config::get_key`] gets the key from the subscription type in the headers.
+///
+/// ```
+/// # use axum_eventsub::{AnyData, Config, VerifyDecodeError};
+/// # use axum::response::IntoResponse;
+/// # struct EventsubConfig;
+/// #
+/// # impl Config<()> for EventsubConfig {
+/// #     type Rejection = VerifyDecodeError;
+/// #
+/// #     fn get_secret(_state: &()) -> &[u8] {
+/// #         b"secret"
+/// #     }
+/// #
+/// #     fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+/// #         error
+/// #     }
+/// # }
+/// async fn log_everything(event: AnyData<EventsubConfig>) -> impl IntoResponse {
+///     let meta = event.meta();
+///     println!(
+///         "{} v{}: {:?}",
+///         meta.subscription_type, meta.subscription_version, event.payload
+///     );
+///     event.handle()
+/// }
+/// # fn main() {}
+/// ```
+pub struct AnyData<C> {
+    /// The extracted payload.
+    pub payload: EventsubPayload<serde_json::Value>,
+    meta: EventMeta,
+    body: Bytes,
+    responses: Responses,
+    #[cfg(feature = "opentelemetry")]
+    context: opentelemetry::Context,
+    _config: PhantomData<C>,
+}
+
+impl<C> AnyData<C> {
+    /// The verified body of the request, see [`decode`](eventsub_common::decode#json-backend)
+    /// for how it changes with the `simd-json` feature.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// The extracted payload.
+    pub fn payload(&self) -> &EventsubPayload<serde_json::Value> {
+        &self.payload
+    }
+
+    /// Take the extracted payload, dropping the body and metadata.
+    pub fn into_payload(self) -> EventsubPayload<serde_json::Value> {
+        self.payload
+    }
+
+    /// Metadata of the message, including the actual subscription type and version.
+    pub fn meta(&self) -> &EventMeta {
+        &self.meta
+    }
+
+    /// Split the extracted data into the payload and its metadata.
+    pub fn into_parts(self) -> (EventsubPayload<serde_json::Value>, EventMeta) {
+        (self.payload, self.meta)
+    }
+
+    /// The OpenTelemetry context of the delivery, with its span as the active span.
+    ///
+    /// See [`Data::otel_context`](crate::Data::otel_context).
+    #[cfg(feature = "opentelemetry")]
+    pub fn otel_context(&self) -> &opentelemetry::Context {
+        &self.context
+    }
+
+    /// Make the OpenTelemetry context of the delivery current while `future` is polled.
+    ///
+    /// See [`Data::in_otel_context`](crate::Data::in_otel_context).
+    #[cfg(feature = "opentelemetry")]
+    pub fn in_otel_context<F: std::future::Future>(
+        &self,
+        future: F,
+    ) -> opentelemetry::trace::WithContext<F> {
+        opentelemetry::trace::FutureExt::with_context(future, self.context.clone())
+    }
+
+    /// Respond to the payload: verifications are answered with their challenge,
+    /// notifications with [`Config::notification_response`] and revocations
+    /// with `204 No Content`.
+    pub fn handle(self) -> Response {
+        match self.payload {
+            EventsubPayload::Verification(Verification { challenge, .. }) => {
+                challenge.into_response()
+            }
+            EventsubPayload::Notification(_) => self.responses.notification(),
+            EventsubPayload::Revocation(_) => StatusCode::NO_CONTENT.into_response(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<State, C, B> FromRequest<State, B> for AnyData<C>
+where
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+    C: Config<State>,
+    State: Send + Sync,
+{
+    type Rejection = C::Rejection;
+
+    async fn from_request(req: Request<B>, state: &State) -> Result<Self, Self::Rejection> {
+        let (parts, body) = req.into_parts();
+        let pending = verify::start_any::<C, State>(&parts, state).await?;
+        let (meta, mut body) = verify::read_verified::<C, State, B>(&parts, body, pending).await?;
+        let payload =
+            decode::decode_any_in_place(meta.message_type, &mut body, C::max_json_depth())
+                .map_err(|e| verify::in_context::<C, State>(&meta, VerifyDecodeError::Serde(e)))?;
+        verify::accept::<C, State>(state, &meta, payload.subscription())?;
+        Ok(Self {
+            payload,
+            #[cfg(feature = "opentelemetry")]
+            context: eventsub_common::otel::delivery_context(&meta),
+            meta,
+            body: body.freeze(),
+            responses: Responses::of::<C, State>(),
+            _config: PhantomData,
+        })
+    }
+}
@@ -0,0 +1,171 @@
+use crate::extractors::{
+    eventsub::{Config, Responses, VerifyDecodeError},
+    verify,
+};
+use axum::{
+    body::HttpBody,
+    extract::FromRequest,
+    http::{Request, StatusCode},
+    response::{IntoResponse, Response},
+    BoxError,
+};
+use bytes::Bytes;
+use eventsub_common::{
+    decode, headers::EventMeta, types::EventSubscription, EventsubPayload, MessageType,
+    Verification,
+};
+use std::marker::PhantomData;
+
+/// Extractor for a request with one or more eventsub payloads.
+///
+/// Twitch sends one payload per request - use [`Data`](crate::Data) for that. This is meant
+/// for tools replaying recorded deliveries, which may send a JSON array of payloads
+/// (see [`decode_batch`](eventsub_common::decode::decode_batch)).
+/// A single payload is extracted as a batch of one.
+///
+/// The signature covers the whole body and the headers are shared by all payloads.
+/// [`Config::is_subscription_allowed`], [`Config::accept_condition`] and [`Config::on_verified`]
+/// are called for every payload.
+///
+/// ```
+/// # use axum_eventsub::{BatchData, Config, EventsubPayload, VerifyDecodeError, types::channel::ChannelPointsCustomRewardRedemptionAddV1};
+/// # use axum::response::Response;
+/// # struct EventsubConfig;
+/// #
+/// # impl Config<()> for EventsubConfig {
+/// #     type Rejection = VerifyDecodeError;
+/// #
+/// #     fn get_secret(_state: &()) -> &[u8] {
+/// #         b"secret"
+/// #     }
+/// #
+/// #     fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+/// #         error
+/// #     }
+/// # }
+/// async fn replay(
+///     batch: BatchData<ChannelPointsCustomRewardRedemptionAddV1, EventsubConfig>,
+/// ) -> Response {
+///     for payload in batch.payloads() {
+///         if let EventsubPayload::Notification(notification) = payload {
+///             println!("Notification: {:?}", notification.event);
+///         }
+///     }
+///     batch.response()
+/// }
+/// # fn main() {}
+/// ```
+pub struct BatchData<P: EventSubscription, C> {
+    /// The extracted payloads.
+    pub payloads: Vec<EventsubPayload<P::Payload>>,
+    meta: EventMeta,
+    body: Bytes,
+    responses: Responses,
+    #[cfg(feature = "opentelemetry")]
+    context: opentelemetry::Context,
+    _config: PhantomData<C>,
+}
+
+impl<P: EventSubscription, C> BatchData<P, C> {
+    /// The verified body of the request, see [`decode`](eventsub_common::decode#json-backend)
+    /// for how it changes with the `simd-json` feature.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// The extracted payloads.
+    pub fn payloads(&self) -> &[EventsubPayload<P::Payload>] {
+        &self.payloads
+    }
+
+    /// Take the extracted payloads, dropping the body and metadata.
+    pub fn into_payloads(self) -> Vec<EventsubPayload<P::Payload>> {
+        self.payloads
+    }
+
+    /// Metadata of the request, shared by all payloads.
+    pub fn meta(&self) -> &EventMeta {
+        &self.meta
+    }
+
+    /// Split the extracted data into the payloads and their metadata.
+    pub fn into_parts(self) -> (Vec<EventsubPayload<P::Payload>>, EventMeta) {
+        (self.payloads, self.meta)
+    }
+
+    /// The OpenTelemetry context of the delivery, with its span as the active span.
+    ///
+    /// See [`Data::otel_context`](crate::Data::otel_context).
+    #[cfg(feature = "opentelemetry")]
+    pub fn otel_context(&self) -> &opentelemetry::Context {
+        &self.context
+    }
+
+    /// Make the OpenTelemetry context of the delivery current while `future` is polled.
+    ///
+    /// See [`Data::in_otel_context`](crate::Data::in_otel_context).
+    #[cfg(feature = "opentelemetry")]
+    pub fn in_otel_context<F: std::future::Future>(
+        &self,
+        future: F,
+    ) -> opentelemetry::trace::WithContext<F> {
+        opentelemetry::trace::FutureExt::with_context(future, self.context.clone())
+    }
+
+    /// The response to the batch: the challenge of the first verification,
+    /// [`Config::notification_response`] if the batch holds notifications,
+    /// or `204 No Content` otherwise.
+    pub fn response(&self) -> Response {
+        self.payloads
+            .iter()
+            .find_map(|payload| match payload {
+                EventsubPayload::Verification(Verification { challenge, .. }) => {
+                    Some(challenge.clone().into_response())
+                }
+                _ => None,
+            })
+            .unwrap_or_else(|| match self.meta.message_type {
+                MessageType::Notification => self.responses.notification(),
+                _ => StatusCode::NO_CONTENT.into_response(),
+            })
+    }
+}
+
+#[async_trait::async_trait]
+impl<State, Sub, C, B> FromRequest<State, B> for BatchData<Sub, C>
+where
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+    C: Config<State>,
+    Sub: EventSubscription,
+    State: Send + Sync,
+{
+    type Rejection = C::Rejection;
+
+    async fn from_request(req: Request<B>, state: &State) -> Result<Self, Self::Rejection> {
+        let (parts, body) = req.into_parts();
+        let pending = verify::start_for::<Sub, C, State>(&parts, state).await?;
+        let (meta, mut body) = verify::read_verified::<C, State, B>(&parts, body, pending).await?;
+        let payloads =
+            decode::decode_batch_in_place::<Sub>(meta.message_type, &mut body, C::max_json_depth())
+                .map_err(|e| verify::in_context::<C, State>(&meta, VerifyDecodeError::Serde(e)))?;
+        // the whole batch is rejected before any of it is observed
+        for payload in &payloads {
+            verify::check::<C, State>(state, payload.subscription())
+                .map_err(|e| verify::in_context::<C, State>(&meta, e))?;
+        }
+        for payload in &payloads {
+            verify::observe::<C, State>(state, payload.subscription(), meta.message_type);
+        }
+        Ok(Self {
+            payloads,
+            #[cfg(feature = "opentelemetry")]
+            context: eventsub_common::otel::delivery_context(&meta),
+            meta,
+            body: body.freeze(),
+            responses: Responses::of::<C, State>(),
+            _config: PhantomData,
+        })
+    }
+}
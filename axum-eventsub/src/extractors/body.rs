@@ -0,0 +1,71 @@
+//! Reading request bodies through [`eventsub_common::body`].
+
+use crate::extractors::eventsub::VerifyDecodeError;
+use axum::{body::HttpBody, BoxError};
+use bytes::BytesMut;
+use eventsub_common::body::{self, BodyError};
+use pin_project::pin_project;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// Adapts an `http-body` 0.4 body (used by axum 0.6) to `http-body` 1.0.
+///
+/// Trailers aren't covered by the signature, so they're dropped.
+#[pin_project]
+pub(crate) struct Compat<B>(#[pin] pub(crate) B);
+
+impl<B: HttpBody> http_body::Body for Compat<B> {
+    type Data = B::Data;
+    type Error = B::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+        self.project()
+            .0
+            .poll_data(cx)
+            .map(|data| data.map(|data| data.map(http_body::Frame::data)))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.0.is_end_stream()
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        let hint = HttpBody::size_hint(&self.0);
+        let mut size = http_body::SizeHint::new();
+        size.set_lower(hint.lower());
+        if let Some(upper) = hint.upper() {
+            size.set_upper(upper);
+        }
+        size
+    }
+}
+
+/// Read the complete body of at most `limit` bytes (see [`Config::max_body_size`](crate::Config::max_body_size)),
+/// giving up after `timeout` (see [`Config::read_timeout`](crate::Config::read_timeout)).
+pub(crate) async fn read<B>(
+    body: B,
+    limit: usize,
+    timeout: Option<Duration>,
+) -> Result<BytesMut, VerifyDecodeError>
+where
+    B: HttpBody,
+    B::Error: Into<BoxError>,
+{
+    let read = body::read_body(Compat(body), limit);
+    let read = match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, read)
+            .await
+            .map_err(|_| VerifyDecodeError::ReadTimeout)?,
+        None => read.await,
+    };
+    read.map_err(|e| match e {
+        BodyError::TooLarge(_) => VerifyDecodeError::RequestTooLarge,
+        BodyError::Body(e) => VerifyDecodeError::PayloadError(e.into()),
+    })
+}
@@ -0,0 +1,97 @@
+use crate::extractors::{body, eventsub::Config};
+use axum::{
+    body::HttpBody,
+    extract::FromRequest,
+    http::Request,
+    response::{IntoResponse, Response},
+    BoxError, Json,
+};
+use eventsub_common::{
+    debug::{self, Report},
+    headers,
+};
+use std::marker::PhantomData;
+
+/// Extractor reporting how a request is read and verified, without rejecting it.
+///
+/// **Never expose this in production.** The report reveals the start of the expected
+/// signature, which helps forging requests (see [`eventsub_common::debug`]).
+/// Mount it temporarily on a separate route to find out why a subscription fails.
+///
+/// Any POST is accepted, no matter the subscription type. The key comes from
+/// [`Config::get_key`] - if the headers can't be read, the signature isn't computed.
+/// The extractor responds with the report as JSON.
+///
+/// ```
+/// # use axum_eventsub::{Config, DebugReport, VerifyDecodeError};
+/// # struct EventsubConfig;
+/// #
+/// # impl Config<()> for EventsubConfig {
+/// #     type Rejection = VerifyDecodeError;
+/// #
+/// #     fn get_secret(_state: &()) -> &[u8] {
+/// #         b"secret"
+/// #     }
+/// #
+/// #     fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+/// #         error
+/// #     }
+/// # }
+/// async fn debug_handler(report: DebugReport<EventsubConfig>) -> DebugReport<EventsubConfig> {
+///     eprintln!("{:?}", report.report);
+///     report
+/// }
+/// # fn main() {}
+/// ```
+pub struct DebugReport<C> {
+    /// The report of the request.
+    pub report: Report,
+    _config: PhantomData<C>,
+}
+
+impl<C> DebugReport<C> {
+    /// Take the report.
+    #[must_use]
+    pub fn into_inner(self) -> Report {
+        self.report
+    }
+}
+
+#[async_trait::async_trait]
+impl<State, C, B> FromRequest<State, B> for DebugReport<C>
+where
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+    C: Config<State>,
+    State: Send + Sync,
+{
+    type Rejection = C::Rejection;
+
+    async fn from_request(req: Request<B>, state: &State) -> Result<Self, Self::Rejection> {
+        let (parts, body) = req.into_parts();
+        let body = body::read(body, C::max_body_size(), C::read_timeout())
+            .await
+            .map_err(C::convert_error)?;
+
+        // `get_key` needs the metadata, which can only be read from valid headers.
+        let meta = headers::read_eventsub_headers_untyped(&parts.headers)
+            .ok()
+            .map(|parsed| parsed.meta());
+        let key = match meta {
+            Some(meta) => C::get_key(state, &parts, &meta).await,
+            None => None,
+        };
+
+        Ok(Self {
+            report: debug::report(&parts.headers, key.as_ref(), &body),
+            _config: PhantomData,
+        })
+    }
+}
+
+impl<C> IntoResponse for DebugReport<C> {
+    fn into_response(self) -> Response {
+        Json(self.report).into_response()
+    }
+}
@@ -1,25 +1,306 @@
+use crate::extractors::verify;
 use axum::{
     body::HttpBody,
-    extract::{rejection::BytesRejection, FromRequest},
-    http::{Request, StatusCode},
+    extract::FromRequest,
+    http::{request::Parts, Request, StatusCode},
     response::{IntoResponse, Response},
     BoxError,
 };
 use bytes::Bytes;
-pub use eventsub_common::headers::{HeaderType, InvalidHeaders};
-use eventsub_common::{headers, types::EventSubscription, EventsubPayload, MessageType};
-use hmac::{digest::InvalidLength, Hmac, Mac};
-use sha2::Sha256;
-use std::marker::PhantomData;
-
-type HmacSha256 = Hmac<Sha256>;
+pub use eventsub_common::headers::{EventMeta, HeaderType, InvalidHeaderValue, InvalidHeaders};
+use eventsub_common::{
+    decode,
+    error::ErrorContext,
+    secret::HmacKey,
+    types::{EventSubSubscription, EventSubscription},
+    Condition, EventsubPayload, MessageType, Notification, Revocation, Verification,
+};
+use hmac::digest::InvalidLength;
+use std::{fmt, future::Future, marker::PhantomData, time::Duration};
 
-pub struct Data<P, C> {
+/// Extractor for an eventsub event.
+///
+/// This will verify (hash, subscription-type, time) and deserialize the event for you.
+///
+/// You need to provide a [`EventSubscription`] as the type of event you want to receive and a
+/// [`Config`] that provides the secret and converts potential errors to your preferred rejection.
+///
+/// ```
+/// # use axum_eventsub::{Config, VerifyDecodeError, types::channel::ChannelPointsCustomRewardRedemptionAddV1};
+/// # use axum::response::IntoResponse;
+/// # struct EventsubConfig;
+/// #
+/// # impl Config<()> for EventsubConfig {
+/// #     type Rejection = VerifyDecodeError;
+/// #
+/// #     fn get_secret(_state: &()) -> &[u8] {
+/// #         b"secret"
+/// #     }
+/// #
+/// #     fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+/// #         error
+/// #     }
+/// # }
+/// #
+/// async fn eventsub(
+///     data: axum_eventsub::Data<ChannelPointsCustomRewardRedemptionAddV1, EventsubConfig>,
+/// ) -> impl IntoResponse {
+///     // Verifications are answered with the challenge automatically.
+///     data.handle(
+///         |notification| println!("Notification: {:?}", notification.event),
+///         |revocation| println!("Revoked: {:?}", revocation.subscription),
+///     )
+/// }
+/// # fn main() {}
+/// ```
+pub struct Data<P: EventSubscription, C> {
     /// The extracted payload.
-    pub payload: EventsubPayload<P>,
+    pub payload: EventsubPayload<P::Payload>,
+    meta: EventMeta,
+    body: Bytes,
+    responses: Responses,
+    #[cfg(feature = "opentelemetry")]
+    context: opentelemetry::Context,
     _config: PhantomData<C>,
 }
 
+/// Extractor for an eventsub event that doesn't reject the request.
+///
+/// Verification and decoding errors are passed to the handler (as [`Config::Rejection`]),
+/// so it can decide how to respond. `S` is the app state.
+///
+/// ```
+/// # use axum_eventsub::{Config, TryData, VerifyDecodeError, types::channel::ChannelPointsCustomRewardRedemptionAddV1};
+/// # use axum::{http::StatusCode, response::{IntoResponse, Response}};
+/// # struct EventsubConfig;
+/// #
+/// # impl Config<()> for EventsubConfig {
+/// #     type Rejection = VerifyDecodeError;
+/// #
+/// #     fn get_secret(_state: &()) -> &[u8] {
+/// #         b"secret"
+/// #     }
+/// #
+/// #     fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+/// #         error
+/// #     }
+/// # }
+/// #
+/// async fn eventsub(
+///     data: TryData<ChannelPointsCustomRewardRedemptionAddV1, EventsubConfig>,
+/// ) -> Response {
+///     match data {
+///         Ok(data) => data.handle(|_| StatusCode::NO_CONTENT, |_| StatusCode::NO_CONTENT),
+///         Err(e) => {
+///             println!("Rejected eventsub request: {e}");
+///             StatusCode::UNAUTHORIZED.into_response()
+///         }
+///     }
+/// }
+/// # fn main() {}
+/// ```
+pub type TryData<P, C, S = ()> = Result<Data<P, C>, <C as Config<S>>::Rejection>;
+
+impl<P: EventSubscription, C> Data<P, C> {
+    /// The verified body of the request, see [`decode`](eventsub_common::decode#json-backend)
+    /// for how it changes with the `simd-json` feature.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// The extracted payload.
+    ///
+    /// [`EventsubPayload`] is the same type for actix-web and axum (it's defined in
+    /// `eventsub_common`), so handler logic taking it can be shared between both.
+    pub fn payload(&self) -> &EventsubPayload<P::Payload> {
+        &self.payload
+    }
+
+    /// The event of a notification, or [`None`] for verifications and revocations.
+    ///
+    /// For redemptions, read its fields through [`RedemptionEvent`](crate::RedemptionEvent):
+    /// `data.event().map(|redemption| redemption.reward_title())`.
+    pub fn event(&self) -> Option<&P::Payload> {
+        self.payload.event()
+    }
+
+    /// Take the extracted payload, dropping the body and metadata.
+    pub fn into_payload(self) -> EventsubPayload<P::Payload> {
+        self.payload
+    }
+
+    /// Metadata of the message, such as its id and timestamp.
+    pub fn meta(&self) -> &EventMeta {
+        &self.meta
+    }
+
+    /// Split the extracted data into the payload and its metadata.
+    ///
+    /// Use this to move the event into a spawned task without naming the config.
+    pub fn into_parts(self) -> (EventsubPayload<P::Payload>, EventMeta) {
+        (self.payload, self.meta)
+    }
+
+    /// Deserialize the condition of the subscription (e.g. `broadcaster_user_id`).
+    ///
+    /// See [`EventsubPayload::condition`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the condition isn't a valid condition for `P`.
+    pub fn condition(&self) -> Result<P, serde_json::Error> {
+        self.payload.condition()
+    }
+
+    /// The OpenTelemetry context of the delivery, with its span as the active span.
+    ///
+    /// A fresh trace is started for every delivery (see [`eventsub_common::otel`]).
+    /// [`handle`](Self::handle) and [`spawn_handler`](Self::spawn_handler) make it current
+    /// for their callbacks, [`in_otel_context`](Self::in_otel_context) for any other future.
+    /// The span ends when this data and all clones of the context are dropped.
+    #[cfg(feature = "opentelemetry")]
+    pub fn otel_context(&self) -> &opentelemetry::Context {
+        &self.context
+    }
+
+    /// Make the OpenTelemetry context of the delivery current while `future` is polled.
+    ///
+    /// Spans started while processing the event become children of the delivery span.
+    /// Wrap the work of the handler that isn't done in the callbacks of [`handle`](Self::handle)
+    /// or [`spawn_handler`](Self::spawn_handler):
+    ///
+    /// ```
+    /// # use axum_eventsub::{Config, Data, VerifyDecodeError, types::channel::ChannelPointsCustomRewardRedemptionAddV1};
+    /// # use axum::{http::StatusCode, response::Response};
+    /// # struct EventsubConfig;
+    /// #
+    /// # impl Config<()> for EventsubConfig {
+    /// #     type Rejection = VerifyDecodeError;
+    /// #
+    /// #     fn get_secret(_state: &()) -> &[u8] {
+    /// #         b"secret"
+    /// #     }
+    /// #
+    /// #     fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+    /// #         error
+    /// #     }
+    /// # }
+    /// # async fn store(_id: &str) {}
+    /// async fn eventsub(data: Data<ChannelPointsCustomRewardRedemptionAddV1, EventsubConfig>) -> Response {
+    ///     // spans of the database write are children of the delivery span
+    ///     data.in_otel_context(store(&data.meta().message_id)).await;
+    ///     data.handle(|_| StatusCode::NO_CONTENT, |_| StatusCode::NO_CONTENT)
+    /// }
+    /// # fn main() {}
+    /// ```
+    #[cfg(feature = "opentelemetry")]
+    pub fn in_otel_context<F: Future>(&self, future: F) -> opentelemetry::trace::WithContext<F> {
+        opentelemetry::trace::FutureExt::with_context(future, self.context.clone())
+    }
+
+    /// Respond to the payload without matching on it.
+    ///
+    /// Verifications are answered with their challenge.
+    /// Notifications and revocations are passed to `on_notification` and `on_revocation`
+    /// respectively, and their return value is used as the response - unless
+    /// [`Config::auto_ack_revocations`] is set, then revocations are answered with `204 No Content`.
+    pub fn handle<N, R>(
+        self,
+        on_notification: impl FnOnce(Notification<P::Payload>) -> N,
+        on_revocation: impl FnOnce(Revocation) -> R,
+    ) -> Response
+    where
+        N: IntoResponse,
+        R: IntoResponse,
+    {
+        #[cfg(feature = "opentelemetry")]
+        let _guard = self.context.clone().attach();
+        match self.payload {
+            EventsubPayload::Verification(Verification { challenge, .. }) => {
+                challenge.into_response()
+            }
+            EventsubPayload::Notification(notification) => {
+                on_notification(notification).into_response()
+            }
+            EventsubPayload::Revocation(revocation) if self.responses.auto_ack_revocations => {
+                let _ = on_revocation(revocation);
+                StatusCode::NO_CONTENT.into_response()
+            }
+            EventsubPayload::Revocation(revocation) => on_revocation(revocation).into_response(),
+        }
+    }
+}
+
+impl<P, C> Data<P, C>
+where
+    P: EventSubscription + Send + 'static,
+    P::Payload: Send,
+    C: Send + 'static,
+{
+    /// Respond right away and process the event on a separate task.
+    ///
+    /// Twitch expects a response within a few seconds, so slow handlers risk retries
+    /// and revoked subscriptions. Verifications are answered with their challenge and
+    /// aren't passed to `handler`. Notifications are answered with
+    /// [`Config::notification_response`] and revocations with `204 No Content` without
+    /// waiting for `handler`, which runs on the tokio runtime ([`tokio::spawn`]).
+    ///
+    /// The response is already sent when `handler` runs, so it can't report errors to Twitch.
+    /// If it panics, the metadata of the message is passed to [`Config::on_handler_panic`].
+    ///
+    /// ```
+    /// # use axum_eventsub::{Config, Data, VerifyDecodeError, types::channel::ChannelPointsCustomRewardRedemptionAddV1};
+    /// # use axum::response::Response;
+    /// # struct EventsubConfig;
+    /// #
+    /// # impl Config<()> for EventsubConfig {
+    /// #     type Rejection = VerifyDecodeError;
+    /// #
+    /// #     fn get_secret(_state: &()) -> &[u8] {
+    /// #         b"secret"
+    /// #     }
+    /// #
+    /// #     fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+    /// #         error
+    /// #     }
+    /// # }
+    /// async fn eventsub(data: Data<ChannelPointsCustomRewardRedemptionAddV1, EventsubConfig>) -> Response {
+    ///     data.spawn_handler(|data| async move {
+    ///         // something slow, like writing to a database
+    ///         println!("Handled {}", data.meta().message_id);
+    ///     })
+    /// }
+    /// # fn main() {}
+    /// ```
+    pub fn spawn_handler<F, Fut>(self, handler: F) -> Response
+    where
+        F: FnOnce(Self) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        if let EventsubPayload::Verification(Verification { challenge, .. }) = self.payload {
+            return challenge.into_response();
+        }
+        let meta = self.meta.clone();
+        let responses = self.responses;
+        let response = match self.payload {
+            EventsubPayload::Notification(_) => responses.notification(),
+            _ => StatusCode::NO_CONTENT.into_response(),
+        };
+        #[cfg(feature = "opentelemetry")]
+        let context = self.context.clone();
+        let handler = async move { handler(self).await };
+        #[cfg(feature = "opentelemetry")]
+        let handler = opentelemetry::trace::FutureExt::with_context(handler, context);
+        let task = tokio::spawn(handler);
+        tokio::spawn(async move {
+            if task.await.is_err_and(|e| e.is_panic()) {
+                (responses.on_handler_panic)(&meta);
+            }
+        });
+        response
+    }
+}
+
 /// Configuration for verifying and decoding eventsub payloads.
 ///
 /// The config is generic over the app state (`S`).
@@ -30,13 +311,429 @@ pub trait Config<S> {
     type Rejection: IntoResponse;
 
     /// Get the eventsub secret from the app state.
+    ///
+    /// This is the key of every subscription, unless [`Config::get_key`] is overridden.
     fn get_secret(state: &S) -> &[u8];
 
+    /// Get the key to verify a message with.
+    ///
+    /// Override this if subscriptions were created with different secrets (e.g. one per
+    /// endpoint with [`PathScopedSecret`](crate::PathScopedSecret) or one per subscription
+    /// type in [`EventMeta::subscription_type`]), if the secret has to be fetched (e.g. from a
+    /// database) or if you don't store the raw secret, but derive it when needed (e.g. with
+    /// HKDF from a master key). The key material is only used to initialize the HMAC and
+    /// dropped right after. Resolve to [`None`] to reject the request with
+    /// [`VerifyDecodeError::NoHmacKey`].
+    ///
+    /// This is used by all extractors, including `DebugReport`.
+    ///
+    /// Defaults to [`Config::get_secret`].
+    ///
+    /// ```
+    /// # use std::future::Future;
+    /// # use axum::http::request::Parts;
+    /// # use axum_eventsub::{Config, EventMeta, HmacKey, PathScopedSecret, VerifyDecodeError};
+    /// struct AppState {
+    ///     secret: Vec<u8>,
+    ///     endpoints: PathScopedSecret,
+    /// }
+    ///
+    /// struct EventsubConfig;
+    ///
+    /// impl Config<AppState> for EventsubConfig {
+    ///     type Rejection = VerifyDecodeError;
+    ///
+    ///     fn get_secret(state: &AppState) -> &[u8] {
+    ///         &state.secret
+    ///     }
+    ///
+    ///     fn get_key<'a>(
+    ///         state: &'a AppState,
+    ///         parts: &Parts,
+    ///         _meta: &EventMeta,
+    ///     ) -> impl Future<Output = Option<impl HmacKey + Send + 'a>> + Send {
+    ///         // `/eventsub/alice` uses the secret of "alice", other endpoints the shared one
+    ///         let secret = state.endpoints.for_path(parts.uri.path());
+    ///         std::future::ready(Some(secret.unwrap_or(Self::get_secret(state))))
+    ///     }
+    ///
+    ///     fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+    ///         error
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Keys that are derived or fetched can be returned by value:
+    ///
+    /// ```
+    /// # use std::{borrow::Cow, future::Future};
+    /// # use axum::http::request::Parts;
+    /// # use axum_eventsub::{Config, EventMeta, HmacKey, VerifyDecodeError};
+    /// # fn hkdf_expand(master: &[u8], info: &[u8]) -> Vec<u8> { [master, info].concat() }
+    /// struct DerivedKey<'a>(&'a [u8], String);
+    ///
+    /// impl HmacKey for DerivedKey<'_> {
+    ///     fn key_material(&self) -> Cow<'_, [u8]> {
+    ///         Cow::Owned(hkdf_expand(self.0, self.1.as_bytes()))
+    ///     }
+    /// }
+    ///
+    /// struct EventsubConfig;
+    ///
+    /// impl Config<Vec<u8>> for EventsubConfig {
+    ///     type Rejection = VerifyDecodeError;
+    ///
+    ///     /// The master key.
+    ///     fn get_secret(state: &Vec<u8>) -> &[u8] {
+    ///         state
+    ///     }
+    ///
+    ///     fn get_key<'a>(
+    ///         state: &'a Vec<u8>,
+    ///         _parts: &Parts,
+    ///         meta: &EventMeta,
+    ///     ) -> impl Future<Output = Option<impl HmacKey + Send + 'a>> + Send {
+    ///         // one key per subscription type
+    ///         let info = meta.subscription_type.clone();
+    ///         std::future::ready(Some(DerivedKey(Self::get_secret(state), info)))
+    ///     }
+    ///
+    ///     fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+    ///         error
+    ///     }
+    /// }
+    /// ```
+    fn get_key<'a>(
+        state: &'a S,
+        parts: &Parts,
+        meta: &EventMeta,
+    ) -> impl Future<Output = Option<impl HmacKey + Send + 'a>> + Send {
+        let _ = (parts, meta);
+        std::future::ready(Some(Self::get_secret(state)))
+    }
+
+    /// Called with the subscription of every request that was verified and decoded,
+    /// before the handler runs.
+    ///
+    /// This runs for verifications, notifications and revocations - after
+    /// [`Config::is_subscription_allowed`]. Use it to record metrics or the last time a
+    /// subscription was seen in a single place.
+    ///
+    /// Defaults to doing nothing.
+    fn on_verified(state: &S, subscription: &EventSubSubscription, message_type: MessageType) {
+        let _ = (state, subscription, message_type);
+    }
+
+    /// Called with every revocation that was verified and decoded, before the handler runs.
+    ///
+    /// Use it to clean up after a revoked subscription (e.g. resubscribe or remove it
+    /// from the allow-list) in a single place. It runs right after [`Config::on_verified`],
+    /// so it's called again if Twitch retries the revocation.
+    ///
+    /// Defaults to doing nothing.
+    fn on_revocation(state: &S, revocation: &Revocation) {
+        let _ = (state, revocation);
+    }
+
+    /// Respond to revocations with `204 No Content` in [`Data::handle`], ignoring
+    /// the response of `on_revocation`.
+    ///
+    /// The revocation handler still runs, but it can't make Twitch retry the revocation
+    /// by failing. Handle failures in [`Config::on_revocation`] or the handler instead.
+    ///
+    /// Defaults to `false` - the handler controls the response.
+    #[must_use]
+    fn auto_ack_revocations() -> bool {
+        false
+    }
+
+    /// The response to notifications that are answered without the handler.
+    ///
+    /// This is used by [`Data::spawn_handler`], [`AnyData::handle`](crate::AnyData::handle)
+    /// and [`BatchData::response`](crate::BatchData::response), for example to send a small
+    /// JSON acknowledgement to a monitoring proxy. Twitch retries notifications that aren't
+    /// answered with a `2xx` status, so responses outside of that range are replaced with
+    /// `204 No Content`. Verifications are still answered with their challenge.
+    ///
+    /// Defaults to `204 No Content`.
+    #[must_use]
+    fn notification_response() -> Response {
+        StatusCode::NO_CONTENT.into_response()
+    }
+
+    /// Called when a handler started by [`Data::spawn_handler`] panicked.
+    ///
+    /// The response was already sent at that point, so this is the place to log or count
+    /// the failure with the subscription type and message id of `meta`. The panic message
+    /// itself is reported by the panic hook.
+    ///
+    /// Defaults to doing nothing.
+    fn on_handler_panic(meta: &EventMeta) {
+        let _ = meta;
+    }
+
+    /// Maximum size of the body in bytes.
+    ///
+    /// Larger bodies are rejected with [`VerifyDecodeError::RequestTooLarge`]. The body is
+    /// read by the extractors themselves, so axum's `DefaultBodyLimit` doesn't apply to it -
+    /// override this instead of adding the layer.
+    ///
+    /// Defaults to [`MAX_BODY_SIZE`](eventsub_common::body::MAX_BODY_SIZE) (10MB).
+    #[must_use]
+    fn max_body_size() -> usize {
+        eventsub_common::body::MAX_BODY_SIZE
+    }
+
+    /// Maximum time to wait for the full payload to arrive.
+    ///
+    /// If the body isn't received in time, the request is rejected with
+    /// [`VerifyDecodeError::ReadTimeout`]. This protects the server from clients
+    /// trickling in the body. Return [`None`] to disable the timeout.
+    ///
+    /// Defaults to 30 seconds.
+    #[must_use]
+    fn read_timeout() -> Option<Duration> {
+        Some(Duration::from_secs(30))
+    }
+
+    /// Maximum number of retries after which a message is still handled.
+    ///
+    /// Messages Twitch already retried more often (`Twitch-Eventsub-Message-Retry`) likely
+    /// fail again. They're rejected with [`VerifyDecodeError::RetryLimitExceeded`] once the
+    /// signature is checked, which responds with `204 No Content`, so Twitch stops retrying.
+    /// Verifications are never skipped, as the subscription wouldn't be confirmed otherwise.
+    ///
+    /// Defaults to [`None`] (no limit).
+    #[must_use]
+    fn max_retries() -> Option<u32> {
+        None
+    }
+
+    /// Check if events of the subscription with the id `subscription_id` should be accepted.
+    ///
+    /// This is checked after the payload is verified and decoded - for all message types,
+    /// so add the id of a new subscription before Twitch sends the verification.
+    /// Rejected requests fail with [`VerifyDecodeError::SubscriptionNotAllowed`].
+    ///
+    /// An allow-list of the subscriptions you created defends against someone reusing
+    /// your callback URL and secret for their own subscriptions.
+    /// It doesn't help if the secret is compromised, as the attacker can forge any payload then.
+    ///
+    /// Defaults to accepting all subscriptions.
+    #[must_use]
+    fn is_subscription_allowed(state: &S, subscription_id: &str) -> bool {
+        let _ = (state, subscription_id);
+        true
+    }
+
+    /// Check if events with the subscription condition `condition` should be accepted.
+    ///
+    /// The condition holds fields like `broadcaster_user_id`, which aren't sent as headers.
+    /// That's why this can't be checked before the body is read: it runs after the payload
+    /// is verified and decoded, right after [`Config::is_subscription_allowed`].
+    /// Rejected requests fail with [`VerifyDecodeError::ConditionNotAccepted`], which is
+    /// answered with `204 No Content` and never reaches the handler. So are requests whose
+    /// condition isn't an object of strings, without calling this.
+    ///
+    /// Twitch retries deliveries that aren't answered with a `2xx` status and revokes the
+    /// subscription once too many failed, so rejected events are acknowledged and dropped.
+    /// Verifications are dropped the same way, without their challenge, so subscriptions
+    /// with a rejected condition are never confirmed. If [`Config::convert_error`] answers
+    /// with another status, expect the subscription to be revoked.
+    ///
+    /// Use it to give each broadcaster its own config on a shared endpoint.
+    /// The body is consumed by then, so a rejected request doesn't fall through
+    /// to another handler - to dispatch on the broadcaster in a single handler,
+    /// use [`Data::condition`] instead.
+    ///
+    /// ```
+    /// # use axum_eventsub::{Condition, Config, VerifyDecodeError};
+    /// struct ChannelConfig;
+    ///
+    /// impl Config<()> for ChannelConfig {
+    ///     type Rejection = VerifyDecodeError;
+    ///
+    ///     fn get_secret(_state: &()) -> &[u8] {
+    ///         b"secretsecret"
+    ///     }
+    ///
+    ///     fn accept_condition(_state: &(), condition: &Condition<'_>) -> bool {
+    ///         condition.broadcaster_user_id == Some("53946574")
+    ///     }
+    ///
+    ///     fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+    ///         error
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Defaults to accepting all conditions.
+    #[must_use]
+    fn accept_condition(state: &S, condition: &Condition<'_>) -> bool {
+        let _ = (state, condition);
+        true
+    }
+
+    /// Reject requests whose `Content-Type` isn't `application/json` with
+    /// [`VerifyDecodeError::BadContentType`].
+    ///
+    /// Twitch always sends JSON, so this catches misrouted requests before the body is hashed.
+    /// Disabled by default, as some proxies change the content type.
+    #[must_use]
+    fn require_json_content_type() -> bool {
+        false
+    }
+
+    /// Maximum nesting depth of arrays and objects in the payload.
+    ///
+    /// The depth is counted while the payload is deserialized, and payloads nested deeper
+    /// than this are rejected with [`VerifyDecodeError::Serde`]. `serde_json` always rejects
+    /// payloads nested deeper than 128 levels, so larger values have no effect.
+    ///
+    /// Defaults to [`None`] (only `serde_json`'s limit applies).
+    #[must_use]
+    fn max_json_depth() -> Option<usize> {
+        None
+    }
+
     /// Convert the [`VerifyDecodeError`] into a custom error.
     ///
     /// If you want to return a custom rejection (for example an error wrapped in JSON),
     /// then you should construct it here. Otherwise, return the given error.
+    /// To only change the status codes, return a [`ConfiguredError`].
     fn convert_error(error: VerifyDecodeError) -> Self::Rejection;
+
+    /// Convert a [`VerifyDecodeError`] that happened after the headers were read.
+    ///
+    /// The [`ErrorContext`] carries the subscription type and message id of the request,
+    /// override this to include them in logs:
+    ///
+    /// ```
+    /// # use axum_eventsub::{Config, ErrorContext, VerifyDecodeError};
+    /// struct EventsubConfig;
+    ///
+    /// impl Config<()> for EventsubConfig {
+    ///     type Rejection = VerifyDecodeError;
+    ///
+    ///     fn get_secret(_state: &()) -> &[u8] {
+    ///         b"secretsecret"
+    ///     }
+    ///
+    ///     fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+    ///         error
+    ///     }
+    ///
+    ///     fn convert_error_with_context(error: ErrorContext<VerifyDecodeError>) -> Self::Rejection {
+    ///         // e.g. "The provided signature wasn't expected (channel.follow id=abc)"
+    ///         eprintln!("Rejected eventsub request: {error}");
+    ///         Self::convert_error(error.into_inner())
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Defaults to [`Config::convert_error`], dropping the context.
+    fn convert_error_with_context(error: ErrorContext<VerifyDecodeError>) -> Self::Rejection {
+        Self::convert_error(error.into_inner())
+    }
+}
+
+/// A [`VerifyDecodeError`] responding with a custom status.
+///
+/// Every rejection goes through [`Config::convert_error`], so returning this from there
+/// changes the status of all of them. The body is the same text as the one of
+/// [`VerifyDecodeError`].
+///
+/// ```
+/// # use axum::http::StatusCode;
+/// # use axum_eventsub::{Config, ConfiguredError, VerifyDecodeError};
+/// struct EventsubConfig;
+///
+/// impl Config<()> for EventsubConfig {
+///     type Rejection = ConfiguredError;
+///
+///     fn get_secret(_state: &()) -> &[u8] {
+///         b"secretsecret"
+///     }
+///
+///     fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+///         let status = match error {
+///             VerifyDecodeError::SignatureMismatch => StatusCode::UNAUTHORIZED,
+///             VerifyDecodeError::Serde(_) => StatusCode::UNPROCESSABLE_ENTITY,
+///             _ => error.status_code(),
+///         };
+///         ConfiguredError::new(error, status)
+///     }
+/// }
+/// ```
+pub struct ConfiguredError {
+    error: VerifyDecodeError,
+    status: StatusCode,
+}
+
+impl ConfiguredError {
+    /// Wrap the error, responding with `status`.
+    ///
+    /// Success statuses respond without a body.
+    #[must_use]
+    pub fn new(error: VerifyDecodeError, status: StatusCode) -> Self {
+        Self { error, status }
+    }
+
+    /// The wrapped error.
+    #[must_use]
+    pub fn error(&self) -> &VerifyDecodeError {
+        &self.error
+    }
+
+    /// Unwrap the error.
+    #[must_use]
+    pub fn into_inner(self) -> VerifyDecodeError {
+        self.error
+    }
+}
+
+impl From<VerifyDecodeError> for ConfiguredError {
+    /// Wrap the error, keeping its default status.
+    fn from(error: VerifyDecodeError) -> Self {
+        let status = error.status_code();
+        Self::new(error, status)
+    }
+}
+
+impl fmt::Debug for ConfiguredError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ConfiguredError")
+            .field(&self.error)
+            .field(&self.status)
+            .finish()
+    }
+}
+
+impl fmt::Display for ConfiguredError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl std::error::Error for ConfiguredError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
+impl IntoResponse for ConfiguredError {
+    fn into_response(self) -> Response {
+        error_response(self.status, &self.error)
+    }
+}
+
+/// Respond with the error as text, unless the status is a success.
+fn error_response(status: StatusCode, error: &VerifyDecodeError) -> Response {
+    if status.is_success() {
+        status.into_response()
+    } else {
+        (status, error.to_string()).into_response()
+    }
 }
 
 /// Errors when verifying and decoding the eventsub payload.
@@ -48,21 +745,47 @@ pub enum VerifyDecodeError {
     /// The provided signature was incorrect - it didn't match the computed one.
     #[error("The provided signature wasn't expected")]
     SignatureMismatch,
-    /// The payload was too large (>10MB).
-    #[error("The request was too large (> 10MB)")]
+    /// The payload was larger than [`Config::max_body_size`].
+    #[error("The request was too large")]
     RequestTooLarge,
-    /// actix-web couldn't parse the payload.
+    /// The body couldn't be read.
+    ///
+    /// This used to be an [`axum::extract::rejection::BytesRejection`], when the body was read
+    /// through `Bytes::from_request`. It's the error of the body now, which is a breaking change
+    /// for code matching on the rejection.
     #[error("Payload error: {0}")]
-    PayloadError(BytesRejection),
+    PayloadError(BoxError),
+    /// The payload wasn't received within [`Config::read_timeout`].
+    #[error("Timed out while reading the payload")]
+    ReadTimeout,
     /// serde_json couldn't deserialize the payload.
     #[error("JSON Deserialization error: {0}")]
     Serde(serde_json::Error),
+    /// No HMAC key was provided - [`Config::get_key`] resolved to [`None`].
+    #[error("No HMAC key provided")]
+    NoHmacKey,
     /// The HMAC key was too short - [`Config::get_secret`] returned a slice that was too short.
     #[error("Bad secret key")]
     HmacInit(InvalidLength),
     /// The subscription version didn't match the expected one.
     #[error("Version mismatch - expected {0}")]
     VersionMismatch(&'static str),
+    /// The subscription isn't accepted - [`Config::is_subscription_allowed`] returned `false`.
+    #[error("Events of this subscription aren't accepted")]
+    SubscriptionNotAllowed,
+    /// The subscription condition isn't accepted - [`Config::accept_condition`] returned `false`.
+    ///
+    /// This is answered with `204 No Content` by default, so Twitch doesn't retry the event.
+    #[error("Events with this condition aren't accepted")]
+    ConditionNotAccepted,
+    /// The `Content-Type` wasn't `application/json` - see [`Config::require_json_content_type`].
+    #[error("Expected an application/json body")]
+    BadContentType,
+    /// Twitch retried the message more often than [`Config::max_retries`] allows.
+    ///
+    /// This responds with `204 No Content` without a body, so Twitch stops retrying.
+    #[error("The message was retried too often")]
+    RetryLimitExceeded,
 }
 
 #[async_trait::async_trait]
@@ -78,64 +801,99 @@ where
     type Rejection = C::Rejection;
 
     async fn from_request(req: Request<B>, state: &State) -> Result<Self, Self::Rejection> {
-        let headers = headers::read_eventsub_headers::<_, Sub>(req.headers())
-            .map_err(|e| C::convert_error(VerifyDecodeError::Headers(e)))?;
-        let mut mac = init_mac::<State, C>(state, headers.id_bytes, headers.timestamp_bytes)
-            .map_err(C::convert_error)?;
-        let payload_headers = headers.payload;
-        let payload = Bytes::from_request(req, state)
-            .await
-            .map_err(|e| C::convert_error(VerifyDecodeError::PayloadError(e)))?;
-        mac.update(&payload);
-
-        if mac.verify_slice(&payload_headers.signature).is_ok() {
-            match payload_headers.message_type {
-                MessageType::Verification => {
-                    serde_json::from_slice(&payload).map(EventsubPayload::Verification)
-                }
-                MessageType::Revocation => {
-                    serde_json::from_slice(&payload).map(EventsubPayload::Revocation)
-                }
-                MessageType::Notification => {
-                    serde_json::from_slice(&payload).map(EventsubPayload::Notification)
-                }
-            }
-            .map(|payload| Data {
-                payload,
-                _config: PhantomData,
-            })
-            .map_err(|e| C::convert_error(VerifyDecodeError::Serde(e)))
-        } else {
-            Err(C::convert_error(VerifyDecodeError::SignatureMismatch))
-        }
+        let (parts, body) = req.into_parts();
+        let pending = verify::start_for::<Sub, C, State>(&parts, state).await?;
+        let (meta, mut body) = verify::read_verified::<C, State, B>(&parts, body, pending).await?;
+        let payload = decode::decode_payload_in_place::<Sub>(
+            meta.message_type,
+            &mut body,
+            C::max_json_depth(),
+        )
+        .map_err(|e| verify::in_context::<C, State>(&meta, VerifyDecodeError::Serde(e)))?;
+        verify::accept::<C, State>(state, &meta, payload.subscription())?;
+        Ok(Data {
+            payload,
+            #[cfg(feature = "opentelemetry")]
+            context: eventsub_common::otel::delivery_context(&meta),
+            meta,
+            body: body.freeze(),
+            responses: Responses::of::<C, State>(),
+            _config: PhantomData,
+        })
     }
 }
 
-fn init_mac<S, T: Config<S>>(
-    state: &S,
-    id_bytes: &[u8],
-    timestamp_bytes: &[u8],
-) -> Result<HmacSha256, VerifyDecodeError> {
-    let mut mac =
-        HmacSha256::new_from_slice(T::get_secret(state)).map_err(VerifyDecodeError::HmacInit)?;
-    mac.update(id_bytes);
-    mac.update(timestamp_bytes);
+/// The settings of a [`Config`] for answering deliveries, read when the request is extracted.
+///
+/// The extractors keep these instead of bounds on the config, as the state `S` isn't known
+/// once the handler responds.
+#[derive(Clone, Copy)]
+pub(crate) struct Responses {
+    /// [`Config::auto_ack_revocations`]
+    pub(crate) auto_ack_revocations: bool,
+    /// [`Config::on_handler_panic`]
+    pub(crate) on_handler_panic: fn(&EventMeta),
+    notification_response: fn() -> Response,
+}
+
+impl Responses {
+    pub(crate) fn of<C: Config<S>, S>() -> Self {
+        Self {
+            auto_ack_revocations: C::auto_ack_revocations(),
+            on_handler_panic: C::on_handler_panic,
+            notification_response: C::notification_response,
+        }
+    }
 
-    Ok(mac)
+    /// [`Config::notification_response`], if it's in the `2xx` range.
+    pub(crate) fn notification(&self) -> Response {
+        let response = (self.notification_response)();
+        if response.status().is_success() {
+            response
+        } else {
+            StatusCode::NO_CONTENT.into_response()
+        }
+    }
 }
 
-impl IntoResponse for VerifyDecodeError {
-    fn into_response(self) -> Response {
-        let status = match &self {
+impl VerifyDecodeError {
+    /// The HTTP status this error responds with.
+    ///
+    /// To respond with another status, return a [`ConfiguredError`] from
+    /// [`Config::convert_error`].
+    ///
+    /// | Error | Status |
+    /// |-------|--------|
+    /// | [`RetryLimitExceeded`](VerifyDecodeError::RetryLimitExceeded), [`ConditionNotAccepted`](VerifyDecodeError::ConditionNotAccepted) | `204 No Content` |
+    /// | [`SubscriptionNotAllowed`](VerifyDecodeError::SubscriptionNotAllowed) | `403 Forbidden` |
+    /// | [`ReadTimeout`](VerifyDecodeError::ReadTimeout) | `408 Request Timeout` |
+    /// | [`BadContentType`](VerifyDecodeError::BadContentType) | `415 Unsupported Media Type` |
+    /// | [`NoHmacKey`](VerifyDecodeError::NoHmacKey), [`HmacInit`](VerifyDecodeError::HmacInit) | `500 Internal Server Error` |
+    /// | Everything else | `400 Bad Request` |
+    #[must_use]
+    pub fn status_code(&self) -> StatusCode {
+        match self {
             VerifyDecodeError::Headers(_)
             | VerifyDecodeError::SignatureMismatch
             | VerifyDecodeError::RequestTooLarge
             | VerifyDecodeError::PayloadError(_)
             | VerifyDecodeError::Serde(_)
             | VerifyDecodeError::VersionMismatch(_) => StatusCode::BAD_REQUEST,
-            VerifyDecodeError::HmacInit(_) => StatusCode::INTERNAL_SERVER_ERROR,
-        };
+            VerifyDecodeError::RetryLimitExceeded | VerifyDecodeError::ConditionNotAccepted => {
+                StatusCode::NO_CONTENT
+            }
+            VerifyDecodeError::SubscriptionNotAllowed => StatusCode::FORBIDDEN,
+            VerifyDecodeError::ReadTimeout => StatusCode::REQUEST_TIMEOUT,
+            VerifyDecodeError::BadContentType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            VerifyDecodeError::NoHmacKey | VerifyDecodeError::HmacInit(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
 
-        (status, self.to_string()).into_response()
+impl IntoResponse for VerifyDecodeError {
+    fn into_response(self) -> Response {
+        error_response(self.status_code(), &self)
     }
 }
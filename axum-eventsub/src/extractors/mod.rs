@@ -1 +1,8 @@
+pub mod any;
+pub mod batch;
+mod body;
+#[cfg(feature = "debug-report")]
+pub mod debug;
 pub mod eventsub;
+pub mod raw;
+mod verify;
@@ -0,0 +1,161 @@
+use crate::extractors::{
+    eventsub::{Config, VerifyDecodeError},
+    verify,
+};
+use axum::{body::HttpBody, extract::FromRequest, http::Request, BoxError};
+use bytes::Bytes;
+use eventsub_common::{
+    decode,
+    headers::EventMeta,
+    types::{EventSubSubscription, EventSubscription},
+    EventsubPayload,
+};
+use serde::Deserialize;
+use std::marker::PhantomData;
+
+/// Extractor for a verified eventsub event that isn't decoded yet.
+///
+/// This checks the request like [`Data`](crate::Data), but only reads the subscription at the
+/// start of the body, which the hooks of [`Config`] need. The event is deserialized by
+/// [`payload`](Self::payload) as any type borrowing from the body, such as the mirrors in
+/// `borrowed`, so the body is parsed once and strings aren't copied.
+///
+/// If only the [subscription id](Self::subscription_id) is needed, e.g. for deduplication
+/// or logging, the event is never read at all.
+///
+/// Verifications still have to be answered with their challenge.
+///
+/// ```
+/// # use axum_eventsub::{Config, EventsubPayload, RawData, VerifyDecodeError, types::channel::ChannelPointsCustomRewardRedemptionAddV1};
+/// # use axum::{http::StatusCode, response::{IntoResponse, Response}};
+/// # use std::borrow::Cow;
+/// # struct EventsubConfig;
+/// #
+/// # impl Config<()> for EventsubConfig {
+/// #     type Rejection = VerifyDecodeError;
+/// #
+/// #     fn get_secret(_state: &()) -> &[u8] {
+/// #         b"secret"
+/// #     }
+/// #
+/// #     fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+/// #         error
+/// #     }
+/// # }
+/// #[derive(serde::Deserialize)]
+/// struct Redemption<'a> {
+///     #[serde(borrow)]
+///     user_login: Cow<'a, str>,
+/// }
+///
+/// async fn event_handler(
+///     event: RawData<ChannelPointsCustomRewardRedemptionAddV1, EventsubConfig>,
+/// ) -> Response {
+///     match event.payload::<Redemption<'_>>() {
+///         Ok(EventsubPayload::Verification(verification)) => {
+///             verification.challenge.into_response()
+///         }
+///         Ok(EventsubPayload::Notification(notification)) => {
+///             println!("Redeemed by {}", notification.event.user_login);
+///             StatusCode::NO_CONTENT.into_response()
+///         }
+///         Ok(EventsubPayload::Revocation(_)) => StatusCode::NO_CONTENT.into_response(),
+///         Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+///     }
+/// }
+/// # fn main() {}
+/// ```
+pub struct RawData<P, C> {
+    subscription: EventSubSubscription,
+    meta: EventMeta,
+    body: Bytes,
+    #[cfg(feature = "opentelemetry")]
+    context: opentelemetry::Context,
+    max_depth: Option<usize>,
+    _config: PhantomData<(P, C)>,
+}
+
+impl<P, C> RawData<P, C> {
+    /// The verified body of the request.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Metadata of the message, such as its id and timestamp.
+    pub fn meta(&self) -> &EventMeta {
+        &self.meta
+    }
+
+    /// The subscription this message was sent for.
+    pub fn subscription(&self) -> &EventSubSubscription {
+        &self.subscription
+    }
+
+    /// The id of the subscription this message was sent for.
+    pub fn subscription_id(&self) -> &str {
+        self.subscription.id.as_str()
+    }
+
+    /// The OpenTelemetry context of the delivery, with its span as the active span.
+    ///
+    /// See [`Data::otel_context`](crate::Data::otel_context).
+    #[cfg(feature = "opentelemetry")]
+    pub fn otel_context(&self) -> &opentelemetry::Context {
+        &self.context
+    }
+
+    /// Make the OpenTelemetry context of the delivery current while `future` is polled.
+    ///
+    /// See [`Data::in_otel_context`](crate::Data::in_otel_context).
+    #[cfg(feature = "opentelemetry")]
+    pub fn in_otel_context<F: std::future::Future>(
+        &self,
+        future: F,
+    ) -> opentelemetry::trace::WithContext<F> {
+        opentelemetry::trace::FutureExt::with_context(future, self.context.clone())
+    }
+
+    /// Deserialize the payload, borrowing the event from the [body](Self::body).
+    ///
+    /// The nesting is limited by [`Config::max_json_depth`] like in [`Data`](crate::Data).
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the body isn't a valid payload with an event of type `E`.
+    pub fn payload<'a, E: Deserialize<'a>>(
+        &'a self,
+    ) -> Result<EventsubPayload<E>, serde_json::Error> {
+        decode::decode_borrowed(self.meta.message_type, &self.body, self.max_depth)
+    }
+}
+
+#[async_trait::async_trait]
+impl<State, Sub, C, B> FromRequest<State, B> for RawData<Sub, C>
+where
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+    C: Config<State>,
+    Sub: EventSubscription,
+    State: Send + Sync,
+{
+    type Rejection = C::Rejection;
+
+    async fn from_request(req: Request<B>, state: &State) -> Result<Self, Self::Rejection> {
+        let (parts, body) = req.into_parts();
+        let pending = verify::start_for::<Sub, C, State>(&parts, state).await?;
+        let (meta, body) = verify::read_verified::<C, State, B>(&parts, body, pending).await?;
+        let subscription = decode::leading_subscription(&body)
+            .map_err(|e| verify::in_context::<C, State>(&meta, VerifyDecodeError::Serde(e)))?;
+        verify::accept::<C, State>(state, &meta, &subscription)?;
+        Ok(Self {
+            subscription,
+            #[cfg(feature = "opentelemetry")]
+            context: eventsub_common::otel::delivery_context(&meta),
+            meta,
+            body: body.freeze(),
+            max_depth: C::max_json_depth(),
+            _config: PhantomData,
+        })
+    }
+}
@@ -0,0 +1,164 @@
+//! The verification steps shared by all extractors.
+//!
+//! Every extractor runs the same pipeline: [`start_for`] or [`start_any`] read the headers
+//! and set up the HMAC before the body is touched, [`read_verified`] reads the body and
+//! checks its signature and then the retries of the message, and [`accept`] runs the hooks
+//! of [`Config`] for the subscription of the verified payload. New checks go here, so they
+//! apply to every extractor.
+
+use crate::extractors::{
+    body,
+    eventsub::{Config, VerifyDecodeError},
+};
+use axum::{
+    body::HttpBody,
+    http::{request::Parts, HeaderMap},
+    BoxError,
+};
+use bytes::BytesMut;
+use eventsub_common::{
+    error::ErrorContext,
+    headers::{self, EventMeta, InvalidHeaders, ParsedHeaders},
+    secret::HmacKey,
+    types::{EventSubSubscription, EventSubscription},
+    verify::StreamingVerifier,
+    Condition, MessageType, Revocation,
+};
+
+/// A request whose headers were read, with the body still to be verified.
+pub(crate) struct Pending {
+    pub(crate) meta: EventMeta,
+    pub(crate) verifier: StreamingVerifier,
+}
+
+/// Start verifying a request for the subscription `P`.
+pub(crate) async fn start_for<P, C, S>(parts: &Parts, state: &S) -> Result<Pending, C::Rejection>
+where
+    P: EventSubscription,
+    C: Config<S>,
+{
+    let parsed = read_headers::<C, S>(parts, headers::read_eventsub_headers::<_, P>)?;
+    let meta = parsed.meta();
+    let key = C::get_key(state, parts, &meta).await;
+    let verifier = new_verifier::<C, S>(&parsed, &meta, key)?;
+    Ok(Pending { meta, verifier })
+}
+
+/// Start verifying a request for any subscription.
+pub(crate) async fn start_any<C: Config<S>, S>(
+    parts: &Parts,
+    state: &S,
+) -> Result<Pending, C::Rejection> {
+    let parsed = read_headers::<C, S>(parts, headers::read_eventsub_headers_untyped)?;
+    let meta = parsed.meta();
+    let key = C::get_key(state, parts, &meta).await;
+    let verifier = new_verifier::<C, S>(&parsed, &meta, key)?;
+    Ok(Pending { meta, verifier })
+}
+
+fn read_headers<'a, C: Config<S>, S>(
+    parts: &'a Parts,
+    read: fn(&HeaderMap) -> Result<ParsedHeaders<'_>, InvalidHeaders>,
+) -> Result<ParsedHeaders<'a>, C::Rejection> {
+    if C::require_json_content_type() && !headers::is_json_content_type(&parts.headers) {
+        return Err(C::convert_error(VerifyDecodeError::BadContentType));
+    }
+    read(&parts.headers).map_err(|e| C::convert_error(VerifyDecodeError::Headers(e)))
+}
+
+fn new_verifier<C: Config<S>, S>(
+    parsed: &ParsedHeaders<'_>,
+    meta: &EventMeta,
+    key: Option<impl HmacKey>,
+) -> Result<StreamingVerifier, C::Rejection> {
+    let key = key.ok_or_else(|| in_context::<C, S>(meta, VerifyDecodeError::NoHmacKey))?;
+    StreamingVerifier::new(&key, parsed)
+        .map_err(|e| in_context::<C, S>(meta, VerifyDecodeError::HmacInit(e)))
+}
+
+/// Read the complete body and check its signature, then [`Config::max_retries`].
+///
+/// The retry header isn't signed, so it's only trusted once the signature matched.
+/// Verifications are never skipped, otherwise the subscription wouldn't be confirmed.
+pub(crate) async fn read_verified<C: Config<S>, S, B>(
+    parts: &Parts,
+    body: B,
+    Pending { meta, mut verifier }: Pending,
+) -> Result<(EventMeta, BytesMut), C::Rejection>
+where
+    B: HttpBody,
+    B::Error: Into<BoxError>,
+{
+    let body = body::read(body, C::max_body_size(), C::read_timeout())
+        .await
+        .map_err(|e| in_context::<C, S>(&meta, e))?;
+    verifier.update(&body);
+    verifier
+        .finish()
+        .map_err(|_| in_context::<C, S>(&meta, VerifyDecodeError::SignatureMismatch))?;
+    if meta.message_type != MessageType::Verification
+        && headers::retries_exceeded(&parts.headers, C::max_retries())
+    {
+        return Err(in_context::<C, S>(
+            &meta,
+            VerifyDecodeError::RetryLimitExceeded,
+        ));
+    }
+    Ok((meta, body))
+}
+
+/// Check if the subscription of a verified payload is accepted
+/// ([`Config::is_subscription_allowed`] and [`Config::accept_condition`]).
+pub(crate) fn check<C: Config<S>, S>(
+    state: &S,
+    subscription: &EventSubSubscription,
+) -> Result<(), VerifyDecodeError> {
+    if !C::is_subscription_allowed(state, &subscription.id) {
+        return Err(VerifyDecodeError::SubscriptionNotAllowed);
+    }
+    // A condition that can't be read can't be accepted either.
+    if !Condition::of(subscription).is_ok_and(|condition| C::accept_condition(state, &condition)) {
+        return Err(VerifyDecodeError::ConditionNotAccepted);
+    }
+    Ok(())
+}
+
+/// Pass an accepted subscription to [`Config::on_verified`] and [`Config::on_revocation`].
+pub(crate) fn observe<C: Config<S>, S>(
+    state: &S,
+    subscription: &EventSubSubscription,
+    message_type: MessageType,
+) {
+    C::on_verified(state, subscription, message_type);
+    if message_type == MessageType::Revocation {
+        C::on_revocation(
+            state,
+            &Revocation {
+                subscription: subscription.clone(),
+            },
+        );
+    }
+}
+
+/// [`check`] and [`observe`] the subscription of a verified payload.
+pub(crate) fn accept<C: Config<S>, S>(
+    state: &S,
+    meta: &EventMeta,
+    subscription: &EventSubSubscription,
+) -> Result<(), C::Rejection> {
+    check::<C, S>(state, subscription).map_err(|e| in_context::<C, S>(meta, e))?;
+    observe::<C, S>(state, subscription, meta.message_type);
+    Ok(())
+}
+
+/// Convert an error that happened after the headers were read.
+pub(crate) fn in_context<C: Config<S>, S>(
+    meta: &EventMeta,
+    error: VerifyDecodeError,
+) -> C::Rejection {
+    C::convert_error_with_context(ErrorContext::new(
+        error,
+        meta.subscription_type.clone(),
+        meta.message_id.as_str(),
+    ))
+}
@@ -1,7 +1,18 @@
 mod extractors;
 
-pub use extractors::eventsub::*;
+pub use extractors::{any::*, batch::*, eventsub::*, raw::*};
 pub mod types {
     pub use eventsub_common::types::*;
 }
-pub use eventsub_common::{EventsubPayload, Notification, Revocation, Verification};
+#[cfg(feature = "borrowed")]
+pub use eventsub_common::borrowed;
+#[cfg(feature = "zeroize")]
+pub use eventsub_common::secret::{secrecy, zeroize};
+pub use eventsub_common::{
+    error::ErrorContext,
+    redemption::RedemptionEvent,
+    secret::{HmacKey, PathScopedSecret},
+    subscription, Condition, EventsubPayload, MessageType, Notification, Revocation, Verification,
+};
+#[cfg(feature = "debug-report")]
+pub use extractors::debug::DebugReport;
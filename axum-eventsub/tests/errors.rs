@@ -0,0 +1,191 @@
+//! Rejected requests and how errors are reported.
+
+use axum::{
+    body::{Body, Bytes},
+    http::{HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Router,
+};
+use axum_eventsub::{
+    types::channel::ChannelPointsCustomRewardRedemptionAddV1, Config, ConfiguredError,
+    ErrorContext, TryData, VerifyDecodeError,
+};
+use futures_util::{stream, StreamExt};
+use tower::ServiceExt;
+
+use crate::util::{Settings, TestConfig};
+mod util;
+
+struct StatusConfig;
+
+impl Config<()> for StatusConfig {
+    type Rejection = ConfiguredError;
+
+    fn get_secret(_state: &()) -> &[u8] {
+        util::SECRET
+    }
+
+    fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+        let status = match error {
+            VerifyDecodeError::SignatureMismatch => StatusCode::UNAUTHORIZED,
+            _ => error.status_code(),
+        };
+        ConfiguredError::new(error, status)
+    }
+}
+
+struct ContextConfig;
+
+impl Config<()> for ContextConfig {
+    type Rejection = (StatusCode, String);
+
+    fn get_secret(_state: &()) -> &[u8] {
+        util::SECRET
+    }
+
+    fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+        (error.status_code(), error.to_string())
+    }
+
+    fn convert_error_with_context(error: ErrorContext<VerifyDecodeError>) -> Self::Rejection {
+        (error.kind().status_code(), error.to_string())
+    }
+}
+
+struct JsonOnly;
+
+impl Settings for JsonOnly {
+    const JSON_ONLY: bool = true;
+}
+
+struct SmallBodies;
+
+impl Settings for SmallBodies {
+    const MAX_BODY_SIZE: usize = 512;
+}
+
+async fn try_eventsub(
+    data: TryData<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+) -> Response {
+    match data {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(VerifyDecodeError::SignatureMismatch) => {
+            (StatusCode::IM_A_TEAPOT, "mismatch").into_response()
+        }
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+#[tokio::test]
+async fn custom_status() {
+    let app = Router::new().route("/eventsub", post(util::handle::<StatusConfig>));
+    let fixture = util::fixture("notification");
+    let body = String::from_utf8(fixture.body().to_vec())
+        .unwrap()
+        .replace("Test Input", "Fake Input");
+
+    let res = app
+        .clone()
+        .oneshot(util::with_body(&fixture, body))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let fixture = util::stale_fixture("notification");
+    let res = app.oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn error_in_handler() {
+    let app = Router::new().route("/eventsub", post(try_eventsub));
+    let fixture = util::fixture("notification");
+
+    let res = app.clone().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    let res = app.oneshot(util::with_body(&fixture, "{}")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::IM_A_TEAPOT);
+    assert_eq!(util::body(res).await, "mismatch");
+}
+
+#[tokio::test]
+async fn error_context() {
+    let app = Router::new().route("/eventsub", post(util::handle::<ContextConfig>));
+    let fixture = util::fixture("notification");
+
+    let res = app.oneshot(util::with_body(&fixture, "{}")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        util::body(res).await,
+        "The provided signature wasn't expected \
+         (channel.channel_points_custom_reward_redemption.add id=a6e2dcd1-3e18-8d6c-7a0f-6fc8f7fd5a51)"
+    );
+}
+
+#[tokio::test]
+async fn content_type() {
+    let json_only = Router::new().route("/eventsub", post(util::handle::<TestConfig<JsonOnly>>));
+    let fixture = util::fixture("notification");
+    let with_content_type = |content_type| {
+        let mut req = util::request(&fixture);
+        req.headers_mut()
+            .insert("Content-Type", HeaderValue::from_static(content_type));
+        req
+    };
+
+    for (content_type, status) in [
+        ("application/json", StatusCode::NO_CONTENT),
+        ("application/json; charset=utf-8", StatusCode::NO_CONTENT),
+        ("text/plain", StatusCode::UNSUPPORTED_MEDIA_TYPE),
+    ] {
+        let res = json_only
+            .clone()
+            .oneshot(with_content_type(content_type))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), status, "{content_type}");
+    }
+
+    // the check is off by default
+    let app = Router::new().route("/eventsub", post(util::handle::<TestConfig>));
+    let res = app.oneshot(with_content_type("text/plain")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+}
+
+#[tokio::test(start_paused = true)]
+async fn stalled_body() {
+    let app = Router::new().route("/eventsub", post(try_eventsub));
+    let fixture = util::fixture("notification");
+    // the first chunk arrives, the rest never does
+    let body = stream::iter([Ok::<_, std::io::Error>(Bytes::copy_from_slice(
+        &fixture.body()[..16],
+    ))])
+    .chain(stream::pending());
+
+    let res = app
+        .oneshot(util::with_body(&fixture, Body::wrap_stream(body)))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(
+        util::body(res).await,
+        VerifyDecodeError::ReadTimeout.to_string()
+    );
+}
+
+#[tokio::test]
+async fn max_body_size() {
+    let app = Router::new().route("/eventsub", post(util::handle::<TestConfig<SmallBodies>>));
+
+    // the verification fits, the notification with its event doesn't
+    let fixture = util::fixture("verification");
+    let res = app.clone().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let fixture = util::fixture("notification");
+    let res = app.oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(util::body(res).await, "The request was too large");
+}
@@ -0,0 +1,175 @@
+//! The extractors besides `Data`.
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+    Router,
+};
+use axum_eventsub::{
+    types::channel::ChannelPointsCustomRewardRedemptionAddV1, AnyData, BatchData, EventsubPayload,
+    RawData,
+};
+use serde::Deserialize;
+use std::{borrow::Cow, sync::Arc};
+use tower::ServiceExt;
+
+use crate::util::{Hooks, TestConfig};
+mod util;
+
+async fn batch_eventsub(
+    batch: BatchData<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+) -> Response {
+    if batch.payloads().len() > 1 {
+        return batch.payloads().len().to_string().into_response();
+    }
+    batch.response()
+}
+
+async fn any_eventsub(data: AnyData<TestConfig>) -> String {
+    let meta = data.meta();
+    format!("{} {}", meta.subscription_type, meta.subscription_version)
+}
+
+#[derive(Deserialize)]
+struct Redemption<'a> {
+    #[serde(borrow)]
+    user_login: Cow<'a, str>,
+}
+
+async fn raw_eventsub(
+    event: RawData<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+) -> String {
+    match event.payload::<Redemption<'_>>() {
+        Ok(EventsubPayload::Notification(notification)) => {
+            let borrowed = matches!(notification.event.user_login, Cow::Borrowed(_));
+            format!("{} {borrowed}", notification.event.user_login)
+        }
+        Ok(EventsubPayload::Verification(verification)) => verification.challenge,
+        Ok(EventsubPayload::Revocation(_)) => event.subscription_id().to_owned(),
+        Err(e) => e.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn any_version() {
+    let app = Router::new().route("/eventsub", post(any_eventsub));
+    let mut fixture = util::fixture("notification");
+    fixture.set_header("Twitch-Eventsub-Subscription-Version", "beta");
+
+    let res = app.clone().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        util::body(res).await,
+        "channel.channel_points_custom_reward_redemption.add beta"
+    );
+
+    // the signature is still checked
+    let res = app.oneshot(util::with_body(&fixture, "{}")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let app = Router::new().route("/eventsub", post(util::handle::<TestConfig>));
+    let res = app.oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn batch() {
+    let app = Router::new().route("/eventsub", post(batch_eventsub));
+
+    let fixture = util::fixture("verification");
+    let res = app.clone().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(
+        util::body(res).await,
+        "f8a6a1ba-12c4-49b6-2b6d-8e7f6a3f3e0e"
+    );
+
+    let mut fixture = util::fixture("notification");
+    let res = app.clone().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    let single = fixture.body().to_vec();
+    fixture.set_body([&b"["[..], &single, b",", &single, b"]"].concat());
+    fixture.sign(util::SECRET);
+    let res = app.clone().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(util::body(res).await, "2");
+
+    // the signature covers the whole batch
+    let tampered = util::with_body(&fixture, [&b"["[..], &single, b"]"].concat());
+    let res = app.oneshot(tampered).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[cfg(feature = "debug-report")]
+#[tokio::test]
+async fn debug_report() {
+    use axum_eventsub::DebugReport;
+
+    async fn debug(report: DebugReport<TestConfig>) -> DebugReport<TestConfig> {
+        report
+    }
+
+    let app = Router::new().route("/eventsub", post(debug));
+    let mut fixture = util::fixture("notification");
+    let res = app.clone().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let report: serde_json::Value = serde_json::from_slice(&util::body(res).await).unwrap();
+    assert_eq!(report["signature_valid"], true);
+    assert_eq!(report["message_type"], "notification");
+    assert_eq!(report["header_error"], serde_json::Value::Null);
+
+    // nothing is rejected
+    fixture.set_body("{}");
+    let res = app.oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let report: serde_json::Value = serde_json::from_slice(&util::body(res).await).unwrap();
+    assert_eq!(report["signature_valid"], false);
+    assert_eq!(report["body_len"], 2);
+}
+
+#[tokio::test]
+async fn raw() {
+    let hooks = Arc::new(Hooks::default());
+    let app = Router::new()
+        .route("/eventsub", post(raw_eventsub))
+        .with_state(hooks.clone());
+
+    let fixture = util::fixture("notification");
+    let res = app.clone().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(util::body(res).await, "testFromUser true");
+
+    let verification = util::fixture("verification");
+    let res = app
+        .clone()
+        .oneshot(util::request(&verification))
+        .await
+        .unwrap();
+    assert_eq!(
+        util::body(res).await,
+        "f8a6a1ba-12c4-49b6-2b6d-8e7f6a3f3e0e"
+    );
+
+    // only the subscription was read
+    let revocation = util::fixture("revocation");
+    let res = app
+        .clone()
+        .oneshot(util::request(&revocation))
+        .await
+        .unwrap();
+    assert_eq!(
+        util::body(res).await,
+        "2d7bb1c5-5d1d-4e0e-9c6f-4f1a7c2c3a10"
+    );
+
+    // the signature is checked before anything is read
+    let res = app.oneshot(util::with_body(&fixture, "{}")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    // the hooks see the subscription
+    assert_eq!(hooks.verified.lock().unwrap().len(), 3);
+    let app = Router::new()
+        .route("/eventsub", post(raw_eventsub))
+        .with_state(Hooks::allowing("f1c2a387-161a-49f9-a165-0f21d7a4e1c4"));
+    let res = app.oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,200 @@
+//! Answering deliveries from the handler.
+
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use axum_eventsub::{
+    types::channel::ChannelPointsCustomRewardRedemptionAddV1, AnyData, Data, EventMeta,
+};
+use tokio::sync::{mpsc, Notify};
+use tower::ServiceExt;
+
+use crate::util::{Settings, TestConfig};
+mod util;
+
+struct JsonAck;
+
+impl Settings for JsonAck {
+    fn notification_response() -> Response {
+        Json(serde_json::json!({ "ack": true })).into_response()
+    }
+}
+
+static PANICKED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct RecordPanics;
+
+impl Settings for RecordPanics {
+    fn on_handler_panic(meta: &EventMeta) {
+        PANICKED.lock().unwrap().push(meta.message_id.clone());
+    }
+}
+
+#[tokio::test]
+async fn spawn_handler() {
+    let release = Arc::new(Notify::new());
+    let (done_tx, mut done_rx) = mpsc::unbounded_channel();
+    let handler = {
+        let release = release.clone();
+        move |data: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>| async move {
+            data.spawn_handler(move |data| async move {
+                release.notified().await;
+                done_tx.send(data.meta().message_id.clone()).unwrap();
+            })
+        }
+    };
+    let app = Router::new().route("/eventsub", post(handler));
+
+    let fixture = util::fixture("verification");
+    let res = app.clone().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(
+        util::body(res).await,
+        "f8a6a1ba-12c4-49b6-2b6d-8e7f6a3f3e0e"
+    );
+
+    // the response is sent while the handler still waits
+    let fixture = util::fixture("notification");
+    let res = app.oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    assert!(done_rx.try_recv().is_err());
+
+    release.notify_one();
+    assert_eq!(
+        done_rx.recv().await.unwrap(),
+        "a6e2dcd1-3e18-8d6c-7a0f-6fc8f7fd5a51"
+    );
+    // verifications never reach the handler
+    assert!(done_rx.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn notification_response() {
+    let app = Router::new()
+        .route(
+            "/eventsub",
+            post(
+                |data: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig<JsonAck>>| async move {
+                    data.spawn_handler(|_| async {})
+                },
+            ),
+        )
+        .route(
+            "/eventsub/any",
+            post(|data: AnyData<TestConfig<JsonAck>>| async move { data.handle() }),
+        );
+
+    let fixture = util::fixture("verification");
+    let res = app.clone().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        util::body(res).await,
+        "f8a6a1ba-12c4-49b6-2b6d-8e7f6a3f3e0e"
+    );
+
+    let fixture = util::fixture("notification");
+    for uri in ["/eventsub", "/eventsub/any"] {
+        let mut req = util::request(&fixture);
+        *req.uri_mut() = uri.parse().unwrap();
+        let res = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(util::body(res).await, r#"{"ack":true}"#);
+    }
+
+    // revocations are still acknowledged with 204
+    let fixture = util::fixture("revocation");
+    let res = app.oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+}
+
+#[cfg(feature = "opentelemetry")]
+#[tokio::test]
+async fn otel_context() {
+    use opentelemetry::trace::{TraceContextExt, TraceId};
+
+    async fn traced(data: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>) -> Response {
+        let trace_id = data.otel_context().span().span_context().trace_id();
+        assert_ne!(trace_id, TraceId::INVALID);
+        let current = data
+            .in_otel_context(async {
+                opentelemetry::Context::current()
+                    .span()
+                    .span_context()
+                    .trace_id()
+            })
+            .await;
+        assert_eq!(current, trace_id);
+        data.handle(
+            move |_| {
+                let current = opentelemetry::Context::current();
+                assert_eq!(current.span().span_context().trace_id(), trace_id);
+                trace_id.to_string()
+            },
+            |_| String::new(),
+        )
+    }
+
+    async fn traced_any(data: AnyData<TestConfig>) -> String {
+        let trace_id = data.otel_context().span().span_context().trace_id();
+        let current = data
+            .in_otel_context(async {
+                opentelemetry::Context::current()
+                    .span()
+                    .span_context()
+                    .trace_id()
+            })
+            .await;
+        assert_eq!(current, trace_id);
+        trace_id.to_string()
+    }
+
+    opentelemetry::global::set_tracer_provider(
+        opentelemetry_sdk::trace::TracerProvider::builder().build(),
+    );
+    let app = Router::new()
+        .route("/eventsub", post(traced))
+        .route("/eventsub/any", post(traced_any));
+    let fixture = util::fixture("notification");
+    for uri in ["/eventsub", "/eventsub/any"] {
+        let mut req = util::request(&fixture);
+        *req.uri_mut() = uri.parse().unwrap();
+        let first = util::body(app.clone().oneshot(req).await.unwrap()).await;
+        let mut req = util::request(&fixture);
+        *req.uri_mut() = uri.parse().unwrap();
+        let second = util::body(app.clone().oneshot(req).await.unwrap()).await;
+        // every delivery starts a new trace
+        assert_eq!(first.len(), 32);
+        assert_ne!(first, second);
+    }
+}
+
+#[tokio::test]
+async fn handler_panic() {
+    let app = Router::new().route(
+        "/eventsub",
+        post(
+            |data: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig<RecordPanics>>| async move {
+                data.spawn_handler(|_| async { panic!("the handler failed") })
+            },
+        ),
+    );
+
+    let fixture = util::fixture("notification");
+    let res = app.oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    for _ in 0..100 {
+        if !PANICKED.lock().unwrap().is_empty() {
+            break;
+        }
+        tokio::task::yield_now().await;
+    }
+    assert_eq!(
+        *PANICKED.lock().unwrap(),
+        ["a6e2dcd1-3e18-8d6c-7a0f-6fc8f7fd5a51"]
+    );
+}
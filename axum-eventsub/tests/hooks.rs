@@ -0,0 +1,180 @@
+//! The checks and callbacks of `Config` that run for verified requests.
+
+use std::sync::Arc;
+
+use axum::{http::StatusCode, response::Response, routing::post, Router};
+use axum_eventsub::{types::channel::ChannelPointsCustomRewardRedemptionAddV1, Data, MessageType};
+use tower::ServiceExt;
+
+use crate::util::{Hooks, Settings, TestConfig};
+mod util;
+
+struct RetryLimit;
+
+impl Settings for RetryLimit {
+    const MAX_RETRIES: Option<u32> = Some(3);
+}
+
+struct AutoAck;
+
+impl Settings for AutoAck {
+    const AUTO_ACK_REVOCATIONS: bool = true;
+}
+
+async fn accepting_eventsub<C>(
+    _data: Data<ChannelPointsCustomRewardRedemptionAddV1, C>,
+) -> &'static str {
+    "handled"
+}
+
+async fn failing_revocation_eventsub<C>(
+    data: Data<ChannelPointsCustomRewardRedemptionAddV1, C>,
+) -> Response {
+    data.handle(|_| StatusCode::OK, |_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+#[tokio::test]
+async fn subscription_allow_list() {
+    let fixture = util::fixture("notification");
+    for (allowed, status) in [
+        (
+            "2d7bb1c5-5d1d-4e0e-9c6f-4f1a7c2c3a10",
+            StatusCode::NO_CONTENT,
+        ),
+        (
+            "f1c2a387-161a-49f9-a165-0f21d7a4e1c4",
+            StatusCode::FORBIDDEN,
+        ),
+    ] {
+        let app = Router::new()
+            .route("/eventsub", post(util::handle::<TestConfig>))
+            .with_state(Hooks::allowing(allowed));
+
+        let res = app.oneshot(util::request(&fixture)).await.unwrap();
+        assert_eq!(res.status(), status, "{allowed}");
+    }
+}
+
+#[tokio::test]
+async fn accept_condition() {
+    let app = Router::new()
+        .nest(
+            "/eventsub/53946574",
+            Router::new()
+                .route("/", post(accepting_eventsub::<TestConfig>))
+                .with_state(Hooks::broadcaster("53946574")),
+        )
+        .nest(
+            "/eventsub/12826",
+            Router::new()
+                .route("/", post(accepting_eventsub::<TestConfig>))
+                .with_state(Hooks::broadcaster("12826")),
+        );
+    let fixture = util::fixture("notification");
+
+    let mut req = util::request(&fixture);
+    *req.uri_mut() = "/eventsub/53946574".parse().unwrap();
+    let res = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let mut req = util::request(&fixture);
+    // acknowledged, so Twitch doesn't retry it, but not handled
+    *req.uri_mut() = "/eventsub/12826".parse().unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    assert!(util::body(res).await.is_empty());
+}
+
+#[tokio::test]
+async fn on_verified() {
+    let hooks = Arc::new(Hooks::default());
+    let app = Router::new()
+        .route("/eventsub", post(util::handle::<TestConfig>))
+        .with_state(hooks.clone());
+
+    for name in ["verification", "notification"] {
+        let fixture = util::fixture(name);
+        let res = app.clone().oneshot(util::request(&fixture)).await.unwrap();
+        assert!(res.status().is_success(), "{name}");
+    }
+    // requests that fail verification aren't observed
+    let fixture = util::fixture("notification");
+    let res = app.oneshot(util::with_body(&fixture, "{}")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let verified = hooks.verified.lock().unwrap();
+    assert_eq!(verified.len(), 2);
+    assert_eq!(verified[0].1, MessageType::Verification);
+    assert_eq!(
+        verified[1],
+        (
+            "2d7bb1c5-5d1d-4e0e-9c6f-4f1a7c2c3a10".to_owned(),
+            MessageType::Notification
+        )
+    );
+}
+
+#[tokio::test]
+async fn auto_ack_revocations() {
+    let hooks = Arc::new(Hooks::default());
+    let app = Router::new()
+        .route(
+            "/eventsub",
+            post(failing_revocation_eventsub::<TestConfig<AutoAck>>),
+        )
+        .with_state(hooks.clone());
+
+    let fixture = util::fixture("revocation");
+    let res = app.clone().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        *hooks.revoked.lock().unwrap(),
+        ["2d7bb1c5-5d1d-4e0e-9c6f-4f1a7c2c3a10"]
+    );
+    // only revocations are acknowledged automatically
+    let fixture = util::fixture("notification");
+    let res = app.oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(hooks.revoked.lock().unwrap().len(), 1);
+
+    // by default, the handler controls the response
+    let app = Router::new().route("/eventsub", post(failing_revocation_eventsub::<TestConfig>));
+    let fixture = util::fixture("revocation");
+    let res = app.oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[tokio::test]
+async fn retry_limit() {
+    let app = Router::new().route(
+        "/eventsub",
+        post(accepting_eventsub::<TestConfig<RetryLimit>>),
+    );
+    let mut fixture = util::fixture("notification");
+
+    let res = app.clone().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    fixture.set_header("Twitch-Eventsub-Message-Retry", "3");
+    let res = app.clone().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    fixture.set_header("Twitch-Eventsub-Message-Retry", "10");
+    let res = app.clone().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    assert_eq!(util::body(res).await, "");
+
+    // the header isn't signed, so the signature is checked first
+    let res = app
+        .clone()
+        .oneshot(util::with_body(&fixture, "{}"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    // verifications are always handled
+    let mut fixture = util::fixture("verification");
+    fixture.set_header("Twitch-Eventsub-Message-Retry", "10");
+    let res = app.oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(util::body(res).await, "handled");
+}
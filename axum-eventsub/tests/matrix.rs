@@ -0,0 +1,201 @@
+//! Every message type and failure for each subscription type, without the twitch-cli.
+//!
+//! Add a subscription type with one line in the `matrix!` invocation at the bottom.
+
+use axum::{http::StatusCode, routing::post, Router};
+use axum_eventsub::{
+    types::{
+        channel::{
+            ChannelChatMessageV1, ChannelPointsCustomRewardRedemptionAddV1,
+            ChannelPointsCustomRewardRedemptionUpdateV1,
+        },
+        EventSubscription,
+    },
+    MessageType, TryData,
+};
+use eventsub_common::{fixture::Fixture, headers::SUBSCRIPTION_VERSION};
+use serde_json::json;
+use tower::ServiceExt;
+
+use crate::{
+    matrix::Case,
+    util::{TestConfig, SECRET},
+};
+#[path = "../../fixtures/matrix.rs"]
+mod matrix;
+#[path = "../../fixtures/process.rs"]
+mod process;
+mod util;
+
+async fn eventsub<P: EventSubscription + Send + 'static>(
+    data: TryData<P, TestConfig>,
+) -> (StatusCode, String)
+where
+    P::Payload: Send,
+{
+    match data {
+        Ok(data) => (StatusCode::OK, process::process(data.payload())),
+        Err(e) => (StatusCode::BAD_REQUEST, e.to_string()),
+    }
+}
+
+async fn send<P: EventSubscription + Send + 'static>(fixture: &Fixture) -> (StatusCode, String)
+where
+    P::Payload: Send,
+{
+    let res = Router::new()
+        .route("/eventsub", post(eventsub::<P>))
+        .oneshot(util::request(fixture))
+        .await
+        .unwrap();
+    let status = res.status();
+    let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+async fn verification<P: EventSubscription + Send + 'static>(case: Case)
+where
+    P::Payload: Send,
+{
+    let fixture = matrix::delivery::<P>(MessageType::Verification, &case, SECRET);
+    assert_eq!(
+        send::<P>(&fixture).await,
+        (StatusCode::OK, matrix::CHALLENGE.to_owned())
+    );
+}
+
+async fn notification<P: EventSubscription + Send + 'static>(case: Case)
+where
+    P::Payload: Send,
+{
+    let fixture = matrix::delivery::<P>(MessageType::Notification, &case, SECRET);
+    assert_eq!(
+        send::<P>(&fixture).await,
+        (
+            StatusCode::OK,
+            format!("notification for {}", matrix::SUBSCRIPTION_ID)
+        )
+    );
+}
+
+async fn revocation<P: EventSubscription + Send + 'static>(case: Case)
+where
+    P::Payload: Send,
+{
+    let fixture = matrix::delivery::<P>(MessageType::Revocation, &case, SECRET);
+    assert_eq!(
+        send::<P>(&fixture).await,
+        (
+            StatusCode::OK,
+            format!("revocation of {}", matrix::SUBSCRIPTION_ID)
+        )
+    );
+}
+
+async fn wrong_signature<P: EventSubscription + Send + 'static>(case: Case)
+where
+    P::Payload: Send,
+{
+    let mut fixture = matrix::delivery::<P>(MessageType::Notification, &case, SECRET);
+    fixture.sign(b"not the secret");
+    assert_eq!(
+        send::<P>(&fixture).await,
+        (
+            StatusCode::BAD_REQUEST,
+            "The provided signature wasn't expected".to_owned()
+        )
+    );
+}
+
+async fn too_old<P: EventSubscription + Send + 'static>(case: Case)
+where
+    P::Payload: Send,
+{
+    let mut fixture = matrix::delivery::<P>(MessageType::Notification, &case, SECRET);
+    matrix::age(&mut fixture, 11, SECRET);
+    assert_eq!(
+        send::<P>(&fixture).await,
+        (
+            StatusCode::BAD_REQUEST,
+            "Invalid headers: The message is too old".to_owned()
+        )
+    );
+}
+
+async fn version_mismatch<P: EventSubscription + Send + 'static>(case: Case)
+where
+    P::Payload: Send,
+{
+    let mut fixture = matrix::delivery::<P>(MessageType::Notification, &case, SECRET);
+    fixture.set_header(SUBSCRIPTION_VERSION, "0");
+    fixture.sign(SECRET);
+    assert_eq!(
+        send::<P>(&fixture).await,
+        (
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Invalid headers: Cannot accept this version, expected: {}",
+                P::VERSION
+            )
+        )
+    );
+}
+
+// axum's `Config` has no `check_event_id`, duplicates are left to the handler,
+// so unlike the actix-web matrix there's no case for them.
+
+/// Run every case for each subscription type: `name: Type => condition, event;`.
+macro_rules! matrix {
+    ($($name:ident: $sub:ty => $condition:expr, $event:expr;)*) => {$(
+        mod $name {
+            use super::*;
+
+            fn case() -> Case {
+                Case {
+                    condition: $condition,
+                    event: $event,
+                }
+            }
+
+            #[tokio::test]
+            async fn verification() {
+                super::verification::<$sub>(case()).await;
+            }
+
+            #[tokio::test]
+            async fn notification() {
+                super::notification::<$sub>(case()).await;
+            }
+
+            #[tokio::test]
+            async fn revocation() {
+                super::revocation::<$sub>(case()).await;
+            }
+
+            #[tokio::test]
+            async fn wrong_signature() {
+                super::wrong_signature::<$sub>(case()).await;
+            }
+
+            #[tokio::test]
+            async fn too_old() {
+                super::too_old::<$sub>(case()).await;
+            }
+
+            #[tokio::test]
+            async fn version_mismatch() {
+                super::version_mismatch::<$sub>(case()).await;
+            }
+        }
+    )*};
+}
+
+matrix! {
+    redemption_add: ChannelPointsCustomRewardRedemptionAddV1 =>
+        json!({ "broadcaster_user_id": "53946574", "reward_id": "" }), matrix::redemption();
+    redemption_update: ChannelPointsCustomRewardRedemptionUpdateV1 =>
+        json!({ "broadcaster_user_id": "53946574", "reward_id": "" }), matrix::redemption();
+    chat_message: ChannelChatMessageV1 =>
+        json!({ "broadcaster_user_id": "53946574", "user_id": "81723905" }),
+        json!({ "message_id": "cc106a89-1814-919d-454c-f4f2f970aae7" });
+}
@@ -0,0 +1,117 @@
+//! Accessing the extracted payload.
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+    Router,
+};
+use axum_eventsub::{
+    types::channel::ChannelPointsCustomRewardRedemptionAddV1, Data, EventsubPayload,
+};
+use tower::ServiceExt;
+
+use crate::{process::process, util::TestConfig};
+#[path = "../../fixtures/process.rs"]
+mod process;
+mod util;
+
+async fn shared_eventsub(
+    data: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+) -> String {
+    let processed = process(data.payload());
+    assert_eq!(process(&data.into_payload()), processed);
+    processed
+}
+
+async fn spawning_eventsub(
+    data: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+) -> Response {
+    let (payload, meta) = data.into_parts();
+    tokio::spawn(async move {
+        assert!(matches!(payload, EventsubPayload::Notification(_)));
+        meta.message_id
+    })
+    .await
+    .unwrap()
+    .into_response()
+}
+
+async fn redemption_eventsub(
+    data: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+) -> String {
+    match data.payload() {
+        EventsubPayload::Notification(notification) => format!(
+            "{} {} {}",
+            notification.event.user_login,
+            notification.event.reward.title,
+            notification.event.user_input
+        ),
+        _ => "no event".to_owned(),
+    }
+}
+
+async fn condition_eventsub(
+    data: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+) -> String {
+    data.condition().unwrap().broadcaster_user_id.to_string()
+}
+
+#[tokio::test]
+async fn shared_logic() {
+    let app = Router::new().route("/eventsub", post(shared_eventsub));
+
+    let fixture = util::fixture("verification");
+    let res = app.clone().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(
+        util::body(res).await,
+        "f8a6a1ba-12c4-49b6-2b6d-8e7f6a3f3e0e"
+    );
+
+    let fixture = util::fixture("notification");
+    let res = app.oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(
+        util::body(res).await,
+        "notification for 2d7bb1c5-5d1d-4e0e-9c6f-4f1a7c2c3a10"
+    );
+}
+
+#[tokio::test]
+async fn into_parts() {
+    let app = Router::new().route("/eventsub", post(spawning_eventsub));
+    let fixture = util::fixture("notification");
+
+    let res = app.oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        util::body(res).await,
+        fixture.header("Twitch-Eventsub-Message-Id").unwrap()
+    );
+}
+
+#[tokio::test]
+async fn redemption_event() {
+    let app = Router::new().route("/eventsub", post(redemption_eventsub));
+
+    let fixture = util::fixture("notification");
+    let res = app.clone().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(
+        util::body(res).await,
+        "testFromUser Test Reward from CLI Test Input From CLI"
+    );
+
+    let fixture = util::fixture("verification");
+    let res = app.oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(util::body(res).await, "no event");
+}
+
+#[tokio::test]
+async fn typed_condition() {
+    let app = Router::new().route("/eventsub", post(condition_eventsub));
+
+    for name in ["notification", "verification"] {
+        let fixture = util::fixture(name);
+        let res = app.clone().oneshot(util::request(&fixture)).await.unwrap();
+        assert_eq!(util::body(res).await, "53946574", "{name}");
+    }
+}
@@ -0,0 +1,87 @@
+//! Replaying the recorded deliveries in `fixtures/`.
+
+use axum::{http::StatusCode, response::Response, routing::post, Router};
+use axum_eventsub::{
+    types::{channel::ChannelPointsCustomRewardRedemptionAddV1, Status},
+    Data, EventsubPayload,
+};
+use chrono::DateTime;
+use eventsub_common::{headers::MESSAGE_TIMESTAMP, verify::verify_and_decode_at};
+use tower::ServiceExt;
+
+use crate::util::TestConfig;
+mod util;
+
+fn app() -> Router {
+    Router::new().route("/eventsub", post(util::handle::<TestConfig>))
+}
+
+async fn status_eventsub(
+    data: Data<ChannelPointsCustomRewardRedemptionAddV1, TestConfig>,
+) -> Response {
+    // the subscription is pending until the challenge is answered
+    assert!(matches!(data.payload, EventsubPayload::Verification(_)));
+    assert_eq!(
+        data.payload.subscription().status,
+        Status::WebhookCallbackVerificationPending
+    );
+    data.handle(|_| StatusCode::NO_CONTENT, |_| StatusCode::NO_CONTENT)
+}
+
+#[test]
+fn recorded_signatures() {
+    for name in ["verification", "notification", "revocation"] {
+        let fixture = util::stale_fixture(name);
+        let recorded_at =
+            DateTime::parse_from_rfc3339(fixture.header(MESSAGE_TIMESTAMP).unwrap()).unwrap();
+
+        verify_and_decode_at::<ChannelPointsCustomRewardRedemptionAddV1, _>(
+            &fixture.header_map(),
+            fixture.body(),
+            util::SECRET,
+            recorded_at.into(),
+        )
+        .unwrap_or_else(|e| panic!("{name}: {e}"));
+    }
+}
+
+#[tokio::test]
+async fn replay_verification() {
+    let fixture = util::fixture("verification");
+
+    let res = app().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        util::body(res).await,
+        "f8a6a1ba-12c4-49b6-2b6d-8e7f6a3f3e0e"
+    );
+}
+
+#[tokio::test]
+async fn replay_notification() {
+    let fixture = util::fixture("notification");
+
+    let res = app().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn rejects_stale_fixture() {
+    let fixture = util::stale_fixture("notification");
+
+    let res = app().oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn pending_verification() {
+    let app = Router::new().route("/eventsub", post(status_eventsub));
+    let fixture = util::fixture("verification");
+
+    let res = app.oneshot(util::request(&fixture)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        util::body(res).await,
+        "f8a6a1ba-12c4-49b6-2b6d-8e7f6a3f3e0e"
+    );
+}
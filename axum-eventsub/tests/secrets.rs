@@ -0,0 +1,282 @@
+use std::{borrow::Cow, collections::HashMap, future::Future, sync::Arc};
+
+use axum::{
+    http::{request::Parts, StatusCode},
+    response::Response,
+    routing::post,
+    Router,
+};
+use axum_eventsub::{
+    types::channel::ChannelPointsCustomRewardRedemptionAddV1, Config, Data, EventMeta, HmacKey,
+    PathScopedSecret, VerifyDecodeError,
+};
+use tokio::sync::RwLock;
+use tower::ServiceExt;
+mod util;
+
+struct Scoped {
+    secret: &'static [u8],
+    endpoints: PathScopedSecret,
+}
+
+struct ScopedConfig;
+
+impl Config<Arc<Scoped>> for ScopedConfig {
+    type Rejection = VerifyDecodeError;
+
+    fn get_secret(state: &Arc<Scoped>) -> &[u8] {
+        state.secret
+    }
+
+    fn get_key<'a>(
+        state: &'a Arc<Scoped>,
+        parts: &Parts,
+        _meta: &EventMeta,
+    ) -> impl Future<Output = Option<impl HmacKey + Send + 'a>> + Send {
+        std::future::ready(match parts.uri.path() {
+            "/eventsub" => Some(Self::get_secret(state)),
+            path => state.endpoints.for_path(path),
+        })
+    }
+
+    fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+        error
+    }
+}
+
+async fn eventsub(data: Data<ChannelPointsCustomRewardRedemptionAddV1, ScopedConfig>) -> Response {
+    data.handle(|_| StatusCode::NO_CONTENT, |_| StatusCode::NO_CONTENT)
+}
+
+#[tokio::test]
+async fn secret_per_path() {
+    let state = Scoped {
+        secret: util::SECRET,
+        endpoints: PathScopedSecret::new()
+            .with("alice", util::SECRET)
+            .with("bob", "bob's secret"),
+    };
+    let app = Router::new()
+        .route("/eventsub", post(eventsub))
+        .route("/eventsub/:broadcaster", post(eventsub))
+        .with_state(Arc::new(state));
+    let fixture = util::fixture("notification");
+
+    for (path, status) in [
+        ("/eventsub", StatusCode::NO_CONTENT),
+        ("/eventsub/alice", StatusCode::NO_CONTENT),
+        ("/eventsub/bob", StatusCode::BAD_REQUEST),
+        ("/eventsub/eve", StatusCode::INTERNAL_SERVER_ERROR),
+    ] {
+        let mut req = util::request(&fixture);
+        *req.uri_mut() = path.parse().unwrap();
+        let res = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), status, "{path}");
+    }
+}
+
+#[cfg(feature = "debug-report")]
+#[tokio::test]
+async fn debug_report_per_path() {
+    use axum_eventsub::DebugReport;
+
+    async fn debug(report: DebugReport<ScopedConfig>) -> DebugReport<ScopedConfig> {
+        report
+    }
+
+    let state = Scoped {
+        secret: b"unused",
+        endpoints: PathScopedSecret::new()
+            .with("alice", util::SECRET)
+            .with("bob", "bob's secret"),
+    };
+    let app = Router::new()
+        .route("/eventsub/:broadcaster", post(debug))
+        .with_state(Arc::new(state));
+    let fixture = util::fixture("notification");
+
+    // eve has no key, so no signature is computed
+    for (path, valid, computed) in [
+        ("/eventsub/alice", true, true),
+        ("/eventsub/bob", false, true),
+        ("/eventsub/eve", false, false),
+    ] {
+        let mut req = util::request(&fixture);
+        *req.uri_mut() = path.parse().unwrap();
+        let res = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK, "{path}");
+        let report: serde_json::Value = serde_json::from_slice(&util::body(res).await).unwrap();
+        assert_eq!(report["signature_valid"], valid, "{path}");
+        assert_eq!(report["computed_signature"].is_string(), computed, "{path}");
+    }
+}
+
+#[derive(Default)]
+struct SecretStore {
+    secret: Vec<u8>,
+    rotated: RwLock<HashMap<String, Vec<u8>>>,
+}
+
+struct AsyncConfig;
+
+impl Config<Arc<SecretStore>> for AsyncConfig {
+    type Rejection = VerifyDecodeError;
+
+    fn get_secret(state: &Arc<SecretStore>) -> &[u8] {
+        &state.secret
+    }
+
+    fn get_key<'a>(
+        state: &'a Arc<SecretStore>,
+        parts: &Parts,
+        _meta: &EventMeta,
+    ) -> impl Future<Output = Option<impl HmacKey + Send + 'a>> + Send {
+        let path = parts.uri.path().to_owned();
+        async move {
+            let rotated = state.rotated.read().await.get(&path).cloned();
+            Some(rotated.map_or(Cow::Borrowed(Self::get_secret(state)), Cow::Owned))
+        }
+    }
+
+    fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+        error
+    }
+}
+
+async fn async_eventsub(
+    data: Data<ChannelPointsCustomRewardRedemptionAddV1, AsyncConfig>,
+) -> Response {
+    data.handle(|_| StatusCode::NO_CONTENT, |_| StatusCode::NO_CONTENT)
+}
+
+#[tokio::test]
+async fn async_secret() {
+    let store = Arc::new(SecretStore {
+        secret: b"old secret".to_vec(),
+        ..Default::default()
+    });
+    let app = Router::new()
+        .route("/eventsub/:broadcaster", post(async_eventsub))
+        .with_state(store.clone());
+    let fixture = util::fixture("notification");
+    let send = |path: &'static str| {
+        let mut req = util::request(&fixture);
+        *req.uri_mut() = path.parse().unwrap();
+        app.clone().oneshot(req)
+    };
+
+    let res = send("/eventsub/alice").await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    store
+        .rotated
+        .write()
+        .await
+        .insert("/eventsub/alice".to_owned(), util::SECRET.to_vec());
+    let res = send("/eventsub/alice").await.unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+}
+
+/// The secret, stored reversed - a stand-in for a real key derivation.
+struct MasterKey(Vec<u8>);
+
+struct DerivedKey<'a>(&'a [u8]);
+
+impl HmacKey for DerivedKey<'_> {
+    fn key_material(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(self.0.iter().rev().copied().collect())
+    }
+}
+
+struct DerivingConfig;
+
+impl Config<Arc<MasterKey>> for DerivingConfig {
+    type Rejection = VerifyDecodeError;
+
+    fn get_secret(state: &Arc<MasterKey>) -> &[u8] {
+        &state.0
+    }
+
+    fn get_key<'a>(
+        state: &'a Arc<MasterKey>,
+        _parts: &Parts,
+        _meta: &EventMeta,
+    ) -> impl Future<Output = Option<impl HmacKey + Send + 'a>> + Send {
+        std::future::ready(Some(DerivedKey(Self::get_secret(state))))
+    }
+
+    fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+        error
+    }
+}
+
+async fn deriving_eventsub(
+    data: Data<ChannelPointsCustomRewardRedemptionAddV1, DerivingConfig>,
+) -> Response {
+    data.handle(|_| StatusCode::NO_CONTENT, |_| StatusCode::NO_CONTENT)
+}
+
+#[tokio::test]
+async fn derived_key() {
+    let fixture = util::fixture("notification");
+    let reversed = util::SECRET.iter().rev().copied().collect();
+
+    for (master, status) in [
+        (reversed, StatusCode::NO_CONTENT),
+        (util::SECRET.to_vec(), StatusCode::BAD_REQUEST),
+    ] {
+        let app = Router::new()
+            .route("/eventsub", post(deriving_eventsub))
+            .with_state(Arc::new(MasterKey(master)));
+        let res = app.oneshot(util::request(&fixture)).await.unwrap();
+        assert_eq!(res.status(), status);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+mod zeroizing {
+    use super::*;
+    use axum_eventsub::secrecy::{ExposeSecret, SecretString};
+
+    struct SecretConfig;
+
+    impl Config<Arc<SecretString>> for SecretConfig {
+        type Rejection = VerifyDecodeError;
+
+        fn get_secret(state: &Arc<SecretString>) -> &[u8] {
+            state.expose_secret().as_bytes()
+        }
+
+        fn get_key<'a>(
+            state: &'a Arc<SecretString>,
+            _parts: &Parts,
+            _meta: &EventMeta,
+        ) -> impl Future<Output = Option<impl HmacKey + Send + 'a>> + Send {
+            // pass the secret on without exposing it
+            std::future::ready(Some(&**state))
+        }
+
+        fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+            error
+        }
+    }
+
+    async fn secret_eventsub(
+        data: Data<ChannelPointsCustomRewardRedemptionAddV1, SecretConfig>,
+    ) -> Response {
+        data.handle(|_| StatusCode::NO_CONTENT, |_| StatusCode::NO_CONTENT)
+    }
+
+    #[tokio::test]
+    async fn secret_string() {
+        let secret = SecretString::from(std::str::from_utf8(util::SECRET).unwrap());
+        let app = Router::new()
+            .route("/eventsub", post(secret_eventsub))
+            .with_state(Arc::new(secret));
+        let res = app
+            .oneshot(util::request(&util::fixture("notification")))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    }
+}
@@ -0,0 +1,202 @@
+#![allow(dead_code)]
+
+use std::{
+    collections::HashSet,
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    response::{IntoResponse, Response},
+};
+use axum_eventsub::{
+    types::{channel::ChannelPointsCustomRewardRedemptionAddV1, EventSubSubscription},
+    Condition, Config, Data, EventMeta, MessageType, Revocation, VerifyDecodeError,
+};
+use eventsub_common::fixture::Fixture;
+
+pub const SECRET: &[u8] = b"5f5f121fc807a21bab4209b2f34e90932778f12c099ca3ca17ee00afd0b328ba";
+
+/// Settings of [`TestConfig`] that can't be read from the state.
+///
+/// Tests override these with a unit struct: `impl Settings for RetryLimit { ... }`.
+pub trait Settings {
+    const MAX_RETRIES: Option<u32> = None;
+    const JSON_ONLY: bool = false;
+    const AUTO_ACK_REVOCATIONS: bool = false;
+    const MAX_BODY_SIZE: usize = eventsub_common::body::MAX_BODY_SIZE;
+
+    fn notification_response() -> Response {
+        StatusCode::NO_CONTENT.into_response()
+    }
+
+    fn on_handler_panic(_meta: &EventMeta) {}
+}
+
+pub struct Defaults;
+
+impl Settings for Defaults {}
+
+/// The state the hooks of [`TestConfig`] check and record to.
+#[derive(Default)]
+pub struct Hooks {
+    /// Only accept these subscription ids.
+    pub allowed: Option<HashSet<String>>,
+    /// Only accept events of this broadcaster.
+    pub broadcaster: Option<String>,
+    pub verified: Mutex<Vec<(String, MessageType)>>,
+    pub revoked: Mutex<Vec<String>>,
+}
+
+impl Hooks {
+    pub fn allowing(id: &str) -> Arc<Self> {
+        Arc::new(Self {
+            allowed: Some(HashSet::from([id.to_owned()])),
+            ..Self::default()
+        })
+    }
+
+    pub fn broadcaster(id: &str) -> Arc<Self> {
+        Arc::new(Self {
+            broadcaster: Some(id.to_owned()),
+            ..Self::default()
+        })
+    }
+}
+
+/// Router states [`TestConfig`] works with.
+///
+/// With `()`, everything is accepted and nothing is recorded.
+pub trait HookState {
+    fn hooks(&self) -> Option<&Hooks>;
+}
+
+impl HookState for () {
+    fn hooks(&self) -> Option<&Hooks> {
+        None
+    }
+}
+
+impl HookState for Arc<Hooks> {
+    fn hooks(&self) -> Option<&Hooks> {
+        Some(self)
+    }
+}
+
+/// The config shared by the tests.
+pub struct TestConfig<S = Defaults>(PhantomData<S>);
+
+impl<St: HookState, S: Settings> Config<St> for TestConfig<S> {
+    type Rejection = VerifyDecodeError;
+
+    fn get_secret(_state: &St) -> &[u8] {
+        SECRET
+    }
+
+    fn is_subscription_allowed(state: &St, subscription_id: &str) -> bool {
+        state
+            .hooks()
+            .and_then(|hooks| hooks.allowed.as_ref())
+            .is_none_or(|allowed| allowed.contains(subscription_id))
+    }
+
+    fn accept_condition(state: &St, condition: &Condition<'_>) -> bool {
+        state
+            .hooks()
+            .and_then(|hooks| hooks.broadcaster.as_deref())
+            .is_none_or(|broadcaster| condition.broadcaster_user_id == Some(broadcaster))
+    }
+
+    fn on_verified(state: &St, subscription: &EventSubSubscription, message_type: MessageType) {
+        if let Some(hooks) = state.hooks() {
+            hooks
+                .verified
+                .lock()
+                .unwrap()
+                .push((subscription.id.to_string(), message_type));
+        }
+    }
+
+    fn on_revocation(state: &St, revocation: &Revocation) {
+        if let Some(hooks) = state.hooks() {
+            hooks
+                .revoked
+                .lock()
+                .unwrap()
+                .push(revocation.subscription.id.to_string());
+        }
+    }
+
+    fn auto_ack_revocations() -> bool {
+        S::AUTO_ACK_REVOCATIONS
+    }
+
+    fn notification_response() -> Response {
+        S::notification_response()
+    }
+
+    fn on_handler_panic(meta: &EventMeta) {
+        S::on_handler_panic(meta);
+    }
+
+    fn max_body_size() -> usize {
+        S::MAX_BODY_SIZE
+    }
+
+    fn max_retries() -> Option<u32> {
+        S::MAX_RETRIES
+    }
+
+    fn require_json_content_type() -> bool {
+        S::JSON_ONLY
+    }
+
+    fn convert_error(error: VerifyDecodeError) -> Self::Rejection {
+        error
+    }
+}
+
+/// Answer verifications with their challenge and everything else with `204 No Content`.
+pub async fn handle<C>(data: Data<ChannelPointsCustomRewardRedemptionAddV1, C>) -> Response {
+    data.handle(|_| StatusCode::NO_CONTENT, |_| StatusCode::NO_CONTENT)
+}
+
+/// Load a fixture from `fixtures/` and refresh it with [`SECRET`].
+pub fn fixture(name: &str) -> Fixture {
+    let mut fixture = stale_fixture(name);
+    fixture.refresh(SECRET);
+    fixture
+}
+
+/// Load a fixture from `fixtures/` as it was recorded.
+pub fn stale_fixture(name: &str) -> Fixture {
+    Fixture::load(format!(
+        "{}/../fixtures/{name}.http",
+        env!("CARGO_MANIFEST_DIR")
+    ))
+    .expect("fixture should load")
+}
+
+/// Build a request to `/eventsub` replaying the fixture.
+pub fn request(fixture: &Fixture) -> Request<Body> {
+    fixture
+        .headers()
+        .fold(Request::post("/eventsub"), |req, (name, value)| {
+            req.header(name, value)
+        })
+        .body(Body::from(fixture.body().to_vec()))
+        .unwrap()
+}
+
+/// Replace the body of a request to the fixture.
+pub fn with_body(fixture: &Fixture, body: impl Into<Body>) -> Request<Body> {
+    let (parts, _) = request(fixture).into_parts();
+    Request::from_parts(parts, body.into())
+}
+
+/// Read a response body to the end.
+pub async fn body(res: Response) -> axum::body::Bytes {
+    hyper::body::to_bytes(res.into_body()).await.unwrap()
+}
@@ -0,0 +1,52 @@
+//! Compare the JSON backends on a notification body.
+//!
+//! Run with `cargo bench -p eventsub-common --features simd-json` to compare simd-json
+//! (parsing in place) with the `serde_json` baseline, or without the feature to measure
+//! `serde_json` twice.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use eventsub_common::{
+    decode::decode_payload_in_place,
+    types::{channel::ChannelPointsCustomRewardRedemptionAddV1, EventSubscription},
+    MessageType, Notification,
+};
+
+const NOTIFICATION: &str = r#"{"subscription":{"id":"2d7bb1c5-5d1d-4e0e-9c6f-4f1a7c2c3a10","status":"enabled","type":"channel.channel_points_custom_reward_redemption.add","version":"1","condition":{"broadcaster_user_id":"53946574","reward_id":""},"transport":{"method":"webhook","callback":"http://127.0.0.1:8080/eventsub"},"created_at":"2024-11-11T18:24:39.125012394Z","cost":0},"event":{"id":"f1f3c6a5-1bd4-4b7c-9bb7-7d1b2c8f4e2d","broadcaster_user_id":"53946574","broadcaster_user_login":"testBroadcaster","broadcaster_user_name":"testBroadcaster","user_id":"81723905","user_login":"testFromUser","user_name":"testFromUser","user_input":"Test Input From CLI","status":"unfulfilled","reward":{"id":"9c3d2e8b-7c2a-4f6e-8d2b-1a5e7f9b3c4d","title":"Test Reward from CLI","cost":150,"prompt":"Redeem Your Test Reward from CLI"},"redeemed_at":"2024-11-11T18:25:02.310938745Z"}}"#;
+
+fn decode(c: &mut Criterion) {
+    let body = NOTIFICATION.as_bytes();
+    let backend = if cfg!(feature = "simd-json") {
+        "simd-json"
+    } else {
+        "serde_json"
+    };
+
+    c.bench_function("serde_json baseline", |b| {
+        b.iter(|| {
+            serde_json::from_slice::<
+                Notification<
+                    <ChannelPointsCustomRewardRedemptionAddV1 as EventSubscription>::Payload,
+                >,
+            >(black_box(body))
+            .unwrap()
+        });
+    });
+    c.bench_function(&format!("decode_payload_in_place ({backend})"), |b| {
+        // the body is overwritten, so every iteration gets a fresh copy (which isn't timed)
+        b.iter_batched_ref(
+            || body.to_vec(),
+            |body| {
+                decode_payload_in_place::<ChannelPointsCustomRewardRedemptionAddV1>(
+                    MessageType::Notification,
+                    black_box(body),
+                    None,
+                )
+                .unwrap()
+            },
+            BatchSize::SmallInput,
+        );
+    });
+}
+
+criterion_group!(benches, decode);
+criterion_main!(benches);
@@ -0,0 +1,90 @@
+//! Buffering request bodies of any [`http_body::Body`] (1.0).
+//!
+//! Signatures are checked over the complete body, so it has to be buffered before a
+//! request can be verified. The framework crates adapt their own body types to
+//! `http-body` 1.0 and read them through this module, which keeps the verification
+//! independent of the `http-body` version a framework is built on. Bodies of hyper 1.0
+//! or `http-body-util` can be passed directly:
+//!
+//! ```
+//! # use eventsub_common::body::{read_body, MAX_BODY_SIZE};
+//! # use http_body_util::Full;
+//! # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+//! let body = read_body(Full::new(&b"{}"[..]), MAX_BODY_SIZE)
+//!     .await
+//!     .unwrap();
+//! assert_eq!(&body[..], b"{}");
+//! # });
+//! ```
+//!
+//! Trailers aren't covered by the signature, so they're skipped.
+
+use bytes::{Buf, BufMut, BytesMut};
+use http_body::Body;
+use std::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+/// The maximum body size the framework extractors accept (10MB).
+pub const MAX_BODY_SIZE: usize = 10_000_000;
+
+/// Errors when reading a body.
+#[derive(Debug, thiserror::Error)]
+pub enum BodyError<E> {
+    /// The body was larger than the limit (in bytes).
+    #[error("The request was too large (> {0} bytes)")]
+    TooLarge(usize),
+    /// The body returned an error.
+    #[error("Couldn't read the body: {0}")]
+    Body(E),
+}
+
+/// Read the complete body into memory.
+///
+/// The buffer is mutable, so it can be decoded in place (see
+/// [`decode_payload_in_place`](crate::decode::decode_payload_in_place)).
+///
+/// ## Errors
+///
+/// Returns [`BodyError::TooLarge`] if the body (or its announced size) exceeds `limit`
+/// bytes and [`BodyError::Body`] if the body returns an error.
+pub async fn read_body<B: Body>(body: B, limit: usize) -> Result<BytesMut, BodyError<B::Error>> {
+    let lower = body.size_hint().lower();
+    if lower > limit as u64 {
+        return Err(BodyError::TooLarge(limit));
+    }
+    let mut body = std::pin::pin!(body);
+    let mut buf = BytesMut::with_capacity(lower as usize);
+    std::future::poll_fn(|cx| poll_read_body(body.as_mut(), cx, &mut buf, limit)).await?;
+    Ok(buf)
+}
+
+/// Poll `body` until it ends, appending its data to `buf`.
+///
+/// This is the building block of [`read_body`] for hand-written futures.
+///
+/// ## Errors
+///
+/// See [`read_body`].
+pub fn poll_read_body<B: Body + ?Sized>(
+    mut body: Pin<&mut B>,
+    cx: &mut Context<'_>,
+    buf: &mut BytesMut,
+    limit: usize,
+) -> Poll<Result<(), BodyError<B::Error>>> {
+    loop {
+        match ready!(body.as_mut().poll_frame(cx)) {
+            Some(Ok(frame)) => {
+                if let Ok(data) = frame.into_data() {
+                    if buf.len() + data.remaining() > limit {
+                        return Poll::Ready(Err(BodyError::TooLarge(limit)));
+                    }
+                    buf.put(data);
+                }
+            }
+            Some(Err(e)) => return Poll::Ready(Err(BodyError::Body(e))),
+            None => return Poll::Ready(Ok(())),
+        }
+    }
+}
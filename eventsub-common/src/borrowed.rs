@@ -0,0 +1,89 @@
+//! Borrowed mirrors of high-volume events.
+//!
+//! [`EventSubscription`](crate::types::EventSubscription) requires its types to be
+//! [`DeserializeOwned`](serde::de::DeserializeOwned), so the extractors can't hand out events
+//! borrowing from the request body. For events that arrive frequently, like chat messages,
+//! allocating every string can be noticeable. The types in this module borrow from the
+//! verified body instead. Use [`notification`] or
+//! [`decode_borrowed`](crate::decode::decode_borrowed) to deserialize them.
+//!
+//! Strings are [`Cow`]s, since strings containing escape sequences can't be borrowed.
+
+use crate::Notification;
+use serde::Deserialize;
+use std::borrow::Cow;
+
+/// Deserialize a notification whose event borrows from `body`.
+///
+/// Only call this with a body that was already verified.
+///
+/// ## Errors
+///
+/// Returns an error if the body isn't a valid notification for `E`.
+pub fn notification<'a, E: Deserialize<'a>>(
+    body: &'a [u8],
+) -> Result<Notification<E>, serde_json::Error> {
+    serde_json::from_slice(body)
+}
+
+/// Borrowed mirror of the [`channel.chat.message`](https://dev.twitch.tv/docs/eventsub/eventsub-subscription-types/#channelchatmessage) event.
+///
+/// Only the most commonly used fields are included.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ChannelChatMessage<'a> {
+    /// The broadcaster user ID.
+    #[serde(borrow)]
+    pub broadcaster_user_id: Cow<'a, str>,
+    /// The broadcaster login.
+    #[serde(borrow)]
+    pub broadcaster_user_login: Cow<'a, str>,
+    /// The broadcaster display name.
+    #[serde(borrow)]
+    pub broadcaster_user_name: Cow<'a, str>,
+    /// The user ID of the user that sent the message.
+    #[serde(borrow)]
+    pub chatter_user_id: Cow<'a, str>,
+    /// The user login of the user that sent the message.
+    #[serde(borrow)]
+    pub chatter_user_login: Cow<'a, str>,
+    /// The user display name of the user that sent the message.
+    #[serde(borrow)]
+    pub chatter_user_name: Cow<'a, str>,
+    /// A UUID that identifies the message.
+    #[serde(borrow)]
+    pub message_id: Cow<'a, str>,
+    /// The message.
+    #[serde(borrow)]
+    pub message: ChatMessage<'a>,
+    /// The color of the user's name in the chat room (may be empty).
+    #[serde(borrow)]
+    pub color: Cow<'a, str>,
+    /// The type of message (e.g. `text`).
+    #[serde(borrow)]
+    pub message_type: Cow<'a, str>,
+}
+
+/// The message of a [`ChannelChatMessage`].
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ChatMessage<'a> {
+    /// The chat message in plain text.
+    #[serde(borrow)]
+    pub text: Cow<'a, str>,
+    /// Ordered list of chat message fragments.
+    #[serde(borrow)]
+    pub fragments: Vec<ChatMessageFragment<'a>>,
+}
+
+/// A fragment of a [`ChatMessage`].
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ChatMessageFragment<'a> {
+    /// The type of fragment (e.g. `text`, `emote`, `cheermote` or `mention`).
+    #[serde(borrow, rename = "type")]
+    pub type_: Cow<'a, str>,
+    /// The fragment's text.
+    #[serde(borrow)]
+    pub text: Cow<'a, str>,
+}
@@ -0,0 +1,146 @@
+//! Reports of how a request is read and verified, for debugging failing subscriptions.
+//!
+//! **This is insecure.** A [`Report`] reveals the start of the expected signature and
+//! doesn't reject anything, so an endpoint responding with it helps forging requests.
+//! Only mount it temporarily while investigating, never in production.
+
+use crate::{
+    headers::{
+        self, HeaderMapExt, MESSAGE_ID, MESSAGE_RETRY, MESSAGE_SIGNATURE, MESSAGE_TIMESTAMP,
+        MESSAGE_TYPE, SUBSCRIPTION_TYPE, SUBSCRIPTION_VERSION,
+    },
+    secret::HmacKey,
+    verify, MessageType,
+};
+use chrono::{DateTime, Utc};
+use hmac::Mac;
+use serde::Serialize;
+
+/// Number of hex characters of the signatures included in a [`Report`].
+pub const SIGNATURE_PREFIX_LEN: usize = 8;
+
+/// What the crate saw in a request.
+///
+/// See the [module documentation](self) - never expose this in production.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Report {
+    /// The eventsub headers and their values ([`None`] if missing).
+    ///
+    /// The signature is shortened to its first [`SIGNATURE_PREFIX_LEN`] hex characters.
+    pub headers: Vec<HeaderReport>,
+    /// The error from reading the headers, if any (the subscription type isn't checked).
+    pub header_error: Option<String>,
+    /// The message type, if it's present and recognized.
+    pub message_type: Option<&'static str>,
+    /// Seconds since the timestamp of the message, if it's present and well-formed.
+    pub timestamp_age_secs: Option<i64>,
+    /// The first characters of the provided signature.
+    pub provided_signature: Option<String>,
+    /// The first characters of the signature computed with the configured key.
+    ///
+    /// [`None`] if the id or timestamp is missing or no key is configured.
+    pub computed_signature: Option<String>,
+    /// Whether the full signatures match.
+    pub signature_valid: bool,
+    /// Length of the body in bytes.
+    pub body_len: usize,
+}
+
+/// A header in a [`Report`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HeaderReport {
+    /// Name of the header.
+    pub name: &'static str,
+    /// The value (truncated to [`MAX_VALUE_LEN`](headers::MAX_VALUE_LEN) characters), if present.
+    pub value: Option<Box<str>>,
+}
+
+/// Report how the request with `headers` and `body` is read and verified with `key`.
+///
+/// Nothing is rejected - problems are recorded in the report.
+/// Pass [`None`] as the key if it isn't available, the signature isn't computed then.
+#[cfg(feature = "clock")]
+pub fn report<M: HeaderMapExt, K: HmacKey + ?Sized>(
+    headers: &M,
+    key: Option<&K>,
+    body: &[u8],
+) -> Report {
+    report_at(headers, key, body, Utc::now())
+}
+
+/// Like [`report`], but measures the message age against `now`
+/// instead of reading the system clock.
+pub fn report_at<M: HeaderMapExt, K: HmacKey + ?Sized>(
+    headers: &M,
+    key: Option<&K>,
+    body: &[u8],
+    now: DateTime<Utc>,
+) -> Report {
+    let header_reports = [
+        MESSAGE_ID,
+        MESSAGE_TYPE,
+        MESSAGE_SIGNATURE,
+        MESSAGE_TIMESTAMP,
+        MESSAGE_RETRY,
+        SUBSCRIPTION_TYPE,
+        SUBSCRIPTION_VERSION,
+    ]
+    .into_iter()
+    .map(|name| HeaderReport {
+        name,
+        value: headers.get(name).map(|value| {
+            if name == MESSAGE_SIGNATURE {
+                signature_prefix(value.as_bytes()).into()
+            } else {
+                headers::truncated(value)
+            }
+        }),
+    })
+    .collect();
+
+    let provided = headers.get(MESSAGE_SIGNATURE).map(|value| value.as_bytes());
+    let computed = key
+        .zip(headers.get(MESSAGE_ID).zip(headers.get(MESSAGE_TIMESTAMP)))
+        .and_then(|(key, (id, timestamp))| {
+            let mut mac = verify::init_mac(key, id.as_bytes(), timestamp.as_bytes()).ok()?;
+            mac.update(body);
+            Some(format!(
+                "sha256={}",
+                hex::encode(mac.finalize().into_bytes())
+            ))
+        });
+
+    Report {
+        headers: header_reports,
+        header_error: headers::read_eventsub_headers_untyped_at(headers, now)
+            .err()
+            .map(|e| e.to_string()),
+        message_type: headers
+            .get(MESSAGE_TYPE)
+            .and_then(|value| MessageType::try_from(value).ok())
+            .map(MessageType::as_str),
+        timestamp_age_secs: headers
+            .get(MESSAGE_TIMESTAMP)
+            .and_then(|value| headers::parse_timestamp(value).ok())
+            .map(|timestamp| (now - timestamp).num_seconds()),
+        provided_signature: provided.map(signature_prefix),
+        signature_valid: computed
+            .as_deref()
+            .zip(provided)
+            .is_some_and(|(computed, provided)| computed.as_bytes().eq_ignore_ascii_case(provided)),
+        computed_signature: computed.as_deref().map(str::as_bytes).map(signature_prefix),
+        body_len: body.len(),
+    }
+}
+
+/// Shorten a signature header value to `sha256=` and [`SIGNATURE_PREFIX_LEN`] characters.
+fn signature_prefix(value: &[u8]) -> String {
+    let value = String::from_utf8_lossy(value);
+    let hex = value.strip_prefix("sha256=").unwrap_or(&value);
+    let prefix: String = hex.chars().take(SIGNATURE_PREFIX_LEN).collect();
+    if value.starts_with("sha256=") {
+        format!("sha256={prefix}")
+    } else {
+        prefix
+    }
+}
@@ -0,0 +1,328 @@
+//! Deserialization of verified eventsub payloads.
+//!
+//! ## JSON backend
+//!
+//! Payloads are deserialized with `serde_json` by default. With the `simd-json` feature,
+//! [`decode_payload_in_place`], [`decode_batch_in_place`] and [`decode_any_in_place`] use
+//! `simd-json` instead, which the extractors of the framework crates call on the buffered
+//! body. `simd-json` needs a mutable buffer: strings with escape sequences are unescaped in
+//! place, so the body is garbled there afterwards - the `body()` accessors of the
+//! extractors return it in that state. Errors are converted to
+//! [`serde_json::Error`]s and lose their position. `simd-json` doesn't limit the recursion
+//! itself, so it's held to `serde_json`'s limit of 128 levels while deserializing.
+//!
+//! The functions taking an immutable body ([`decode_payload`], [`decode_borrowed`], ...)
+//! always use `serde_json`, so the body isn't copied.
+//!
+//! On the notification of the `decode` benchmark (about 1KB, an x86-64 Xeon with AVX2),
+//! `serde_json` took 2.9µs and `simd-json` 2.7µs when built with `-C target-cpu=native`.
+//! Without the CPU features enabled at compile time, `simd-json` uses its scalar fallback
+//! and was slower (3.1µs). Eventsub payloads are small, so the gain is modest - measure
+//! with your own events (`cargo bench -p eventsub-common --features simd-json`) before
+//! enabling the feature.
+
+use crate::{
+    depth::Limited,
+    types::{EventSubSubscription, EventSubscription},
+    EventsubPayload, MessageType,
+};
+use serde::{
+    de::{DeserializeOwned, IgnoredAny, MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use std::{borrow::Cow, fmt};
+
+/// Deserialize the body of an eventsub request into an [`EventsubPayload`].
+///
+/// The event of notifications is the [payload](EventSubscription::Payload) of `P`,
+/// e.g. who redeemed which reward.
+///
+/// `max_depth` limits how deeply arrays and objects may be nested in the body. It's checked
+/// while deserializing, skipped fields included. `serde_json` already stops recursing after
+/// 128 levels, so [`None`] keeps its behavior and values above 128 won't raise that limit.
+///
+/// ## Errors
+///
+/// Returns an error if the body isn't a valid payload for `message_type`
+/// or if it's nested deeper than `max_depth`.
+pub fn decode_payload<P: EventSubscription>(
+    message_type: MessageType,
+    body: &[u8],
+    max_depth: Option<usize>,
+) -> Result<EventsubPayload<P::Payload>, serde_json::Error> {
+    decode_as(message_type, body, max_depth)
+}
+
+/// Like [`decode_payload`], but parses `body` in place with the `simd-json` feature
+/// (see the [module docs](self)).
+///
+/// ## Errors
+///
+/// See [`decode_payload`].
+pub fn decode_payload_in_place<P: EventSubscription>(
+    message_type: MessageType,
+    body: &mut [u8],
+    max_depth: Option<usize>,
+) -> Result<EventsubPayload<P::Payload>, serde_json::Error> {
+    decode_as(message_type, body, max_depth)
+}
+
+fn decode_as<E: DeserializeOwned>(
+    message_type: MessageType,
+    body: impl Input,
+    max_depth: Option<usize>,
+) -> Result<EventsubPayload<E>, serde_json::Error> {
+    match message_type {
+        MessageType::Verification => body.parse(max_depth).map(EventsubPayload::Verification),
+        MessageType::Revocation => body.parse(max_depth).map(EventsubPayload::Revocation),
+        MessageType::Notification => body.parse(max_depth).map(EventsubPayload::Notification),
+    }
+}
+
+/// Deserialize the body of an eventsub request into an [`EventsubPayload`] borrowing from it.
+///
+/// Unlike [`decode_payload`], the event can be any type borrowing from `body`, such as the
+/// mirrors in the `borrowed` module. The body is parsed once, strings without escape
+/// sequences aren't copied. See [`decode_payload`] for `max_depth`.
+///
+/// ## Errors
+///
+/// Returns an error if the body isn't a valid payload for `message_type`
+/// or if it's nested deeper than `max_depth`.
+pub fn decode_borrowed<'a, E: Deserialize<'a>>(
+    message_type: MessageType,
+    body: &'a [u8],
+    max_depth: Option<usize>,
+) -> Result<EventsubPayload<E>, serde_json::Error> {
+    match message_type {
+        MessageType::Verification => from_slice(body, max_depth).map(EventsubPayload::Verification),
+        MessageType::Revocation => from_slice(body, max_depth).map(EventsubPayload::Revocation),
+        MessageType::Notification => from_slice(body, max_depth).map(EventsubPayload::Notification),
+    }
+}
+
+/// Deserialize the body of an eventsub request holding a payload or a JSON array of payloads.
+///
+/// Twitch sends one payload per request, but tools replaying recorded deliveries may
+/// send a batch. All payloads in a batch have the message type from the headers.
+/// A single payload is returned as a batch of one. See [`decode_payload`] for `max_depth`.
+///
+/// ## Errors
+///
+/// Returns an error if the body is neither an object nor a non-empty array, if any payload
+/// isn't valid for `message_type`, or if it's nested deeper than `max_depth`.
+pub fn decode_batch<P: EventSubscription>(
+    message_type: MessageType,
+    body: &[u8],
+    max_depth: Option<usize>,
+) -> Result<Vec<EventsubPayload<P::Payload>>, serde_json::Error> {
+    batch_as(message_type, body, max_depth)
+}
+
+/// Like [`decode_batch`], but parses `body` in place with the `simd-json` feature
+/// (see the [module docs](self)).
+///
+/// ## Errors
+///
+/// See [`decode_batch`].
+pub fn decode_batch_in_place<P: EventSubscription>(
+    message_type: MessageType,
+    body: &mut [u8],
+    max_depth: Option<usize>,
+) -> Result<Vec<EventsubPayload<P::Payload>>, serde_json::Error> {
+    batch_as(message_type, body, max_depth)
+}
+
+fn batch_as<E: DeserializeOwned>(
+    message_type: MessageType,
+    body: impl Input,
+    max_depth: Option<usize>,
+) -> Result<Vec<EventsubPayload<E>>, serde_json::Error> {
+    fn all<T, P>(
+        body: impl Input,
+        max_depth: Option<usize>,
+        wrap: fn(T) -> EventsubPayload<P>,
+    ) -> Result<Vec<EventsubPayload<P>>, serde_json::Error>
+    where
+        T: DeserializeOwned,
+    {
+        // the array adds a level
+        body.parse::<Vec<T>>(max_depth.map(|max_depth| max_depth + 1))
+            .map(|payloads| payloads.into_iter().map(wrap).collect())
+    }
+
+    let first = body.bytes().iter().find(|byte| !byte.is_ascii_whitespace());
+    match first {
+        Some(b'{') => decode_as(message_type, body, max_depth).map(|payload| vec![payload]),
+        Some(b'[') => {
+            let payloads = match message_type {
+                MessageType::Verification => all(body, max_depth, EventsubPayload::Verification),
+                MessageType::Revocation => all(body, max_depth, EventsubPayload::Revocation),
+                MessageType::Notification => all(body, max_depth, EventsubPayload::Notification),
+            }?;
+            if payloads.is_empty() {
+                return Err(serde::de::Error::custom("the batch is empty"));
+            }
+            Ok(payloads)
+        }
+        _ => Err(serde::de::Error::custom(
+            "expected a payload or an array of payloads",
+        )),
+    }
+}
+
+/// Deserialize the body of an eventsub request for any subscription type and version.
+///
+/// The event of notifications is kept as a [`serde_json::Value`], since its type isn't known.
+/// See [`decode_payload`] for `max_depth`.
+///
+/// ## Errors
+///
+/// Returns an error if the body isn't a valid payload for `message_type`
+/// or if it's nested deeper than `max_depth`.
+pub fn decode_any(
+    message_type: MessageType,
+    body: &[u8],
+    max_depth: Option<usize>,
+) -> Result<EventsubPayload<serde_json::Value>, serde_json::Error> {
+    decode_as(message_type, body, max_depth)
+}
+
+/// Like [`decode_any`], but parses `body` in place with the `simd-json` feature
+/// (see the [module docs](self)).
+///
+/// ## Errors
+///
+/// See [`decode_any`].
+pub fn decode_any_in_place(
+    message_type: MessageType,
+    body: &mut [u8],
+    max_depth: Option<usize>,
+) -> Result<EventsubPayload<serde_json::Value>, serde_json::Error> {
+    decode_as(message_type, body, max_depth)
+}
+
+/// Read only `subscription.id` from the body of an eventsub request.
+///
+/// Everything else is skipped without being deserialized, which makes this
+/// considerably cheaper than [`decode_payload`] when only the id is needed.
+/// The body should be verified first, as this doesn't check the signature.
+///
+/// ## Errors
+///
+/// Returns an error if the body isn't valid JSON or has no `subscription.id` string.
+pub fn subscription_id(body: &[u8]) -> Result<Cow<'_, str>, serde_json::Error> {
+    #[derive(Deserialize)]
+    struct Subscription<'a> {
+        #[serde(borrow)]
+        id: Cow<'a, str>,
+    }
+
+    #[derive(Deserialize)]
+    struct Payload<'a> {
+        #[serde(borrow)]
+        subscription: Subscription<'a>,
+    }
+
+    from_slice::<Payload<'_>>(body, None).map(|payload| payload.subscription.id)
+}
+
+/// Read the `subscription` from the start of the body of an eventsub request.
+///
+/// Twitch sends the subscription before the event, so it can be read from the first
+/// few kilobytes of a body that's still streaming in. Everything after the subscription
+/// is ignored, even if it's cut off. Only use a prefix of a verified body, as this
+/// doesn't check the signature.
+///
+/// ## Errors
+///
+/// Returns an error if `prefix` doesn't hold a complete and valid `subscription`.
+pub fn leading_subscription(prefix: &[u8]) -> Result<EventSubSubscription, serde_json::Error> {
+    /// Stores the subscription as soon as it's read, the rest may be missing.
+    struct Leading<'a>(&'a mut Option<EventSubSubscription>);
+
+    impl<'de> Visitor<'de> for Leading<'_> {
+        type Value = ();
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an eventsub payload")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+            while let Some(key) = map.next_key::<String>()? {
+                if key == "subscription" {
+                    *self.0 = Some(map.next_value()?);
+                    return Ok(());
+                }
+                map.next_value::<IgnoredAny>()?;
+            }
+            Ok(())
+        }
+    }
+
+    let mut subscription = None;
+    let read =
+        serde_json::Deserializer::from_slice(prefix).deserialize_map(Leading(&mut subscription));
+    match (subscription, read) {
+        // the end of the object is likely cut off, which doesn't matter anymore
+        (Some(subscription), _) => Ok(subscription),
+        (None, Err(e)) => Err(e),
+        (None, Ok(())) => Err(serde::de::Error::missing_field("subscription")),
+    }
+}
+
+fn from_slice<'a, T: Deserialize<'a>>(
+    body: &'a [u8],
+    max_depth: Option<usize>,
+) -> Result<T, serde_json::Error> {
+    let mut deserializer = serde_json::Deserializer::from_slice(body);
+    let value = match max_depth {
+        Some(max_depth) => T::deserialize(Limited::new(&mut deserializer, max_depth))?,
+        None => T::deserialize(&mut deserializer)?,
+    };
+    deserializer.end()?;
+    Ok(value)
+}
+
+/// A body the owned payloads are deserialized from.
+trait Input: Sized {
+    fn bytes(&self) -> &[u8];
+
+    fn parse<T: DeserializeOwned>(self, max_depth: Option<usize>) -> Result<T, serde_json::Error>;
+}
+
+impl Input for &[u8] {
+    fn bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn parse<T: DeserializeOwned>(self, max_depth: Option<usize>) -> Result<T, serde_json::Error> {
+        from_slice(self, max_depth)
+    }
+}
+
+impl Input for &mut [u8] {
+    fn bytes(&self) -> &[u8] {
+        self
+    }
+
+    #[cfg(not(feature = "simd-json"))]
+    fn parse<T: DeserializeOwned>(self, max_depth: Option<usize>) -> Result<T, serde_json::Error> {
+        from_slice(self, max_depth)
+    }
+
+    #[cfg(feature = "simd-json")]
+    fn parse<T: DeserializeOwned>(self, max_depth: Option<usize>) -> Result<T, serde_json::Error> {
+        // simd-json has no recursion limit of its own
+        let max_depth = max_depth.map_or(SERDE_JSON_MAX_DEPTH, |max_depth| {
+            max_depth.min(SERDE_JSON_MAX_DEPTH)
+        });
+        simd_json::Deserializer::from_slice(self)
+            .and_then(|mut deserializer| T::deserialize(Limited::new(&mut deserializer, max_depth)))
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Nesting depth after which `serde_json` stops recursing.
+#[cfg(feature = "simd-json")]
+const SERDE_JSON_MAX_DEPTH: usize = 128;
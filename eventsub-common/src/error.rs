@@ -0,0 +1,64 @@
+//! Context for errors that happen after the headers were read.
+
+use std::{borrow::Cow, error::Error, fmt};
+
+/// An error together with the subscription type and message id of the failed request.
+///
+/// Both are read from the headers, so this is only available for errors that happen
+/// after the headers were parsed (for example a signature mismatch).
+/// The [`Display`](fmt::Display) output is meant for logs:
+/// `The provided signature wasn't expected (channel.follow id=abc)`.
+#[derive(Debug)]
+pub struct ErrorContext<E> {
+    error: E,
+    subscription_type: Cow<'static, str>,
+    message_id: String,
+}
+
+impl<E> ErrorContext<E> {
+    /// Attach the subscription type and message id to an error.
+    pub fn new(
+        error: E,
+        subscription_type: impl Into<Cow<'static, str>>,
+        message_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            error,
+            subscription_type: subscription_type.into(),
+            message_id: message_id.into(),
+        }
+    }
+
+    /// The underlying error (e.g. for metrics).
+    pub fn kind(&self) -> &E {
+        &self.error
+    }
+
+    /// `Twitch-Eventsub-Subscription-Type` of the request.
+    pub fn subscription_type(&self) -> &str {
+        &self.subscription_type
+    }
+
+    /// `Twitch-Eventsub-Message-Id` of the request.
+    pub fn message_id(&self) -> &str {
+        &self.message_id
+    }
+
+    /// Drop the context and return the underlying error.
+    pub fn into_inner(self) -> E {
+        self.error
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for ErrorContext<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({} id={})",
+            self.error, self.subscription_type, self.message_id
+        )
+    }
+}
+
+// The message of the error is already part of the `Display` output, so it's not a `source`.
+impl<E: Error> Error for ErrorContext<E> {}
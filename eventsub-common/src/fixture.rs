@@ -0,0 +1,174 @@
+//! Replaying recorded deliveries in tests.
+//!
+//! A fixture is a raw HTTP request as Twitch (or the [twitch-cli](https://dev.twitch.tv/docs/cli))
+//! sent it: an optional request line, the headers, an empty line and the body.
+//! The body is kept byte-for-byte, so the signature can be checked against it.
+//! If a `Content-Length` header is present, the body is cut to that length
+//! (editors like to add a trailing newline).
+//!
+//! Since deliveries older than ten minutes are rejected, a recorded fixture has to be
+//! [refreshed](Fixture::refresh) before it's replayed. This updates the timestamp and
+//! signs the unchanged body again.
+//!
+//! See `fixtures/README.md` in the repository for how to capture a fixture.
+
+use crate::{
+    headers::{MESSAGE_ID, MESSAGE_SIGNATURE, MESSAGE_TIMESTAMP},
+    verify,
+};
+use chrono::{SecondsFormat, Utc};
+use std::{io, path::Path};
+
+/// Errors when loading a [`Fixture`].
+#[derive(Debug, thiserror::Error)]
+pub enum FixtureError {
+    /// The file couldn't be read.
+    #[error("Couldn't read fixture: {0}")]
+    Io(#[from] io::Error),
+    /// There's no empty line separating the headers from the body.
+    #[error("Missing empty line before the body")]
+    MissingBody,
+    /// A header line is missing its colon.
+    #[error("Malformed header line: {0:?}")]
+    MalformedHeader(String),
+}
+
+/// A recorded eventsub delivery.
+///
+/// The default is an empty delivery without headers, to build one from scratch.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Fixture {
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Fixture {
+    /// Load a fixture from a file.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the file can't be read or isn't a valid fixture.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, FixtureError> {
+        Self::parse(&std::fs::read(path)?)
+    }
+
+    /// Parse a fixture from a raw HTTP request.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the request isn't a valid fixture.
+    pub fn parse(raw: &[u8]) -> Result<Self, FixtureError> {
+        let (head, body) = split_head(raw).ok_or(FixtureError::MissingBody)?;
+        let head = String::from_utf8_lossy(head);
+        let mut headers = Vec::new();
+        for line in head.lines() {
+            if line.is_empty() || (headers.is_empty() && line.contains(" HTTP/")) {
+                continue;
+            }
+            let (name, value) = line
+                .split_once(':')
+                .ok_or_else(|| FixtureError::MalformedHeader(line.to_owned()))?;
+            headers.push((name.trim().to_owned(), value.trim().to_owned()));
+        }
+        let mut fixture = Self {
+            headers,
+            body: body.to_vec(),
+        };
+        if let Some(length) = fixture
+            .header("Content-Length")
+            .and_then(|len| len.parse::<usize>().ok())
+        {
+            fixture.body.truncate(length);
+        }
+        Ok(fixture)
+    }
+
+    /// All headers in the order they were recorded.
+    pub fn headers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.headers.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Get the value of a header (case-insensitive).
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Set (or add) a header.
+    pub fn set_header(&mut self, name: &str, value: impl Into<String>) {
+        let value = value.into();
+        match self
+            .headers
+            .iter_mut()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        {
+            Some((_, v)) => *v = value,
+            None => self.headers.push((name.to_owned(), value)),
+        }
+    }
+
+    /// The raw body.
+    #[must_use]
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Replace the body.
+    ///
+    /// The delivery has to be [signed](Self::sign) again afterwards.
+    pub fn set_body(&mut self, body: impl Into<Vec<u8>>) {
+        self.body = body.into();
+    }
+
+    /// All headers as a [`http::HeaderMap`].
+    ///
+    /// ## Panics
+    ///
+    /// Panics if a header name or value is invalid.
+    #[must_use]
+    pub fn header_map(&self) -> http::HeaderMap {
+        self.headers()
+            .map(|(k, v)| {
+                (
+                    http::HeaderName::from_bytes(k.as_bytes()).expect("valid header name"),
+                    http::HeaderValue::from_str(v).expect("valid header value"),
+                )
+            })
+            .collect()
+    }
+
+    /// Set the timestamp to now and sign the delivery with `secret`.
+    ///
+    /// The body isn't modified.
+    pub fn refresh(&mut self, secret: &[u8]) {
+        let timestamp = Utc::now().to_rfc3339_opts(SecondsFormat::Nanos, true);
+        self.set_header(MESSAGE_TIMESTAMP, timestamp);
+        self.sign(secret);
+    }
+
+    /// Sign the delivery with `secret` without changing the timestamp.
+    pub fn sign(&mut self, secret: &[u8]) {
+        let signature = verify::sign(
+            secret,
+            self.header(MESSAGE_ID).unwrap_or_default(),
+            self.header(MESSAGE_TIMESTAMP).unwrap_or_default(),
+            &self.body,
+        );
+        self.set_header(MESSAGE_SIGNATURE, signature);
+    }
+}
+
+fn split_head(raw: &[u8]) -> Option<(&[u8], &[u8])> {
+    [&b"\r\n\r\n"[..], b"\n\n"]
+        .iter()
+        .filter_map(|sep| {
+            raw.windows(sep.len())
+                .position(|w| w == *sep)
+                .map(|pos| (pos, sep.len()))
+        })
+        .min_by_key(|(pos, _)| *pos)
+        .map(|(pos, len)| (&raw[..pos], &raw[pos + len..]))
+}
@@ -1,7 +1,7 @@
 use crate::{types::EventSubscription, MessageType};
 use chrono::{DateTime, Duration, Utc};
 use http::HeaderValue;
-use std::str::FromStr;
+use std::fmt;
 
 pub const SUBSCRIPTION_TYPE: &str = "Twitch-Eventsub-Subscription-Type";
 pub const SUBSCRIPTION_VERSION: &str = "Twitch-Eventsub-Subscription-Version";
@@ -9,6 +9,7 @@ pub const MESSAGE_SIGNATURE: &str = "Twitch-Eventsub-Message-Signature";
 pub const MESSAGE_TYPE: &str = "Twitch-Eventsub-Message-Type";
 pub const MESSAGE_ID: &str = "Twitch-Eventsub-Message-Id";
 pub const MESSAGE_TIMESTAMP: &str = "Twitch-Eventsub-Message-Timestamp";
+pub const MESSAGE_RETRY: &str = "Twitch-Eventsub-Message-Retry";
 
 pub trait HeaderMapExt {
     fn get(&self, key: &str) -> Option<&HeaderValue>;
@@ -39,6 +40,12 @@ pub trait HeaderMapExt {
         self.get(MESSAGE_TIMESTAMP)
             .ok_or(InvalidHeaders::Missing(HeaderType::Timestamp))
     }
+    /// How often Twitch already tried to deliver this message (`Twitch-Eventsub-Message-Retry`).
+    ///
+    /// Returns [`None`] if the header is missing or isn't a number.
+    fn get_message_retry(&self) -> Option<u32> {
+        self.get(MESSAGE_RETRY)?.to_str().ok()?.parse().ok()
+    }
 }
 
 impl HeaderMapExt for http::HeaderMap {
@@ -61,8 +68,40 @@ pub struct PayloadHeaders {
 
 pub struct ParsedHeaders<'a> {
     pub payload: PayloadHeaders,
-    pub id_bytes: &'a [u8],
+    pub id: &'a str,
     pub timestamp_bytes: &'a [u8],
+    pub timestamp: DateTime<Utc>,
+    pub subscription_type: &'a str,
+    pub subscription_version: &'a str,
+}
+
+impl ParsedHeaders<'_> {
+    /// Copy the metadata of the message out of the headers.
+    pub fn meta(&self) -> EventMeta {
+        EventMeta {
+            message_id: self.id.to_owned(),
+            message_type: self.payload.message_type,
+            timestamp: self.timestamp,
+            subscription_type: self.subscription_type.to_owned(),
+            subscription_version: self.subscription_version.to_owned(),
+        }
+    }
+}
+
+/// Metadata of an eventsub message, read from the request headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct EventMeta {
+    /// `Twitch-Eventsub-Message-Id` - unique for each message, but the same for retries.
+    pub message_id: String,
+    /// `Twitch-Eventsub-Message-Type`
+    pub message_type: MessageType,
+    /// `Twitch-Eventsub-Message-Timestamp` - when the message was sent.
+    pub timestamp: DateTime<Utc>,
+    /// `Twitch-Eventsub-Subscription-Type`
+    pub subscription_type: String,
+    /// `Twitch-Eventsub-Subscription-Version`
+    pub subscription_version: String,
 }
 
 /// The [request headers](https://dev.twitch.tv/docs/eventsub/handling-webhook-events#list-of-request-headers) twitch will send.
@@ -83,6 +122,8 @@ pub enum HeaderType {
 }
 
 /// Common Errors
+///
+/// Use [`InvalidHeaderValue::new`] to get the malformed value as well.
 #[derive(Debug, thiserror::Error, Copy, Clone, PartialEq, Eq)]
 pub enum InvalidHeaders {
     #[error("Missing header {0:?}")]
@@ -101,45 +142,335 @@ pub enum InvalidHeaders {
     BadMessageType,
     #[error("Wrong subscription type - expected {0}")]
     WrongSubscriptionType(&'static str),
+    #[error("The message id wasn't valid utf8")]
+    IdNotUtf8,
+    #[error("The header {0:?} wasn't valid utf8")]
+    NotUtf8(HeaderType),
 }
 
+impl InvalidHeaders {
+    /// The header this error is about.
+    pub fn header(self) -> HeaderType {
+        match self {
+            Self::Missing(header) | Self::NotUtf8(header) => header,
+            Self::SignatureTooShort | Self::SignatureNotHex => HeaderType::Signature,
+            Self::VersionMismatch(_) => HeaderType::SubscriptionVersion,
+            Self::BadTimestamp | Self::MessageTooOld => HeaderType::Timestamp,
+            Self::BadMessageType => HeaderType::MessageType,
+            Self::WrongSubscriptionType(_) => HeaderType::SubscriptionType,
+            Self::IdNotUtf8 => HeaderType::Id,
+        }
+    }
+}
+
+/// An [`InvalidHeaders`] together with the value of the header it's about.
+///
+/// The value is copied (truncated to [`MAX_VALUE_LEN`] characters) to help diagnosing
+/// misbehaving proxies, so this is only built on request - the extractors reject with the
+/// plain [`InvalidHeaders`].
+///
+/// ```
+/// # use eventsub_common::headers::{InvalidHeaderValue, InvalidHeaders, MESSAGE_TYPE};
+/// # use http::{HeaderMap, HeaderValue};
+/// let mut headers = HeaderMap::new();
+/// headers.insert(MESSAGE_TYPE, HeaderValue::from_static("notification "));
+///
+/// let error = InvalidHeaderValue::new(InvalidHeaders::BadMessageType, &headers);
+/// assert_eq!(error.value.as_deref(), Some("notification "));
+/// assert_eq!(
+///     error.to_string(),
+///     r#"This message type is not recognized: "notification ""#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidHeaderValue {
+    /// What was wrong with the header.
+    pub error: InvalidHeaders,
+    /// The value of the header, [`None`] if it's missing.
+    pub value: Option<Box<str>>,
+}
+
+impl InvalidHeaderValue {
+    /// Copy the value of the header `error` is about from `headers`.
+    pub fn new<M: HeaderMapExt>(error: InvalidHeaders, headers: &M) -> Self {
+        Self {
+            error,
+            value: headers.get(header_name(error.header())).map(truncated),
+        }
+    }
+}
+
+impl fmt::Display for InvalidHeaderValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.value {
+            Some(value) => write!(f, "{}: {value:?}", self.error),
+            None => fmt::Display::fmt(&self.error, f),
+        }
+    }
+}
+
+impl std::error::Error for InvalidHeaderValue {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<InvalidHeaders> for InvalidHeaderValue {
+    fn from(error: InvalidHeaders) -> Self {
+        Self { error, value: None }
+    }
+}
+
+fn header_name(header: HeaderType) -> &'static str {
+    match header {
+        HeaderType::Id => MESSAGE_ID,
+        HeaderType::MessageType => MESSAGE_TYPE,
+        HeaderType::Signature => MESSAGE_SIGNATURE,
+        HeaderType::Timestamp => MESSAGE_TIMESTAMP,
+        HeaderType::SubscriptionVersion => SUBSCRIPTION_VERSION,
+        HeaderType::SubscriptionType => SUBSCRIPTION_TYPE,
+    }
+}
+
+/// Read and check the eventsub headers of a request.
+///
+/// The checks are done in this order, and the first failing one is returned:
+///
+/// 1. All headers are present ([`InvalidHeaders::Missing`])
+/// 2. The subscription type matches `P` ([`InvalidHeaders::WrongSubscriptionType`])
+/// 3. The subscription version matches `P` ([`InvalidHeaders::VersionMismatch`])
+/// 4. The subscription type and version ([`InvalidHeaders::NotUtf8`]), message id
+///    ([`InvalidHeaders::IdNotUtf8`]), message type, signature and timestamp are well-formed
+/// 5. The message isn't older than ten minutes ([`InvalidHeaders::MessageTooOld`])
+///
+/// ## Errors
+///
+/// Returns an error if any of the checks fails. Use [`validate_all_headers`] to get all
+/// problems with the headers instead of the first one.
+#[cfg(feature = "clock")]
 pub fn read_eventsub_headers<M: HeaderMapExt, P: EventSubscription>(
     headers: &M,
 ) -> Result<ParsedHeaders<'_>, InvalidHeaders> {
-    headers
-        .get_subscription_type()
-        .ok()
-        .filter(|s| P::EVENT_TYPE.to_str().as_bytes() == s.as_bytes())
-        .ok_or_else(|| InvalidHeaders::WrongSubscriptionType(P::EVENT_TYPE.to_str()))?;
+    read_eventsub_headers_at::<M, P>(headers, Utc::now())
+}
 
-    let message_type = headers.get_message_type()?;
-    let signature = headers.get_signature()?;
-    if signature.len() <= 7 || !signature.as_bytes().starts_with(b"sha256=") {
-        return Err(InvalidHeaders::SignatureTooShort);
+/// Like [`read_eventsub_headers`], but checks the message age against `now`
+/// instead of reading the system clock.
+///
+/// Use this on targets without a clock (see the `clock` feature).
+///
+/// ## Errors
+///
+/// See [`read_eventsub_headers`].
+pub fn read_eventsub_headers_at<M: HeaderMapExt, P: EventSubscription>(
+    headers: &M,
+    now: DateTime<Utc>,
+) -> Result<ParsedHeaders<'_>, InvalidHeaders> {
+    read_headers(headers, Some((P::EVENT_TYPE.to_str(), P::VERSION)), now)
+}
+
+/// Read and check the eventsub headers of a request for any subscription.
+///
+/// This is like [`read_eventsub_headers`], but skips checking the subscription
+/// type and version. Use this if you only verify requests and forward them.
+///
+/// ## Errors
+///
+/// Returns an error if a header is missing or malformed or if the message is
+/// older than ten minutes.
+#[cfg(feature = "clock")]
+pub fn read_eventsub_headers_untyped<M: HeaderMapExt>(
+    headers: &M,
+) -> Result<ParsedHeaders<'_>, InvalidHeaders> {
+    read_headers(headers, None, Utc::now())
+}
+
+/// Like [`read_eventsub_headers_untyped`], but checks the message age against `now`
+/// instead of reading the system clock.
+///
+/// ## Errors
+///
+/// See [`read_eventsub_headers_untyped`].
+pub fn read_eventsub_headers_untyped_at<M: HeaderMapExt>(
+    headers: &M,
+    now: DateTime<Utc>,
+) -> Result<ParsedHeaders<'_>, InvalidHeaders> {
+    read_headers(headers, None, now)
+}
+
+/// Check all eventsub headers of a request and report every problem at once.
+///
+/// [`read_eventsub_headers`] stops at the first missing or malformed header, which is
+/// what the extractors want. When setting up a proxy or a tunnel, it's more helpful to
+/// see everything that's wrong with a request, so this checks each header on its own
+/// and collects the errors in header order (type, version, message type, signature,
+/// id, timestamp) with the malformed values. The subscription type and version are only
+/// checked for presence and valid UTF-8.
+///
+/// ## Errors
+///
+/// Returns all errors found if any header is missing or malformed, or if the
+/// message is older than ten minutes.
+#[cfg(feature = "clock")]
+pub fn validate_all_headers<M: HeaderMapExt>(headers: &M) -> Result<(), Vec<InvalidHeaderValue>> {
+    validate_all_headers_at(headers, Utc::now())
+}
+
+/// Like [`validate_all_headers`], but checks the message age against `now`
+/// instead of reading the system clock.
+///
+/// ## Errors
+///
+/// See [`validate_all_headers`].
+pub fn validate_all_headers_at<M: HeaderMapExt>(
+    headers: &M,
+    now: DateTime<Utc>,
+) -> Result<(), Vec<InvalidHeaderValue>> {
+    let checks = [
+        headers
+            .get_subscription_type()
+            .and_then(|value| utf8(value, HeaderType::SubscriptionType))
+            .map(drop),
+        headers
+            .get_subscription_version()
+            .and_then(|value| utf8(value, HeaderType::SubscriptionVersion))
+            .map(drop),
+        headers.get_message_type().map(drop),
+        headers.get_signature().and_then(parse_signature).map(drop),
+        headers
+            .get_message_id()
+            .and_then(|id| id.to_str().map_err(|_| InvalidHeaders::IdNotUtf8))
+            .map(drop),
+        headers
+            .get_message_timestamp()
+            .and_then(parse_timestamp)
+            .and_then(|timestamp| check_age(timestamp, now)),
+    ];
+    let errors: Vec<_> = checks
+        .into_iter()
+        .filter_map(Result::err)
+        .map(|error| InvalidHeaderValue::new(error, headers))
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
     }
-    let signature =
-        hex::decode(&signature.as_bytes()[7..]).map_err(|_| InvalidHeaders::SignatureNotHex)?;
+}
+
+/// Maximum number of characters of a malformed header value kept in [`InvalidHeaderValue`].
+pub const MAX_VALUE_LEN: usize = 64;
 
-    if headers.get_subscription_version()?.as_bytes() != P::VERSION.as_bytes() {
-        return Err(InvalidHeaders::VersionMismatch(P::VERSION));
+/// Copy a header value for an error, truncating long values.
+pub(crate) fn truncated(value: &HeaderValue) -> Box<str> {
+    let value = String::from_utf8_lossy(value.as_bytes());
+    match value.char_indices().nth(MAX_VALUE_LEN) {
+        Some((end, _)) => format!("{}...", &value[..end]).into(),
+        None => value.into(),
     }
+}
 
-    let id_header = headers.get_message_id()?;
-    let timestamp_header = headers.get_message_timestamp()?;
-    let timestamp = timestamp_header
+/// Check if the `Content-Type` of a request is `application/json` (parameters like `charset` are ignored).
+///
+/// Twitch always sends JSON, so other content types point to a misrouted request.
+pub fn is_json_content_type<M: HeaderMapExt>(headers: &M) -> bool {
+    headers
+        .get(http::header::CONTENT_TYPE.as_str())
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("application/json"))
+}
+
+/// Check if the message was retried more than `max_retries` times.
+///
+/// Messages without a (valid) retry header never exceed the limit,
+/// and neither does any message if `max_retries` is [`None`].
+/// The header isn't signed, so only act on this once the signature was checked.
+pub fn retries_exceeded<M: HeaderMapExt>(headers: &M, max_retries: Option<u32>) -> bool {
+    max_retries.is_some_and(|max| headers.get_message_retry().is_some_and(|retry| retry > max))
+}
+
+/// Parse the value of the `Twitch-Eventsub-Message-Timestamp` header.
+///
+/// Twitch sends RFC3339 timestamps with up to nanosecond precision (e.g. `2024-11-11T18:25:02.310938745Z`).
+///
+/// ## Errors
+///
+/// Returns [`InvalidHeaders::BadTimestamp`] if the value isn't an RFC3339 timestamp.
+pub fn parse_timestamp(value: &HeaderValue) -> Result<DateTime<Utc>, InvalidHeaders> {
+    value
         .to_str()
         .ok()
-        .and_then(|h| DateTime::<Utc>::from_str(h).ok())
-        .ok_or(InvalidHeaders::BadTimestamp)?;
-    if Utc::now() - timestamp > Duration::minutes(10) {
-        return Err(InvalidHeaders::MessageTooOld);
+        .and_then(|h| DateTime::parse_from_rfc3339(h).ok())
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .ok_or(InvalidHeaders::BadTimestamp)
+}
+
+/// Read the headers, checking the subscription type and version if `expected` is set.
+fn read_headers<'a, M: HeaderMapExt>(
+    headers: &'a M,
+    expected: Option<(&'static str, &'static str)>,
+    now: DateTime<Utc>,
+) -> Result<ParsedHeaders<'a>, InvalidHeaders> {
+    // Check that all headers are present first, then check the subscription,
+    // and only then look at the values, so setup errors are reported precisely.
+    let subscription_type = headers.get_subscription_type()?;
+    let subscription_version = headers.get_subscription_version()?;
+    let message_type = headers
+        .get(MESSAGE_TYPE)
+        .ok_or(InvalidHeaders::Missing(HeaderType::MessageType))?;
+    let signature = headers.get_signature()?;
+    let id_header = headers.get_message_id()?;
+    let timestamp_header = headers.get_message_timestamp()?;
+
+    if let Some((expected_type, expected_version)) = expected {
+        if subscription_type.as_bytes() != expected_type.as_bytes() {
+            return Err(InvalidHeaders::WrongSubscriptionType(expected_type));
+        }
+        if subscription_version.as_bytes() != expected_version.as_bytes() {
+            return Err(InvalidHeaders::VersionMismatch(expected_version));
+        }
     }
+
+    let subscription_type = utf8(subscription_type, HeaderType::SubscriptionType)?;
+    let subscription_version = utf8(subscription_version, HeaderType::SubscriptionVersion)?;
+    // checked here, so requests with a bad id are rejected before the body is hashed
+    let id = id_header.to_str().map_err(|_| InvalidHeaders::IdNotUtf8)?;
+    let message_type =
+        MessageType::try_from(message_type).map_err(|_| InvalidHeaders::BadMessageType)?;
+    let signature = parse_signature(signature)?;
+    let timestamp = parse_timestamp(timestamp_header)?;
+    check_age(timestamp, now)?;
     Ok(ParsedHeaders {
         payload: PayloadHeaders {
             signature,
             message_type,
         },
-        id_bytes: id_header.as_bytes(),
+        id,
         timestamp_bytes: timestamp_header.as_bytes(),
+        timestamp,
+        subscription_type,
+        subscription_version,
     })
 }
+
+/// Read a header value that may contain any UTF-8, unlike [`HeaderValue::to_str`].
+fn utf8(value: &HeaderValue, header: HeaderType) -> Result<&str, InvalidHeaders> {
+    std::str::from_utf8(value.as_bytes()).map_err(|_| InvalidHeaders::NotUtf8(header))
+}
+
+/// Decode the value of the `Twitch-Eventsub-Message-Signature` header (`sha256=<hex>`).
+fn parse_signature(value: &HeaderValue) -> Result<Vec<u8>, InvalidHeaders> {
+    if value.len() <= 7 || !value.as_bytes().starts_with(b"sha256=") {
+        return Err(InvalidHeaders::SignatureTooShort);
+    }
+    // Twitch sends lowercase hex, but other tools may not - decoding must stay case-insensitive.
+    hex::decode(&value.as_bytes()[7..]).map_err(|_| InvalidHeaders::SignatureNotHex)
+}
+
+fn check_age(timestamp: DateTime<Utc>, now: DateTime<Utc>) -> Result<(), InvalidHeaders> {
+    if now - timestamp > Duration::minutes(10) {
+        return Err(InvalidHeaders::MessageTooOld);
+    }
+    Ok(())
+}
@@ -1,6 +1,8 @@
 use http::HeaderValue;
 use serde::{Deserialize, Serialize};
-use types::{EventSubSubscription, EventSubscription};
+#[cfg(feature = "deserialize")]
+use types::EventSubscription;
+use types::{EventSubSubscription, TransportResponse};
 
 /// The eventsub payload sent by twitch.
 /// It may be a [`Verification`], [`Notification`] or [`Revocation`].
@@ -14,6 +16,143 @@ pub enum EventsubPayload<T> {
     Revocation(Revocation),
 }
 
+impl<T> EventsubPayload<T> {
+    /// The subscription this payload was sent for.
+    pub fn subscription(&self) -> &EventSubSubscription {
+        match self {
+            Self::Verification(verification) => &verification.subscription,
+            Self::Notification(notification) => &notification.subscription,
+            Self::Revocation(revocation) => &revocation.subscription,
+        }
+    }
+
+    /// The transport of the subscription.
+    pub fn transport(&self) -> &TransportResponse {
+        &self.subscription().transport
+    }
+
+    /// The id of the conduit, if the subscription uses the conduit transport.
+    ///
+    /// Events for conduits are still delivered through the webhook or websocket
+    /// of a shard, so they're verified like any other event.
+    pub fn conduit_id(&self) -> Option<&str> {
+        match self.transport() {
+            TransportResponse::Conduit(conduit) => Some(&conduit.conduit_id),
+            _ => None,
+        }
+    }
+
+    /// The event of a notification, or [`None`] for verifications and revocations.
+    ///
+    /// For redemptions, read its fields through [`RedemptionEvent`](redemption::RedemptionEvent).
+    pub fn event(&self) -> Option<&T> {
+        match self {
+            Self::Notification(notification) => Some(&notification.event),
+            _ => None,
+        }
+    }
+
+    /// Transform the event of a notification with `f`.
+    ///
+    /// Verifications and revocations are passed through unchanged, the subscription
+    /// of every variant is kept. Use it to turn the decoded event into your own type:
+    ///
+    /// ```
+    /// # use eventsub_common::{EventsubPayload, Notification, types::channel::ChannelPointsCustomRewardRedemptionAddV1Payload};
+    /// # let body = r#"{"subscription":{"id":"2d7bb1c5-5d1d-4e0e-9c6f-4f1a7c2c3a10","status":"enabled","type":"channel.channel_points_custom_reward_redemption.add","version":"1","condition":{"broadcaster_user_id":"53946574","reward_id":""},"transport":{"method":"webhook","callback":"https://example.com/eventsub"},"created_at":"2024-11-11T18:24:39.125012394Z","cost":0},"event":{"id":"f1f3c6a5-1bd4-4b7c-9bb7-7d1b2c8f4e2d","broadcaster_user_id":"53946574","broadcaster_user_login":"testBroadcaster","broadcaster_user_name":"testBroadcaster","user_id":"81723905","user_login":"testFromUser","user_name":"testFromUser","user_input":"Test Input From CLI","status":"unfulfilled","reward":{"id":"9c3d2e8b-7c2a-4f6e-8d2b-1a5e7f9b3c4d","title":"Test Reward from CLI","cost":150,"prompt":"Redeem Your Test Reward from CLI"},"redeemed_at":"2024-11-11T18:25:02.310938745Z"}}"#;
+    /// struct Redemption {
+    ///     channel: String,
+    /// }
+    ///
+    /// let notification: Notification<ChannelPointsCustomRewardRedemptionAddV1Payload> =
+    ///     serde_json::from_str(body).unwrap();
+    /// let payload = EventsubPayload::Notification(notification).map(|event| Redemption {
+    ///     channel: event.broadcaster_user_id.as_str().to_owned(),
+    /// });
+    ///
+    /// let EventsubPayload::Notification(notification) = payload else {
+    ///     unreachable!();
+    /// };
+    /// assert_eq!(notification.event.channel, "53946574");
+    /// assert_eq!(notification.subscription.id.as_str(), "2d7bb1c5-5d1d-4e0e-9c6f-4f1a7c2c3a10");
+    /// ```
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> EventsubPayload<U> {
+        match self {
+            Self::Verification(verification) => EventsubPayload::Verification(verification),
+            Self::Notification(notification) => EventsubPayload::Notification(notification.map(f)),
+            Self::Revocation(revocation) => EventsubPayload::Revocation(revocation),
+        }
+    }
+
+    /// Deserialize the condition of the subscription.
+    ///
+    /// In `twitch_api`, the subscription type is its condition, so this returns `P`
+    /// with typed fields like `broadcaster_user_id`. Unlike the event of a notification,
+    /// the condition is available for every message type.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the condition isn't a valid condition for `P`.
+    #[cfg(feature = "deserialize")]
+    pub fn condition<P: EventSubscription>(&self) -> Result<P, serde_json::Error> {
+        condition(self.subscription())
+    }
+}
+
+/// Deserialize the condition of `subscription` as `P`.
+#[cfg(feature = "deserialize")]
+fn condition<P: EventSubscription>(
+    subscription: &EventSubSubscription,
+) -> Result<P, serde_json::Error> {
+    P::deserialize(&subscription.condition)
+}
+
+/// The fields of subscription conditions that identify whose events are sent.
+///
+/// Twitch doesn't send the condition as a header, it's only known once the payload is decoded.
+/// Unlike the condition of a subscription type (see [`EventsubPayload::condition`]), this works
+/// for any subscription, as each field is only set if the condition has it.
+/// Other fields of the condition are ignored.
+///
+/// ```
+/// # use eventsub_common::{Condition, types::EventSubSubscription};
+/// fn is_mine(subscription: &EventSubSubscription) -> bool {
+///     Condition::of(subscription)
+///         .is_ok_and(|condition| condition.broadcaster_user_id == Some("53946574"))
+/// }
+/// ```
+#[cfg(feature = "deserialize")]
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Condition<'a> {
+    /// The broadcaster whose channel the events are sent for.
+    pub broadcaster_user_id: Option<&'a str>,
+    /// The broadcaster sending a raid (`channel.raid`).
+    pub from_broadcaster_user_id: Option<&'a str>,
+    /// The broadcaster receiving a raid (`channel.raid`).
+    pub to_broadcaster_user_id: Option<&'a str>,
+    /// The moderator that authorized the subscription.
+    pub moderator_user_id: Option<&'a str>,
+    /// The user the events are sent for (e.g. `user.update`).
+    pub user_id: Option<&'a str>,
+    /// The channel points reward the events are limited to.
+    pub reward_id: Option<&'a str>,
+    /// The client id of the application the events are sent for (e.g. `user.authorization.grant`).
+    pub client_id: Option<&'a str>,
+}
+
+#[cfg(feature = "deserialize")]
+impl<'a> Condition<'a> {
+    /// Read the condition of `subscription`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the condition isn't an object or one of the fields isn't a string.
+    pub fn of(subscription: &'a EventSubSubscription) -> Result<Self, serde_json::Error> {
+        Self::deserialize(&subscription.condition)
+    }
+}
+
 /// A verification payload.
 /// The server must respond to this payload with the `challenge` string as text.
 ///
@@ -27,15 +166,42 @@ pub struct Verification {
 }
 
 /// A notification payload.
+///
+/// The event is usually the [payload](types::EventSubscription::Payload) of a subscription,
+/// but any deserializable type works (see the `borrowed` module for events borrowing from the body).
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct Notification<T> {
     /// The event's data
-    #[serde(bound = "T: EventSubscription")]
+    #[serde(bound(deserialize = "T: Deserialize<'de>", serialize = "T: Serialize"))]
     pub event: T,
     /// The current subscription
     pub subscription: EventSubSubscription,
 }
 
+impl<T> Notification<T> {
+    /// Transform the event with `f`, keeping the subscription.
+    ///
+    /// See [`EventsubPayload::map`].
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Notification<U> {
+        Notification {
+            event: f(self.event),
+            subscription: self.subscription,
+        }
+    }
+
+    /// Deserialize the condition of the subscription.
+    ///
+    /// See [`EventsubPayload::condition`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the condition isn't a valid condition for `P`.
+    #[cfg(feature = "deserialize")]
+    pub fn condition<P: EventSubscription>(&self) -> Result<P, serde_json::Error> {
+        condition(&self.subscription)
+    }
+}
+
 /// A revocation payload.
 ///
 /// Twitch will no longer send events for this subscription.
@@ -56,20 +222,51 @@ pub enum MessageType {
     Revocation,
 }
 
+impl MessageType {
+    /// The value of the `Twitch-Eventsub-Message-Type` header for this message type.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Notification => "notification",
+            Self::Verification => "webhook_callback_verification",
+            Self::Revocation => "revocation",
+        }
+    }
+}
+
 impl TryFrom<&HeaderValue> for MessageType {
     type Error = ();
 
     fn try_from(value: &HeaderValue) -> Result<Self, Self::Error> {
-        match value.to_str() {
-            Ok("notification") => Ok(Self::Notification),
-            Ok("webhook_callback_verification") => Ok(Self::Verification),
-            Ok("revocation") => Ok(Self::Revocation),
-            _ => Err(()),
-        }
+        [Self::Notification, Self::Verification, Self::Revocation]
+            .into_iter()
+            .find(|message_type| value.as_bytes() == message_type.as_str().as_bytes())
+            .ok_or(())
     }
 }
 
+#[cfg(feature = "http-body")]
+pub mod body;
+#[cfg(feature = "borrowed")]
+pub mod borrowed;
+#[cfg(feature = "debug-report")]
+pub mod debug;
+#[cfg(feature = "deserialize")]
+pub mod decode;
+#[cfg(feature = "deserialize")]
+mod depth;
+pub mod error;
+#[cfg(feature = "test-util")]
+pub mod fixture;
 pub mod headers;
+#[cfg(feature = "opentelemetry")]
+pub mod otel;
+pub mod redemption;
+pub mod secret;
+#[cfg(feature = "memory-store")]
+pub mod store;
+#[cfg(feature = "deserialize")]
+pub mod subscription;
+pub mod verify;
 pub mod types {
     pub use twitch_api::eventsub::*;
 }
@@ -0,0 +1,50 @@
+//! OpenTelemetry spans for eventsub deliveries.
+//!
+//! Twitch doesn't send a `traceparent` header, so there's no trace to continue:
+//! every delivery starts a fresh trace, rooted at the span created here.
+//! Make the returned [`Context`] current while processing the event, so spans of
+//! downstream work (database writes, Helix calls, ...) link back to the delivery.
+
+use crate::headers::EventMeta;
+use opentelemetry::{
+    global,
+    trace::{SpanKind, TraceContextExt, Tracer},
+    Context, KeyValue,
+};
+
+/// Name of the tracer used by [`delivery_context`].
+pub const TRACER_NAME: &str = "eventsub";
+
+/// Start the span of a verified delivery with the global tracer.
+///
+/// See [`delivery_context_with`].
+pub fn delivery_context(meta: &EventMeta) -> Context {
+    delivery_context_with(&global::tracer(TRACER_NAME), meta)
+}
+
+/// Start the span of a verified delivery with `tracer` and return a context containing it.
+///
+/// The span is a server span named `eventsub <subscription type>` with the attributes
+/// `eventsub.subscription.type`, `eventsub.subscription.version`, `eventsub.message.id`
+/// and `eventsub.message.type`. It's the root of a new trace, regardless of the current context.
+/// The span ends when the last clone of the context is dropped.
+pub fn delivery_context_with<T>(tracer: &T, meta: &EventMeta) -> Context
+where
+    T: Tracer,
+    T::Span: Send + Sync + 'static,
+{
+    let span = tracer
+        .span_builder(format!("eventsub {}", meta.subscription_type))
+        .with_kind(SpanKind::Server)
+        .with_attributes([
+            KeyValue::new("eventsub.subscription.type", meta.subscription_type.clone()),
+            KeyValue::new(
+                "eventsub.subscription.version",
+                meta.subscription_version.clone(),
+            ),
+            KeyValue::new("eventsub.message.id", meta.message_id.clone()),
+            KeyValue::new("eventsub.message.type", meta.message_type.as_str()),
+        ])
+        .start_with_context(tracer, &Context::new());
+    Context::new().with_span(span)
+}
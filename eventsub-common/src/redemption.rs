@@ -0,0 +1,86 @@
+//! Accessors for the fields of channel points redemptions.
+//!
+//! The payloads of [`channel.channel_points_custom_reward_redemption.add`](crate::types::channel::ChannelPointsCustomRewardRedemptionAddV1)
+//! and [`.update`](crate::types::channel::ChannelPointsCustomRewardRedemptionUpdateV1) have the same fields,
+//! so handlers for both can be written against [`RedemptionEvent`].
+
+use crate::types::channel::{
+    ChannelPointsCustomRewardRedemptionAddV1Payload,
+    ChannelPointsCustomRewardRedemptionUpdateV1Payload,
+};
+
+/// The commonly used fields of a redemption event.
+///
+/// ```
+/// # use eventsub_common::redemption::RedemptionEvent;
+/// fn describe(redemption: &impl RedemptionEvent) -> String {
+///     match redemption.user_input() {
+///         Some(input) => format!(
+///             "{} redeemed {}: {input}",
+///             redemption.redeemer_login(),
+///             redemption.reward_title()
+///         ),
+///         None => format!(
+///             "{} redeemed {}",
+///             redemption.redeemer_login(),
+///             redemption.reward_title()
+///         ),
+///     }
+/// }
+/// ```
+pub trait RedemptionEvent {
+    /// Login of the user who redeemed the reward.
+    fn redeemer_login(&self) -> &str;
+    /// Display name of the user who redeemed the reward.
+    fn redeemer_name(&self) -> &str;
+    /// Title of the redeemed reward.
+    fn reward_title(&self) -> &str;
+    /// Cost of the redeemed reward in channel points.
+    fn reward_cost(&self) -> i64;
+    /// The text the user entered, or [`None`] if the reward doesn't ask for any.
+    fn user_input(&self) -> Option<&str>;
+}
+
+impl RedemptionEvent for ChannelPointsCustomRewardRedemptionAddV1Payload {
+    fn redeemer_login(&self) -> &str {
+        self.user_login.as_str()
+    }
+
+    fn redeemer_name(&self) -> &str {
+        self.user_name.as_str()
+    }
+
+    fn reward_title(&self) -> &str {
+        &self.reward.title
+    }
+
+    fn reward_cost(&self) -> i64 {
+        self.reward.cost
+    }
+
+    fn user_input(&self) -> Option<&str> {
+        Some(self.user_input.as_str()).filter(|input| !input.is_empty())
+    }
+}
+
+impl RedemptionEvent for ChannelPointsCustomRewardRedemptionUpdateV1Payload {
+    fn redeemer_login(&self) -> &str {
+        self.user_login.as_str()
+    }
+
+    fn redeemer_name(&self) -> &str {
+        self.user_name.as_str()
+    }
+
+    fn reward_title(&self) -> &str {
+        &self.reward.title
+    }
+
+    fn reward_cost(&self) -> i64 {
+        self.reward.cost
+    }
+
+    fn user_input(&self) -> Option<&str> {
+        Some(self.user_input.as_str()).filter(|input| !input.is_empty())
+    }
+}
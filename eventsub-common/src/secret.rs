@@ -0,0 +1,196 @@
+//! Helpers for selecting and providing the eventsub secret.
+//!
+//! ## Zeroizing secrets
+//!
+//! With the `zeroize` feature, [`HmacKey`] is implemented for [`Zeroizing`](zeroize::Zeroizing)
+//! and [`SecretBox`](secrecy::SecretBox) (e.g. [`SecretSlice<u8>`](secrecy::SecretSlice) and
+//! [`SecretString`](secrecy::SecretString)), which wipe the secret when dropped. Their bytes
+//! are only exposed to initialize the HMAC. Owned key material (e.g. a derived key) is wiped
+//! right after that, and so are the secrets in a [`PathScopedSecret`].
+//! Both crates are re-exported to get matching versions.
+//!
+//! No error or [`Debug`](fmt::Debug) output of this crate includes the secret.
+
+use std::{borrow::Cow, collections::HashMap, fmt};
+
+#[cfg(feature = "zeroize")]
+pub use {secrecy, zeroize};
+
+/// A key for verifying eventsub messages.
+///
+/// Usually, this is the raw secret used when creating the subscription (`[u8]`).
+/// Implement this for your own type if you don't store the raw secret,
+/// but derive it when needed (e.g. with HKDF from a master key):
+///
+/// ```
+/// # use eventsub_common::secret::HmacKey;
+/// # use std::borrow::Cow;
+/// # fn hkdf_expand(master: &[u8], info: &[u8]) -> Vec<u8> { [master, info].concat() }
+/// struct DerivedKey<'a> {
+///     master_key: &'a [u8],
+///     broadcaster_id: &'a str,
+/// }
+///
+/// impl HmacKey for DerivedKey<'_> {
+///     fn key_material(&self) -> Cow<'_, [u8]> {
+///         Cow::Owned(hkdf_expand(self.master_key, self.broadcaster_id.as_bytes()))
+///     }
+/// }
+/// ```
+pub trait HmacKey {
+    /// The raw bytes used as the HMAC key.
+    ///
+    /// This is called once per message.
+    fn key_material(&self) -> Cow<'_, [u8]>;
+}
+
+impl HmacKey for [u8] {
+    fn key_material(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl<const N: usize> HmacKey for [u8; N] {
+    fn key_material(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl HmacKey for str {
+    fn key_material(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.as_bytes())
+    }
+}
+
+impl HmacKey for Vec<u8> {
+    fn key_material(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl HmacKey for Cow<'_, [u8]> {
+    fn key_material(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl<K: HmacKey + ?Sized> HmacKey for &K {
+    fn key_material(&self) -> Cow<'_, [u8]> {
+        (**self).key_material()
+    }
+}
+
+impl<K: HmacKey + ?Sized> HmacKey for Box<K> {
+    fn key_material(&self) -> Cow<'_, [u8]> {
+        (**self).key_material()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<K: HmacKey + zeroize::Zeroize> HmacKey for zeroize::Zeroizing<K> {
+    fn key_material(&self) -> Cow<'_, [u8]> {
+        (**self).key_material()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<K: HmacKey + zeroize::Zeroize + ?Sized> HmacKey for secrecy::SecretBox<K> {
+    fn key_material(&self) -> Cow<'_, [u8]> {
+        secrecy::ExposeSecret::expose_secret(self).key_material()
+    }
+}
+
+/// Secrets for multiple endpoints, keyed by the last segment of the request path.
+///
+/// This covers the common case of hosting webhooks for multiple broadcasters on
+/// distinct paths (`/eventsub/alice`, `/eventsub/bob`), each with its own secret.
+///
+/// ```
+/// # use eventsub_common::secret::PathScopedSecret;
+/// let secrets = PathScopedSecret::new()
+///     .with("alice", "alice's secret")
+///     .with("bob", "bob's secret");
+///
+/// assert_eq!(
+///     secrets.for_path("/eventsub/alice"),
+///     Some(&b"alice's secret"[..])
+/// );
+/// assert_eq!(
+///     secrets.for_path("/eventsub/bob/"),
+///     Some(&b"bob's secret"[..])
+/// );
+/// assert_eq!(secrets.for_path("/eventsub/eve"), None);
+/// ```
+#[derive(Clone, Default)]
+pub struct PathScopedSecret {
+    secrets: HashMap<String, Vec<u8>>,
+}
+
+impl PathScopedSecret {
+    /// Create an empty set of secrets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the secret for a path segment.
+    #[must_use]
+    pub fn with(mut self, segment: impl Into<String>, secret: impl Into<Vec<u8>>) -> Self {
+        self.insert(segment, secret);
+        self
+    }
+
+    /// Add the secret for a path segment, replacing the previous one.
+    pub fn insert(&mut self, segment: impl Into<String>, secret: impl Into<Vec<u8>>) {
+        let replaced = self.secrets.insert(segment.into(), secret.into());
+        #[cfg(feature = "zeroize")]
+        if let Some(mut replaced) = replaced {
+            zeroize::Zeroize::zeroize(&mut replaced);
+        }
+        #[cfg(not(feature = "zeroize"))]
+        drop(replaced);
+    }
+
+    /// Get the secret for a path segment (e.g. a matched path parameter).
+    pub fn get(&self, segment: &str) -> Option<&[u8]> {
+        self.secrets.get(segment).map(Vec::as_slice)
+    }
+
+    /// Get the secret for the last segment of a request path.
+    ///
+    /// A trailing slash is ignored.
+    pub fn for_path(&self, path: &str) -> Option<&[u8]> {
+        path.trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .and_then(|segment| self.get(segment))
+    }
+}
+
+impl<K: Into<String>, V: Into<Vec<u8>>> FromIterator<(K, V)> for PathScopedSecret {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        Self {
+            secrets: iter
+                .into_iter()
+                .map(|(segment, secret)| (segment.into(), secret.into()))
+                .collect(),
+        }
+    }
+}
+
+// The secrets themselves shouldn't end up in logs.
+impl fmt::Debug for PathScopedSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PathScopedSecret")
+            .field("segments", &self.secrets.keys())
+            .finish()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for PathScopedSecret {
+    fn drop(&mut self) {
+        for secret in self.secrets.values_mut() {
+            zeroize::Zeroize::zeroize(secret);
+        }
+    }
+}
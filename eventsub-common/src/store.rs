@@ -0,0 +1,158 @@
+//! In-memory store of seen message ids to detect duplicate deliveries.
+//!
+//! Twitch resends messages that weren't acknowledged in time, so the same message id
+//! may arrive more than once. Messages older than ten minutes are rejected anyway,
+//! so ids only have to be remembered for that long ([`DEFAULT_TTL`]).
+//!
+//! The store lives in a single process. If you run multiple instances, use a shared
+//! store instead (see the redis example of `actix-web-eventsub`).
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+use tokio::{task::JoinHandle, time::Instant};
+
+/// How long ids are remembered by default - the maximum age of a message.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Message ids seen in the last [ttl](Self::with_ttl).
+///
+/// Expired ids are only removed when the same id is checked again or when
+/// [`purge_expired`](Self::purge_expired) runs. On a quiet server, nothing triggers
+/// the lazy expiry, so run [`spawn_sweeper`](Self::spawn_sweeper) to keep the memory bounded:
+/// a sweep locks the store and walks all entries, while lazy expiry only touches a single
+/// id but lets expired ids pile up. A sweep every few minutes is cheap at Twitch's volumes.
+///
+/// Cloning the store is cheap, clones share the same ids.
+///
+/// ```
+/// # async fn run() {
+/// use eventsub_common::store::EventIdStore;
+/// use std::time::Duration;
+///
+/// let store = EventIdStore::new();
+/// let _sweeper = store.spawn_sweeper(Duration::from_secs(60));
+///
+/// assert!(store.check_and_insert("e76c6bd4-55c9-4987-8304-da1588d8988b"));
+/// // a duplicate
+/// assert!(!store.check_and_insert("e76c6bd4-55c9-4987-8304-da1588d8988b"));
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct EventIdStore {
+    ids: Arc<Mutex<HashMap<Box<str>, Instant>>>,
+    ttl: Duration,
+}
+
+impl Default for EventIdStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventIdStore {
+    /// Create an empty store remembering ids for [`DEFAULT_TTL`].
+    pub fn new() -> Self {
+        Self::with_ttl(DEFAULT_TTL)
+    }
+
+    /// Create an empty store remembering ids for `ttl`.
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            ids: Arc::default(),
+            ttl,
+        }
+    }
+
+    /// Remember `id` and check if it's new.
+    ///
+    /// Returns `true` if the id wasn't seen in the last ttl - the message should be handled.
+    pub fn check_and_insert(&self, id: &str) -> bool {
+        let now = Instant::now();
+        let mut ids = self.lock();
+        match ids.get_mut(id) {
+            Some(expires) if *expires > now => false,
+            Some(expires) => {
+                *expires = now + self.ttl;
+                true
+            }
+            None => {
+                ids.insert(id.into(), now + self.ttl);
+                true
+            }
+        }
+    }
+
+    /// Check if `id` was seen in the last ttl without remembering it.
+    pub fn contains(&self, id: &str) -> bool {
+        self.lock()
+            .get(id)
+            .is_some_and(|expires| *expires > Instant::now())
+    }
+
+    /// Number of remembered ids, including expired ones that weren't purged yet.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Check if no ids are remembered.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Remove all expired ids. Returns the number of removed ids.
+    pub fn purge_expired(&self) -> usize {
+        let now = Instant::now();
+        let mut ids = self.lock();
+        let before = ids.len();
+        ids.retain(|_, expires| *expires > now);
+        before - ids.len()
+    }
+
+    /// Spawn a task on the current tokio runtime purging expired ids every `interval`.
+    ///
+    /// The task runs until the returned [`Sweeper`] is dropped or [stopped](Sweeper::stop).
+    ///
+    /// ## Panics
+    ///
+    /// Panics if called outside of a tokio runtime or if `interval` is zero.
+    pub fn spawn_sweeper(&self, interval: Duration) -> Sweeper {
+        let store = self.clone();
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        Sweeper {
+            task: tokio::spawn(async move {
+                loop {
+                    interval.tick().await;
+                    store.purge_expired();
+                }
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Box<str>, Instant>> {
+        // the map is always consistent, a panic while holding the lock can't break it
+        self.ids.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Handle of the background task started by [`EventIdStore::spawn_sweeper`].
+///
+/// Dropping the handle stops the task.
+#[derive(Debug)]
+pub struct Sweeper {
+    task: JoinHandle<()>,
+}
+
+impl Sweeper {
+    /// Stop purging expired ids.
+    pub fn stop(self) {}
+}
+
+impl Drop for Sweeper {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -0,0 +1,75 @@
+//! Helpers for creating subscriptions.
+
+use crate::types::{EventSubSubscription, EventType};
+use serde::Serialize;
+use std::fmt;
+
+/// The body of a [Create EventSub Subscription](https://dev.twitch.tv/docs/api/reference/#create-eventsub-subscription) request.
+///
+/// Send it as JSON to `POST https://api.twitch.tv/helix/eventsub/subscriptions`.
+/// See [`build_create_subscription`].
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct CreateSubscription<'a> {
+    /// The type of the subscription.
+    #[serde(rename = "type")]
+    pub type_: &'a EventType,
+    /// The version of the subscription.
+    pub version: &'a str,
+    /// The condition of the subscription.
+    pub condition: &'a serde_json::Value,
+    /// Where the events will be sent to.
+    pub transport: WebhookTransport<'a>,
+}
+
+/// The webhook transport of a subscription.
+#[derive(Serialize, Clone, PartialEq, Eq)]
+#[serde(tag = "method", rename = "webhook")]
+pub struct WebhookTransport<'a> {
+    /// The URL of the endpoint (must use HTTPS on port 443).
+    pub callback: &'a str,
+    /// The secret used to sign the events (10 to 100 ASCII characters).
+    pub secret: &'a str,
+}
+
+// The secret shouldn't end up in logs.
+impl fmt::Debug for WebhookTransport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookTransport")
+            .field("callback", &self.callback)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Build the request to create a subscription like `subscription`, but sent to
+/// `callback` and signed with `secret`.
+///
+/// Type, version and condition are taken from `subscription`, so this can be used to
+/// subscribe again after receiving a [`Revocation`](crate::Revocation).
+/// Only revocations with the status `notification_failures_exceeded` can be fixed like this -
+/// for the other reasons, Twitch will reject the new subscription.
+///
+/// ```
+/// # use eventsub_common::{subscription::build_create_subscription, Revocation};
+/// # fn send(_: &str) {}
+/// fn on_revocation(revocation: &Revocation) -> serde_json::Result<()> {
+///     let request = build_create_subscription(
+///         &revocation.subscription,
+///         "https://example.com/eventsub",
+///         "a fresh secret",
+///     );
+///     send(&serde_json::to_string(&request)?);
+///     Ok(())
+/// }
+/// ```
+pub fn build_create_subscription<'a>(
+    subscription: &'a EventSubSubscription,
+    callback: &'a str,
+    secret: &'a str,
+) -> CreateSubscription<'a> {
+    CreateSubscription {
+        type_: &subscription.type_,
+        version: &subscription.version,
+        condition: &subscription.condition,
+        transport: WebhookTransport { callback, secret },
+    }
+}
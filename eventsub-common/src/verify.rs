@@ -0,0 +1,372 @@
+//! Sans-IO verification and decoding of eventsub requests.
+//!
+//! These functions only need the headers and the body of a request, so they can be used
+//! without a web framework (for example on WASM/edge runtimes).
+//!
+//! The signature covers the body exactly as Twitch sent it. Verify the raw bytes before
+//! transforming them in any way - re-serialized JSON (even if it's equivalent) fails
+//! verification. Gateways that normalize payloads should use [`verify_and_decode_raw`]
+//! and only canonicalize the payload after it was verified.
+
+#[cfg(feature = "deserialize")]
+use crate::{decode, types::EventSubscription, EventsubPayload};
+use crate::{
+    headers::{self, EventMeta, HeaderMapExt, InvalidHeaders, ParsedHeaders},
+    secret::HmacKey,
+    MessageType,
+};
+use chrono::{DateTime, Utc};
+use hmac::{digest::InvalidLength, Hmac, Mac};
+use sha2::Sha256;
+
+/// The MAC used to sign eventsub requests.
+pub type HmacSha256 = Hmac<Sha256>;
+
+/// Errors when verifying and decoding an eventsub request.
+#[derive(Debug, thiserror::Error)]
+pub enum VerifyError {
+    /// An issue with the headers. See [`InvalidHeaders`] for more detail.
+    #[error("Invalid headers: {0}")]
+    Headers(#[from] InvalidHeaders),
+    /// The secret couldn't be used as an HMAC key.
+    #[error("Bad secret key")]
+    HmacInit(#[from] InvalidLength),
+    /// The provided signature was incorrect - it didn't match the computed one.
+    #[error("The provided signature wasn't expected")]
+    SignatureMismatch,
+    /// serde_json couldn't deserialize the payload.
+    #[cfg(feature = "deserialize")]
+    #[error("JSON Deserialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+}
+
+/// Create a MAC for a request and feed it the message id and timestamp.
+///
+/// Update it with the body afterwards and compare it with the signature.
+///
+/// ## Errors
+///
+/// Returns an error if the secret can't be used as an HMAC key.
+pub fn init_mac<K: HmacKey + ?Sized>(
+    secret: &K,
+    id_bytes: &[u8],
+    timestamp_bytes: &[u8],
+) -> Result<HmacSha256, InvalidLength> {
+    let key = secret.key_material();
+    let mac = HmacSha256::new_from_slice(&key);
+    #[cfg(feature = "zeroize")]
+    if let std::borrow::Cow::Owned(mut key) = key {
+        zeroize::Zeroize::zeroize(&mut key);
+    }
+    let mut mac = mac?;
+    mac.update(id_bytes);
+    mac.update(timestamp_bytes);
+    Ok(mac)
+}
+
+/// Compute the `Twitch-Eventsub-Message-Signature` header value for a delivery.
+///
+/// The signature is the HMAC-SHA256 of the message id, the timestamp and the body
+/// (in this order), formatted as `sha256=<lowercase hex>` - exactly like Twitch sends it.
+/// This is useful for simulating Twitch in tests or tools.
+///
+/// ```
+/// # use eventsub_common::verify::sign;
+/// let signature = sign(
+///     b"secretsecret",
+///     "e76c6bd4-55c9-4987-8304-da1588d8988b",
+///     "2019-11-16T10:11:12.634234626Z",
+///     br#"{"challenge":"pogchamp-kappa-360noscope-vohiyo"}"#,
+/// );
+/// assert!(signature.starts_with("sha256="));
+/// ```
+pub fn sign(secret: &[u8], id: &str, timestamp: &str, body: &[u8]) -> String {
+    let mut mac =
+        init_mac(secret, id.as_bytes(), timestamp.as_bytes()).expect("HMAC accepts any key size");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Check the signature of a request whose headers were already read.
+///
+/// ## Errors
+///
+/// Returns an error if the secret is invalid or the signature doesn't match.
+pub fn verify_signature<K: HmacKey + ?Sized>(
+    secret: &K,
+    headers: &ParsedHeaders<'_>,
+    body: &[u8],
+) -> Result<(), VerifyError> {
+    let mut verifier = StreamingVerifier::new(secret, headers)?;
+    verifier.update(body);
+    verifier.finish().map(drop)
+}
+
+/// Verifies a body chunk by chunk, without buffering it.
+///
+/// Feed every chunk to [`update`](Self::update) as it arrives and call
+/// [`finish`](Self::finish) after the last one. Until then, nothing about the
+/// body is trusted, so it can't be deserialized while streaming - forward it,
+/// and only commit to it once the signature is verified.
+pub struct StreamingVerifier {
+    mac: HmacSha256,
+    signature: Vec<u8>,
+    message_type: MessageType,
+}
+
+impl StreamingVerifier {
+    /// Start verifying a request whose headers were already read.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the secret can't be used as an HMAC key.
+    pub fn new<K: HmacKey + ?Sized>(
+        secret: &K,
+        headers: &ParsedHeaders<'_>,
+    ) -> Result<Self, InvalidLength> {
+        Ok(Self {
+            mac: init_mac(secret, headers.id.as_bytes(), headers.timestamp_bytes)?,
+            signature: headers.payload.signature.clone(),
+            message_type: headers.payload.message_type,
+        })
+    }
+
+    /// The type of the message (from the headers).
+    pub fn message_type(&self) -> MessageType {
+        self.message_type
+    }
+
+    /// Add the next chunk of the body.
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.mac.update(chunk);
+    }
+
+    /// Check the signature over all chunks.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`VerifyError::SignatureMismatch`] if the signature doesn't match.
+    pub fn finish(self) -> Result<MessageType, VerifyError> {
+        self.mac
+            .verify_slice(&self.signature)
+            .map(|()| self.message_type)
+            .map_err(|_| VerifyError::SignatureMismatch)
+    }
+}
+
+/// A request body with a verified signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verified<B> {
+    /// The type of the message (from the headers).
+    pub message_type: MessageType,
+    /// The unmodified body.
+    pub body: B,
+}
+
+/// Verify a request without decoding its body.
+///
+/// The subscription type and version aren't checked, so this can be used to verify
+/// requests for any subscription (for example before forwarding them).
+///
+/// ## Errors
+///
+/// Returns an error if the headers are invalid or the signature doesn't match.
+#[cfg(feature = "clock")]
+pub fn verify_only<M: HeaderMapExt, B: AsRef<[u8]>>(
+    headers: &M,
+    body: B,
+    secret: &[u8],
+) -> Result<Verified<B>, VerifyError> {
+    verify_only_at(headers, body, secret, Utc::now())
+}
+
+/// Like [`verify_only`], but checks the message age against `now`
+/// instead of reading the system clock.
+///
+/// ## Errors
+///
+/// See [`verify_only`].
+pub fn verify_only_at<M: HeaderMapExt, B: AsRef<[u8]>>(
+    headers: &M,
+    body: B,
+    secret: &[u8],
+    now: DateTime<Utc>,
+) -> Result<Verified<B>, VerifyError> {
+    let parsed = headers::read_eventsub_headers_untyped_at(headers, now)?;
+    verify_signature(secret, &parsed, body.as_ref())?;
+    Ok(Verified {
+        message_type: parsed.payload.message_type,
+        body,
+    })
+}
+
+/// A request with a verified signature whose subscription type isn't known yet.
+///
+/// Verify a request once with [`verify_raw`] and dispatch on the subscription type
+/// and version. Then deserialize the body with [`into_typed`](Self::into_typed)
+/// without computing the HMAC again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedRaw<B> {
+    /// Metadata of the message, including the subscription type and version.
+    pub meta: EventMeta,
+    /// The unmodified body.
+    pub body: B,
+}
+
+impl<B: AsRef<[u8]>> VerifiedRaw<B> {
+    /// Check if the request was sent for the subscription `P`.
+    #[cfg(feature = "deserialize")]
+    pub fn is<P: EventSubscription>(&self) -> bool {
+        self.meta.subscription_type == P::EVENT_TYPE.to_str()
+            && self.meta.subscription_version == P::VERSION
+    }
+
+    /// Deserialize the body as a payload of the subscription `P`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`InvalidHeaders::WrongSubscriptionType`] or [`InvalidHeaders::VersionMismatch`]
+    /// if the request wasn't sent for `P`, and [`VerifyError::Serde`] if the body can't be deserialized.
+    #[cfg(feature = "deserialize")]
+    pub fn into_typed<P: EventSubscription>(
+        self,
+    ) -> Result<EventsubPayload<P::Payload>, VerifyError> {
+        if self.meta.subscription_type != P::EVENT_TYPE.to_str() {
+            return Err(InvalidHeaders::WrongSubscriptionType(P::EVENT_TYPE.to_str()).into());
+        }
+        if self.meta.subscription_version != P::VERSION {
+            return Err(InvalidHeaders::VersionMismatch(P::VERSION).into());
+        }
+        Ok(decode::decode_payload::<P>(
+            self.meta.message_type,
+            self.body.as_ref(),
+            None,
+        )?)
+    }
+}
+
+/// Verify a request for any subscription, keeping what's needed to decode it later.
+///
+/// ## Errors
+///
+/// Returns an error if the headers are invalid or the signature doesn't match.
+#[cfg(feature = "clock")]
+pub fn verify_raw<M: HeaderMapExt, B: AsRef<[u8]>>(
+    headers: &M,
+    body: B,
+    secret: &[u8],
+) -> Result<VerifiedRaw<B>, VerifyError> {
+    verify_raw_at(headers, body, secret, Utc::now())
+}
+
+/// Like [`verify_raw`], but checks the message age against `now`
+/// instead of reading the system clock.
+///
+/// ## Errors
+///
+/// See [`verify_raw`].
+pub fn verify_raw_at<M: HeaderMapExt, B: AsRef<[u8]>>(
+    headers: &M,
+    body: B,
+    secret: &[u8],
+    now: DateTime<Utc>,
+) -> Result<VerifiedRaw<B>, VerifyError> {
+    let parsed = headers::read_eventsub_headers_untyped_at(headers, now)?;
+    verify_signature(secret, &parsed, body.as_ref())?;
+    Ok(VerifiedRaw {
+        meta: parsed.meta(),
+        body,
+    })
+}
+
+/// Verify a request and decode its body.
+///
+/// This reads and checks the headers, verifies the signature and deserializes the
+/// body - the same steps the framework extractors take.
+///
+/// ## Errors
+///
+/// Returns an error if any of the steps fails.
+#[cfg(all(feature = "clock", feature = "deserialize"))]
+pub fn verify_and_decode<P: EventSubscription, M: HeaderMapExt>(
+    headers: &M,
+    body: &[u8],
+    secret: &[u8],
+) -> Result<EventsubPayload<P::Payload>, VerifyError> {
+    verify_and_decode_at::<P, M>(headers, body, secret, Utc::now())
+}
+
+/// Like [`verify_and_decode`], but checks the message age against `now`
+/// instead of reading the system clock.
+///
+/// ## Errors
+///
+/// See [`verify_and_decode`].
+#[cfg(feature = "deserialize")]
+pub fn verify_and_decode_at<P: EventSubscription, M: HeaderMapExt>(
+    headers: &M,
+    body: &[u8],
+    secret: &[u8],
+    now: DateTime<Utc>,
+) -> Result<EventsubPayload<P::Payload>, VerifyError> {
+    let parsed = headers::read_eventsub_headers_at::<M, P>(headers, now)?;
+    verify_signature(secret, &parsed, body)?;
+    Ok(decode::decode_payload::<P>(
+        parsed.payload.message_type,
+        body,
+        None,
+    )?)
+}
+
+/// A verified and decoded request, along with the raw body the signature was checked against.
+#[cfg(feature = "deserialize")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedPayload<P: EventSubscription, B> {
+    /// Metadata of the message.
+    pub meta: EventMeta,
+    /// The decoded payload.
+    pub payload: EventsubPayload<P::Payload>,
+    /// The body exactly as it was received and verified.
+    pub body: B,
+}
+
+/// Verify a request and decode its body, keeping the raw body.
+///
+/// The HMAC is computed over `body` before it's decoded, so hand this the bytes as they
+/// were received. The same bytes are returned unchanged, so a gateway can forward them
+/// or derive a canonical form from the [payload](VerifiedPayload::payload) afterwards.
+///
+/// ## Errors
+///
+/// See [`verify_and_decode`].
+#[cfg(all(feature = "clock", feature = "deserialize"))]
+pub fn verify_and_decode_raw<P: EventSubscription, M: HeaderMapExt, B: AsRef<[u8]>>(
+    headers: &M,
+    body: B,
+    secret: &[u8],
+) -> Result<VerifiedPayload<P, B>, VerifyError> {
+    verify_and_decode_raw_at(headers, body, secret, Utc::now())
+}
+
+/// Like [`verify_and_decode_raw`], but checks the message age against `now`
+/// instead of reading the system clock.
+///
+/// ## Errors
+///
+/// See [`verify_and_decode`].
+#[cfg(feature = "deserialize")]
+pub fn verify_and_decode_raw_at<P: EventSubscription, M: HeaderMapExt, B: AsRef<[u8]>>(
+    headers: &M,
+    body: B,
+    secret: &[u8],
+    now: DateTime<Utc>,
+) -> Result<VerifiedPayload<P, B>, VerifyError> {
+    let parsed = headers::read_eventsub_headers_at::<M, P>(headers, now)?;
+    verify_signature(secret, &parsed, body.as_ref())?;
+    let payload = decode::decode_payload::<P>(parsed.payload.message_type, body.as_ref(), None)?;
+    Ok(VerifiedPayload {
+        meta: parsed.meta(),
+        payload,
+        body,
+    })
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "http-body")]
+
+use chrono::Utc;
+use eventsub_common::{
+    body::{read_body, BodyError, MAX_BODY_SIZE},
+    headers::{
+        MESSAGE_ID, MESSAGE_SIGNATURE, MESSAGE_TIMESTAMP, MESSAGE_TYPE, SUBSCRIPTION_TYPE,
+        SUBSCRIPTION_VERSION,
+    },
+    types::channel::ChannelPointsCustomRewardRedemptionAddV1,
+    verify::{sign, verify_and_decode_at},
+    EventsubPayload,
+};
+use http::{HeaderMap, HeaderValue};
+use http_body_util::Full;
+
+const SECRET: &[u8] = b"secretsecret";
+const BODY: &str = r#"{
+    "challenge": "pogchamp-kappa-360noscope-vohiyo",
+    "subscription": {
+        "id": "f1c2a387-161a-49f9-a165-0f21d7a4e1c4",
+        "status": "webhook_callback_verification_pending",
+        "type": "channel.channel_points_custom_reward_redemption.add",
+        "version": "1",
+        "condition": { "broadcaster_user_id": "1337" },
+        "transport": { "method": "webhook", "callback": "https://example.com/eventsub" },
+        "created_at": "2019-11-16T10:11:12.634234626Z",
+        "cost": 0
+    }
+}"#;
+
+fn headers() -> HeaderMap {
+    let id = "e76c6bd4-55c9-4987-8304-da1588d8988b";
+    let timestamp = Utc::now().to_rfc3339();
+    let signature = sign(SECRET, id, &timestamp, BODY.as_bytes());
+
+    let mut map = HeaderMap::new();
+    for (name, value) in [
+        (MESSAGE_ID, id),
+        (MESSAGE_TIMESTAMP, &timestamp),
+        (MESSAGE_SIGNATURE, &signature),
+        (MESSAGE_TYPE, "webhook_callback_verification"),
+        (
+            SUBSCRIPTION_TYPE,
+            "channel.channel_points_custom_reward_redemption.add",
+        ),
+        (SUBSCRIPTION_VERSION, "1"),
+    ] {
+        map.insert(name, HeaderValue::from_str(value).unwrap());
+    }
+    map
+}
+
+#[tokio::test]
+async fn full_body() {
+    let body = read_body(Full::new(BODY.as_bytes()), MAX_BODY_SIZE)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], BODY.as_bytes());
+
+    let payload = verify_and_decode_at::<ChannelPointsCustomRewardRedemptionAddV1, _>(
+        &headers(),
+        &body,
+        SECRET,
+        Utc::now(),
+    )
+    .unwrap();
+    let EventsubPayload::Verification(verification) = payload else {
+        panic!("expected a verification, got {payload:?}");
+    };
+    assert_eq!(verification.challenge, "pogchamp-kappa-360noscope-vohiyo");
+}
+
+#[tokio::test]
+async fn too_large() {
+    let limit = BODY.len() - 1;
+    let error = read_body(Full::new(BODY.as_bytes()), limit)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, BodyError::TooLarge(l) if l == limit));
+
+    assert!(read_body(Full::new(BODY.as_bytes()), BODY.len())
+        .await
+        .is_ok());
+}
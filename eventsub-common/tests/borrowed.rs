@@ -0,0 +1,88 @@
+#![cfg(feature = "borrowed")]
+
+use eventsub_common::{
+    borrowed::{self, ChannelChatMessage},
+    decode::decode_borrowed,
+    EventsubPayload, MessageType,
+};
+use std::borrow::Cow;
+
+const BODY: &str = r##"{
+    "subscription": {
+        "id": "0b7f3361-672b-4d39-b307-dd5b576c9b27",
+        "type": "channel.chat.message",
+        "version": "1",
+        "status": "enabled",
+        "cost": 0,
+        "condition": { "broadcaster_user_id": "1971641", "user_id": "2914196" },
+        "transport": { "method": "webhook", "callback": "https://example.com/webhooks/callback" },
+        "created_at": "2023-11-06T18:11:47.492253549Z"
+    },
+    "event": {
+        "broadcaster_user_id": "1971641",
+        "broadcaster_user_login": "streamer",
+        "broadcaster_user_name": "streamer",
+        "chatter_user_id": "4145994",
+        "chatter_user_login": "viewer32",
+        "chatter_user_name": "viewer32",
+        "message_id": "cc106a89-1814-919d-454c-f4f2f970aae7",
+        "message": {
+            "text": "Hi chat \"quoted\"",
+            "fragments": [
+                { "type": "text", "text": "Hi chat \"quoted\"", "cheermote": null, "emote": null, "mention": null }
+            ]
+        },
+        "color": "#00FF7F",
+        "badges": [],
+        "message_type": "text",
+        "cheer": null,
+        "reply": null,
+        "channel_points_custom_reward_id": null
+    }
+}"##;
+
+#[test]
+fn borrows_from_body() {
+    let notification = borrowed::notification::<ChannelChatMessage<'_>>(BODY.as_bytes()).unwrap();
+    let event = notification.event;
+
+    assert!(matches!(
+        event.chatter_user_login,
+        Cow::Borrowed("viewer32")
+    ));
+    assert!(matches!(event.color, Cow::Borrowed("#00FF7F")));
+    // escaped strings have to be allocated
+    assert!(matches!(event.message.text, Cow::Owned(_)));
+    assert_eq!(event.message.text, "Hi chat \"quoted\"");
+    assert_eq!(event.message.fragments.len(), 1);
+    assert_eq!(event.message.fragments[0].type_, "text");
+}
+
+#[test]
+fn borrowed_payload() {
+    let payload = decode_borrowed::<ChannelChatMessage<'_>>(
+        MessageType::Notification,
+        BODY.as_bytes(),
+        Some(8),
+    )
+    .unwrap();
+    let EventsubPayload::Notification(notification) = payload else {
+        panic!("expected a notification");
+    };
+    assert!(matches!(
+        notification.event.chatter_user_login,
+        Cow::Borrowed("viewer32")
+    ));
+    assert_eq!(
+        notification.subscription.id.as_str(),
+        "0b7f3361-672b-4d39-b307-dd5b576c9b27"
+    );
+
+    // the depth limit applies like for owned payloads
+    assert!(decode_borrowed::<ChannelChatMessage<'_>>(
+        MessageType::Notification,
+        BODY.as_bytes(),
+        Some(3),
+    )
+    .is_err());
+}
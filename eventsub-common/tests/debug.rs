@@ -0,0 +1,67 @@
+#![cfg(feature = "debug-report")]
+
+use chrono::{Duration, Utc};
+use eventsub_common::{
+    debug::report_at,
+    headers::{
+        MESSAGE_ID, MESSAGE_SIGNATURE, MESSAGE_TIMESTAMP, MESSAGE_TYPE, SUBSCRIPTION_TYPE,
+        SUBSCRIPTION_VERSION,
+    },
+    verify::sign,
+};
+use http::{HeaderMap, HeaderValue};
+
+const SECRET: &[u8] = b"secretsecret";
+const ID: &str = "e76c6bd4-55c9-4987-8304-da1588d8988b";
+const BODY: &[u8] = br#"{"challenge":"pogchamp-kappa-360noscope-vohiyo"}"#;
+
+#[test]
+fn reports_request() {
+    let now = Utc::now();
+    let timestamp = (now - Duration::seconds(30)).to_rfc3339();
+    let signature = sign(SECRET, ID, &timestamp, BODY);
+
+    let mut headers = HeaderMap::new();
+    for (name, value) in [
+        (MESSAGE_ID, ID),
+        (MESSAGE_TIMESTAMP, &timestamp),
+        (MESSAGE_SIGNATURE, &signature),
+        (MESSAGE_TYPE, "webhook_callback_verification"),
+        (SUBSCRIPTION_TYPE, "channel.follow"),
+    ] {
+        headers.insert(name, HeaderValue::from_str(value).unwrap());
+    }
+
+    let report = report_at(&headers, Some(SECRET), BODY, now);
+    assert!(report.signature_valid);
+    assert_eq!(report.provided_signature.as_deref(), Some(&signature[..15]));
+    assert_eq!(report.computed_signature, report.provided_signature);
+    assert_eq!(report.message_type, Some("webhook_callback_verification"));
+    assert_eq!(report.timestamp_age_secs, Some(30));
+    assert_eq!(report.body_len, BODY.len());
+    assert_eq!(
+        report.header_error.as_deref(),
+        Some("Missing header SubscriptionVersion")
+    );
+    let version = report
+        .headers
+        .iter()
+        .find(|header| header.name == SUBSCRIPTION_VERSION)
+        .unwrap();
+    assert_eq!(version.value, None);
+    // the full signature isn't revealed
+    let provided = report
+        .headers
+        .iter()
+        .find(|header| header.name == MESSAGE_SIGNATURE)
+        .unwrap();
+    assert_eq!(provided.value.as_deref(), Some(&signature[..15]));
+
+    let report = report_at(&headers, Some(b"wrong secret"), BODY, now);
+    assert!(!report.signature_valid);
+    assert_ne!(report.computed_signature, report.provided_signature);
+
+    let report = report_at::<_, [u8]>(&headers, None, BODY, now);
+    assert!(!report.signature_valid);
+    assert_eq!(report.computed_signature, None);
+}
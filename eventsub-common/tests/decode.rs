@@ -0,0 +1,404 @@
+#![cfg(feature = "deserialize")]
+
+use eventsub_common::{
+    decode::{
+        decode_batch, decode_payload, decode_payload_in_place, leading_subscription,
+        subscription_id,
+    },
+    redemption::RedemptionEvent,
+    types::{channel::ChannelPointsCustomRewardRedemptionAddV1, Status},
+    EventsubPayload, MessageType,
+};
+
+fn subscription(condition: &str) -> String {
+    format!(
+        r#"{{
+            "id": "f1c2a387-161a-49f9-a165-0f21d7a4e1c4",
+            "status": "enabled",
+            "type": "channel.channel_points_custom_reward_redemption.add",
+            "version": "1",
+            "condition": {condition},
+            "transport": {{ "method": "webhook", "callback": "https://example.com/eventsub" }},
+            "created_at": "2019-11-16T10:11:12.634234626Z",
+            "cost": 0
+        }}"#
+    )
+}
+
+fn event(user_input: &str) -> String {
+    format!(
+        r#"{{
+            "id": "f1f3c6a5-1bd4-4b7c-9bb7-7d1b2c8f4e2d",
+            "broadcaster_user_id": "1337",
+            "broadcaster_user_login": "cool_user",
+            "broadcaster_user_name": "Cool_User",
+            "user_id": "9001",
+            "user_login": "cooler_user",
+            "user_name": "Cooler_User",
+            "user_input": "{user_input}",
+            "status": "unfulfilled",
+            "reward": {{ "id": "92af127c-7326-4483-a52b-b0da0be61c01", "title": "title", "cost": 100, "prompt": "reward prompt" }},
+            "redeemed_at": "2020-07-15T17:16:03.17106713Z"
+        }}"#
+    )
+}
+
+fn notification(condition: &str) -> Vec<u8> {
+    format!(
+        r#"{{"subscription": {}, "event": {}}}"#,
+        subscription(condition),
+        event("hello")
+    )
+    .into_bytes()
+}
+
+fn nested(depth: usize) -> String {
+    format!(
+        r#"{{"broadcaster_user_id": "1337", "nested": {}{}}}"#,
+        "[".repeat(depth),
+        "]".repeat(depth)
+    )
+}
+
+#[test]
+fn decodes_notification() {
+    let body = notification(r#"{ "broadcaster_user_id": "1337" }"#);
+    let payload = decode_payload::<ChannelPointsCustomRewardRedemptionAddV1>(
+        MessageType::Notification,
+        &body,
+        Some(8),
+    )
+    .unwrap();
+    assert!(matches!(payload, EventsubPayload::Notification(_)));
+}
+
+#[test]
+fn redemption_accessors() {
+    let decode = |body: &[u8]| {
+        decode_payload::<ChannelPointsCustomRewardRedemptionAddV1>(
+            MessageType::Notification,
+            body,
+            None,
+        )
+        .unwrap()
+    };
+
+    let payload = decode(&notification(r#"{ "broadcaster_user_id": "1337" }"#));
+    let redemption = payload.event().unwrap();
+    assert_eq!(redemption.redeemer_login(), "cooler_user");
+    assert_eq!(redemption.redeemer_name(), "Cooler_User");
+    assert_eq!(redemption.reward_title(), "title");
+    assert_eq!(redemption.reward_cost(), 100);
+    assert_eq!(redemption.user_input(), Some("hello"));
+
+    let body = format!(
+        r#"{{"subscription": {}, "event": {}}}"#,
+        subscription(r#"{ "broadcaster_user_id": "1337" }"#),
+        event("")
+    );
+    assert_eq!(decode(body.as_bytes()).event().unwrap().user_input(), None);
+}
+
+#[test]
+fn typed_condition() {
+    let body = notification(
+        r#"{ "broadcaster_user_id": "1337", "reward_id": "92af127c-7326-4483-a52b-b0da0be61c01" }"#,
+    );
+    let payload = decode_payload::<ChannelPointsCustomRewardRedemptionAddV1>(
+        MessageType::Notification,
+        &body,
+        None,
+    )
+    .unwrap();
+    let condition = payload
+        .condition::<ChannelPointsCustomRewardRedemptionAddV1>()
+        .unwrap();
+    assert_eq!(condition.broadcaster_user_id.as_str(), "1337");
+    assert_eq!(
+        condition.reward_id.as_ref().map(|id| id.as_str()),
+        Some("92af127c-7326-4483-a52b-b0da0be61c01")
+    );
+
+    let EventsubPayload::Notification(message) = payload else {
+        panic!("expected a notification");
+    };
+    assert_eq!(
+        message
+            .condition::<ChannelPointsCustomRewardRedemptionAddV1>()
+            .unwrap(),
+        condition
+    );
+
+    let body = notification(r#"{ "reward_id": "92af127c-7326-4483-a52b-b0da0be61c01" }"#);
+    let payload = decode_payload::<ChannelPointsCustomRewardRedemptionAddV1>(
+        MessageType::Notification,
+        &body,
+        None,
+    );
+    // the event is still valid, but the condition misses the broadcaster
+    assert!(payload
+        .unwrap()
+        .condition::<ChannelPointsCustomRewardRedemptionAddV1>()
+        .is_err());
+}
+
+#[test]
+fn map_keeps_subscription() {
+    let sub = subscription(r#"{ "broadcaster_user_id": "1337" }"#);
+    let bodies = [
+        (
+            MessageType::Notification,
+            notification(r#"{ "broadcaster_user_id": "1337" }"#),
+        ),
+        (
+            MessageType::Verification,
+            format!(r#"{{"challenge": "pogchamp", "subscription": {sub}}}"#).into_bytes(),
+        ),
+        (
+            MessageType::Revocation,
+            format!(r#"{{"subscription": {sub}}}"#).into_bytes(),
+        ),
+    ];
+    for (message_type, body) in bodies {
+        let payload =
+            decode_payload::<ChannelPointsCustomRewardRedemptionAddV1>(message_type, &body, None)
+                .unwrap();
+        let expected = payload.subscription().clone();
+        let mapped = payload.map(|event| event.broadcaster_user_id.as_str().len());
+        assert_eq!(mapped.subscription(), &expected);
+        match mapped {
+            EventsubPayload::Notification(message) => assert_eq!(message.event, 4),
+            EventsubPayload::Verification(verification) => {
+                assert_eq!(verification.challenge, "pogchamp");
+            }
+            EventsubPayload::Revocation(_) => assert_eq!(message_type, MessageType::Revocation),
+        }
+    }
+}
+
+#[test]
+fn default_depth_limit() {
+    let body = notification(&nested(100_000));
+    let error = decode_payload::<ChannelPointsCustomRewardRedemptionAddV1>(
+        MessageType::Notification,
+        &body,
+        None,
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("recursion limit exceeded"));
+}
+
+#[test]
+fn custom_depth_limit() {
+    // the payload, the subscription and the condition add three levels
+    let body = notification(&nested(5));
+    decode_payload::<ChannelPointsCustomRewardRedemptionAddV1>(
+        MessageType::Notification,
+        &body,
+        Some(8),
+    )
+    .unwrap();
+
+    let body = notification(&nested(6));
+    let error = decode_payload::<ChannelPointsCustomRewardRedemptionAddV1>(
+        MessageType::Notification,
+        &body,
+        Some(8),
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("recursion limit exceeded"));
+
+    // brackets inside strings don't count
+    let body = notification(r#"{"broadcaster_user_id": "1337", "text": "[[[[[[[[[[\"[[["}"#);
+    decode_payload::<ChannelPointsCustomRewardRedemptionAddV1>(
+        MessageType::Notification,
+        &body,
+        Some(8),
+    )
+    .unwrap();
+}
+
+#[test]
+fn scans_subscription_id() {
+    let body = notification(&nested(8));
+    assert_eq!(
+        subscription_id(&body).unwrap(),
+        "f1c2a387-161a-49f9-a165-0f21d7a4e1c4"
+    );
+
+    assert!(subscription_id(br#"{"subscription": {}}"#).is_err());
+    assert!(subscription_id(br#"{"subscription": {"id": "a"}}trailing"#).is_err());
+}
+
+#[test]
+fn subscription_from_prefix() {
+    let body = notification(r#"{ "broadcaster_user_id": "1337" }"#);
+    let end = body.len() - 10;
+    let leading = leading_subscription(&body[..end]).unwrap();
+    assert_eq!(leading.id.as_str(), "f1c2a387-161a-49f9-a165-0f21d7a4e1c4");
+
+    // keys before the subscription are skipped
+    let body = format!(
+        r#"{{"challenge": "abc", "subscription": {}"#,
+        subscription("{}")
+    );
+    assert!(leading_subscription(body.as_bytes()).is_ok());
+
+    let cut = body.len() / 2;
+    assert!(leading_subscription(&body.as_bytes()[..cut]).is_err());
+    assert!(leading_subscription(br#"{"event": {}}"#).is_err());
+}
+
+#[test]
+fn conduit_transport() {
+    let body = notification(r#"{ "broadcaster_user_id": "1337" }"#);
+    let payload = decode_payload::<ChannelPointsCustomRewardRedemptionAddV1>(
+        MessageType::Notification,
+        &body,
+        None,
+    )
+    .unwrap();
+    assert_eq!(payload.conduit_id(), None);
+
+    let body = String::from_utf8(body).unwrap().replace(
+        r#"{ "method": "webhook", "callback": "https://example.com/eventsub" }"#,
+        r#"{ "method": "conduit", "conduit_id": "bfcfc993-26b1-b876-44d9-afe75a379dac" }"#,
+    );
+    let payload = decode_payload::<ChannelPointsCustomRewardRedemptionAddV1>(
+        MessageType::Notification,
+        body.as_bytes(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        payload.conduit_id(),
+        Some("bfcfc993-26b1-b876-44d9-afe75a379dac")
+    );
+}
+
+#[test]
+fn decodes_event() {
+    let body = notification(r#"{ "broadcaster_user_id": "1337" }"#);
+    let payload = decode_payload::<ChannelPointsCustomRewardRedemptionAddV1>(
+        MessageType::Notification,
+        &body,
+        None,
+    )
+    .unwrap();
+    let EventsubPayload::Notification(notification) = payload else {
+        panic!("expected a notification");
+    };
+    let redemption = notification.event;
+    assert_eq!(redemption.user_login.as_str(), "cooler_user");
+    assert_eq!(redemption.user_name.as_str(), "Cooler_User");
+    assert_eq!(redemption.reward.title, "title");
+    assert_eq!(redemption.reward.cost, 100);
+    assert_eq!(redemption.user_input, "hello");
+
+    // the condition isn't a full event
+    let body = format!(
+        r#"{{"subscription": {}, "event": {{"broadcaster_user_id": "1337"}}}}"#,
+        subscription(r#"{ "broadcaster_user_id": "1337" }"#)
+    );
+    assert!(decode_payload::<ChannelPointsCustomRewardRedemptionAddV1>(
+        MessageType::Notification,
+        body.as_bytes(),
+        None,
+    )
+    .is_err());
+}
+
+#[test]
+fn decodes_in_place() {
+    let body = format!(
+        r#"{{"subscription": {}, "event": {}}}"#,
+        subscription(r#"{ "broadcaster_user_id": "1337" }"#),
+        event(r#"\"quoted\" \u00fcber"#)
+    );
+    let decode = |body: &mut [u8], max_depth| {
+        decode_payload_in_place::<ChannelPointsCustomRewardRedemptionAddV1>(
+            MessageType::Notification,
+            body,
+            max_depth,
+        )
+    };
+
+    let payload = decode(&mut body.clone().into_bytes(), None).unwrap();
+    assert_eq!(
+        payload,
+        decode_payload::<ChannelPointsCustomRewardRedemptionAddV1>(
+            MessageType::Notification,
+            body.as_bytes(),
+            None,
+        )
+        .unwrap()
+    );
+    let EventsubPayload::Notification(message) = payload else {
+        panic!("expected a notification");
+    };
+    assert_eq!(message.event.user_input, "\"quoted\" über");
+
+    // the depth is limited like without the body being mutable
+    let error = decode(&mut notification(&nested(100_000)), None).unwrap_err();
+    assert!(error.to_string().contains("recursion limit exceeded"));
+    let error = decode(&mut notification(&nested(8)), Some(8)).unwrap_err();
+    assert!(error.to_string().contains("recursion limit exceeded"));
+    assert!(decode(&mut b"{}".to_vec(), None).is_err());
+}
+
+#[test]
+fn decodes_batch() {
+    let single = notification(r#"{ "broadcaster_user_id": "1337" }"#);
+    let decode = |body: &[u8]| {
+        decode_batch::<ChannelPointsCustomRewardRedemptionAddV1>(
+            MessageType::Notification,
+            body,
+            Some(8),
+        )
+    };
+
+    assert_eq!(decode(&single).unwrap().len(), 1);
+
+    let batch = [&b" ["[..], &single, b",", &single, b"]"].concat();
+    let payloads = decode(&batch).unwrap();
+    assert_eq!(payloads.len(), 2);
+    assert!(payloads
+        .iter()
+        .all(|payload| matches!(payload, EventsubPayload::Notification(_))));
+
+    let error = decode(b"[]").unwrap_err();
+    assert!(error.to_string().contains("the batch is empty"));
+    let error = decode(b"\"1337\"").unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("expected a payload or an array of payloads"));
+    // all payloads have to be valid
+    let batch = [&b"["[..], &single, b",{}]"].concat();
+    assert!(decode(&batch).is_err());
+}
+
+#[test]
+fn pending_verification() {
+    // the subscription is pending until the challenge is answered
+    let body = format!(
+        r#"{{"challenge": "pogchamp-kappa-360noscope-vohiyo", "subscription": {}}}"#,
+        subscription(r#"{ "broadcaster_user_id": "1337" }"#).replace(
+            r#""status": "enabled""#,
+            r#""status": "webhook_callback_verification_pending""#
+        )
+    );
+    let payload = decode_payload::<ChannelPointsCustomRewardRedemptionAddV1>(
+        MessageType::Verification,
+        body.as_bytes(),
+        None,
+    )
+    .unwrap();
+    let EventsubPayload::Verification(verification) = payload else {
+        panic!("expected a verification, got {payload:?}");
+    };
+    assert_eq!(verification.challenge, "pogchamp-kappa-360noscope-vohiyo");
+    assert_eq!(
+        verification.subscription.status,
+        Status::WebhookCallbackVerificationPending
+    );
+}
@@ -0,0 +1,278 @@
+#![cfg(feature = "clock")]
+
+use chrono::{Duration, TimeZone, Utc};
+use eventsub_common::{
+    headers::{
+        parse_timestamp, read_eventsub_headers, read_eventsub_headers_untyped, retries_exceeded,
+        validate_all_headers, HeaderMapExt, HeaderType, InvalidHeaderValue, InvalidHeaders,
+        MAX_VALUE_LEN, MESSAGE_ID, MESSAGE_RETRY, MESSAGE_SIGNATURE, MESSAGE_TIMESTAMP,
+        MESSAGE_TYPE, SUBSCRIPTION_TYPE, SUBSCRIPTION_VERSION,
+    },
+    types::channel::ChannelPointsCustomRewardRedemptionAddV1,
+};
+use http::{HeaderMap, HeaderValue};
+
+fn headers() -> HeaderMap {
+    let timestamp = Utc::now().to_rfc3339();
+    let mut map = HeaderMap::new();
+    for (name, value) in [
+        (MESSAGE_ID, "e76c6bd4-55c9-4987-8304-da1588d8988b"),
+        (MESSAGE_TIMESTAMP, &timestamp),
+        (
+            MESSAGE_SIGNATURE,
+            "sha256=3a059da461e1eec8c171660e21ed23da2c1050c6bb4e637a6629f1584667f801",
+        ),
+        (MESSAGE_TYPE, "notification"),
+        (
+            SUBSCRIPTION_TYPE,
+            "channel.channel_points_custom_reward_redemption.add",
+        ),
+        (SUBSCRIPTION_VERSION, "1"),
+    ] {
+        map.insert(name, HeaderValue::from_str(value).unwrap());
+    }
+    map
+}
+
+fn read(headers: &HeaderMap) -> Result<(), InvalidHeaders> {
+    read_eventsub_headers::<_, ChannelPointsCustomRewardRedemptionAddV1>(headers).map(|_| ())
+}
+
+#[test]
+fn valid_headers() {
+    assert_eq!(read(&headers()), Ok(()));
+}
+
+#[test]
+fn missing_type() {
+    let mut headers = headers();
+    headers.remove(SUBSCRIPTION_TYPE);
+    headers.insert(SUBSCRIPTION_VERSION, HeaderValue::from_static("2"));
+    assert_eq!(
+        read(&headers),
+        Err(InvalidHeaders::Missing(HeaderType::SubscriptionType))
+    );
+}
+
+#[test]
+fn missing_version() {
+    let mut headers = headers();
+    headers.remove(SUBSCRIPTION_VERSION);
+    headers.insert(
+        SUBSCRIPTION_TYPE,
+        HeaderValue::from_static("channel.follow"),
+    );
+    assert_eq!(
+        read(&headers),
+        Err(InvalidHeaders::Missing(HeaderType::SubscriptionVersion))
+    );
+}
+
+#[test]
+fn wrong_version() {
+    let mut headers = headers();
+    headers.insert(SUBSCRIPTION_VERSION, HeaderValue::from_static("2"));
+    headers.insert(MESSAGE_SIGNATURE, HeaderValue::from_static("md5=abc"));
+    assert_eq!(read(&headers), Err(InvalidHeaders::VersionMismatch("1")));
+
+    // the type is checked before the version
+    headers.insert(
+        SUBSCRIPTION_TYPE,
+        HeaderValue::from_static("channel.follow"),
+    );
+    assert_eq!(
+        read(&headers),
+        Err(InvalidHeaders::WrongSubscriptionType(
+            "channel.channel_points_custom_reward_redemption.add"
+        ))
+    );
+}
+
+#[test]
+fn presence_before_values() {
+    let mut headers = headers();
+    headers.insert(
+        SUBSCRIPTION_TYPE,
+        HeaderValue::from_static("channel.follow"),
+    );
+    headers.remove(MESSAGE_TIMESTAMP);
+    assert_eq!(
+        read(&headers),
+        Err(InvalidHeaders::Missing(HeaderType::Timestamp))
+    );
+}
+
+#[test]
+fn twitch_timestamps() {
+    let at = |h, min, s, nanos| {
+        Utc.with_ymd_and_hms(2023, 7, 19, h, min, s).unwrap() + Duration::nanoseconds(nanos)
+    };
+    for (sample, expected) in [
+        (
+            "2023-07-19T14:56:51.310938745Z",
+            at(14, 56, 51, 310_938_745),
+        ),
+        ("2023-07-19T14:56:51.63417425Z", at(14, 56, 51, 634_174_250)),
+        ("2023-07-19T14:56:51.5Z", at(14, 56, 51, 500_000_000)),
+        ("2023-07-19T14:56:51Z", at(14, 56, 51, 0)),
+        ("2023-07-19T16:56:51.5+02:00", at(14, 56, 51, 500_000_000)),
+    ] {
+        assert_eq!(
+            parse_timestamp(&HeaderValue::from_static(sample)),
+            Ok(expected),
+            "{sample}"
+        );
+    }
+}
+
+#[test]
+fn malformed_timestamps() {
+    for sample in [
+        "",
+        "yesterday",
+        "2023-07-19 14:56:51",
+        "2023-07-19T14:56:51",
+        "2023-07-19",
+        "1689778611",
+    ] {
+        assert_eq!(
+            parse_timestamp(&HeaderValue::from_static(sample)),
+            Err(InvalidHeaders::BadTimestamp),
+            "{sample}"
+        );
+    }
+
+    let mut headers = headers();
+    headers.insert(MESSAGE_TIMESTAMP, HeaderValue::from_static("yesterday"));
+    assert_eq!(read(&headers), Err(InvalidHeaders::BadTimestamp));
+}
+
+#[test]
+fn captures_bad_values() {
+    let mut headers = headers();
+    headers.insert(MESSAGE_TYPE, HeaderValue::from_static("notification "));
+    let error = InvalidHeaderValue::new(read(&headers).unwrap_err(), &headers);
+    assert_eq!(
+        error,
+        InvalidHeaderValue {
+            error: InvalidHeaders::BadMessageType,
+            value: Some("notification ".into()),
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        r#"This message type is not recognized: "notification ""#
+    );
+
+    let mut headers = self::headers();
+    headers.insert(MESSAGE_SIGNATURE, HeaderValue::from_static("sha256=xyz"));
+    let error = read(&headers).unwrap_err();
+    assert_eq!(error, InvalidHeaders::SignatureNotHex);
+    assert_eq!(
+        InvalidHeaderValue::new(error, &headers).value.as_deref(),
+        Some("sha256=xyz")
+    );
+
+    // long values are truncated
+    let signature = format!("sha256={}", "z".repeat(100));
+    headers.insert(
+        MESSAGE_SIGNATURE,
+        HeaderValue::from_str(&signature).unwrap(),
+    );
+    let error = read(&headers).unwrap_err();
+    let value = InvalidHeaderValue::new(error, &headers).value.unwrap();
+    assert_eq!(value.len(), MAX_VALUE_LEN + 3);
+    assert_eq!(&*value, format!("{}...", &signature[..MAX_VALUE_LEN]));
+
+    // missing headers have no value
+    headers.remove(MESSAGE_TIMESTAMP);
+    let error = InvalidHeaderValue::new(read(&headers).unwrap_err(), &headers);
+    assert_eq!(error.value, None);
+    assert_eq!(error.to_string(), "Missing header Timestamp");
+}
+
+#[test]
+fn id_not_utf8() {
+    let mut headers = headers();
+    headers.insert(MESSAGE_ID, HeaderValue::from_bytes(b"e76c\xff").unwrap());
+    assert_eq!(read(&headers), Err(InvalidHeaders::IdNotUtf8));
+}
+
+#[test]
+fn subscription_not_utf8() {
+    let mut headers = headers();
+    headers.insert(
+        SUBSCRIPTION_VERSION,
+        HeaderValue::from_bytes(b"1\xff").unwrap(),
+    );
+    assert_eq!(
+        read_eventsub_headers_untyped(&headers).map(|_| ()),
+        Err(InvalidHeaders::NotUtf8(HeaderType::SubscriptionVersion))
+    );
+    // non-ASCII is fine, as long as it's UTF-8
+    headers.insert(
+        SUBSCRIPTION_TYPE,
+        HeaderValue::from_bytes("channel.ü".as_bytes()).unwrap(),
+    );
+    headers.insert(SUBSCRIPTION_VERSION, HeaderValue::from_static("1"));
+    let parsed = read_eventsub_headers_untyped(&headers).unwrap();
+    assert_eq!(parsed.meta().subscription_type, "channel.ü");
+}
+
+#[test]
+fn validate_all() {
+    let mut headers = headers();
+    assert_eq!(validate_all_headers(&headers), Ok(()));
+
+    headers.remove(SUBSCRIPTION_VERSION);
+    headers.remove(MESSAGE_SIGNATURE);
+    headers.remove(MESSAGE_TIMESTAMP);
+    assert_eq!(
+        read(&headers),
+        Err(InvalidHeaders::Missing(HeaderType::SubscriptionVersion))
+    );
+    assert_eq!(
+        validate_all_headers(&headers),
+        Err(vec![
+            InvalidHeaders::Missing(HeaderType::SubscriptionVersion).into(),
+            InvalidHeaders::Missing(HeaderType::Signature).into(),
+            InvalidHeaders::Missing(HeaderType::Timestamp).into(),
+        ])
+    );
+
+    headers.insert(MESSAGE_TYPE, HeaderValue::from_static("unknown"));
+    headers.insert(MESSAGE_ID, HeaderValue::from_bytes(b"e76c\xff").unwrap());
+    assert_eq!(
+        validate_all_headers(&headers),
+        Err(vec![
+            InvalidHeaders::Missing(HeaderType::SubscriptionVersion).into(),
+            InvalidHeaderValue {
+                error: InvalidHeaders::BadMessageType,
+                value: Some("unknown".into()),
+            },
+            InvalidHeaders::Missing(HeaderType::Signature).into(),
+            InvalidHeaderValue {
+                error: InvalidHeaders::IdNotUtf8,
+                value: Some("e76c\u{fffd}".into()),
+            },
+            InvalidHeaders::Missing(HeaderType::Timestamp).into(),
+        ])
+    );
+}
+
+#[test]
+fn retry_count() {
+    let mut headers = headers();
+    assert_eq!(headers.get_message_retry(), None);
+    assert!(!retries_exceeded(&headers, Some(0)));
+
+    headers.insert(MESSAGE_RETRY, HeaderValue::from_static("3"));
+    assert_eq!(headers.get_message_retry(), Some(3));
+    assert!(!retries_exceeded(&headers, None));
+    assert!(!retries_exceeded(&headers, Some(3)));
+    assert!(retries_exceeded(&headers, Some(2)));
+
+    headers.insert(MESSAGE_RETRY, HeaderValue::from_static("-1"));
+    assert_eq!(headers.get_message_retry(), None);
+    assert!(!retries_exceeded(&headers, Some(0)));
+}
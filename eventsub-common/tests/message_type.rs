@@ -0,0 +1,45 @@
+use eventsub_common::MessageType;
+use http::HeaderValue;
+
+const ALL: [MessageType; 3] = [
+    MessageType::Notification,
+    MessageType::Verification,
+    MessageType::Revocation,
+];
+
+fn parse(value: &'static str) -> Result<MessageType, ()> {
+    MessageType::try_from(&HeaderValue::from_static(value))
+}
+
+#[test]
+fn known_values() {
+    assert_eq!(parse("notification"), Ok(MessageType::Notification));
+    assert_eq!(
+        parse("webhook_callback_verification"),
+        Ok(MessageType::Verification)
+    );
+    assert_eq!(parse("revocation"), Ok(MessageType::Revocation));
+
+    assert_eq!(
+        MessageType::Verification.as_str(),
+        "webhook_callback_verification"
+    );
+}
+
+#[test]
+fn unknown_values() {
+    assert_eq!(parse("verification"), Err(()));
+    assert_eq!(parse("Notification"), Err(()));
+    assert_eq!(parse(""), Err(()));
+}
+
+#[test]
+fn round_trip() {
+    for message_type in ALL {
+        // Fails to compile if a message type is added - add it to `ALL` as well.
+        match message_type {
+            MessageType::Notification | MessageType::Verification | MessageType::Revocation => (),
+        }
+        assert_eq!(parse(message_type.as_str()), Ok(message_type));
+    }
+}
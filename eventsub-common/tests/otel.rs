@@ -0,0 +1,103 @@
+#![cfg(feature = "opentelemetry")]
+
+use chrono::Utc;
+use eventsub_common::{
+    headers::{
+        read_eventsub_headers_untyped, EventMeta, MESSAGE_ID, MESSAGE_SIGNATURE, MESSAGE_TIMESTAMP,
+        MESSAGE_TYPE, SUBSCRIPTION_TYPE, SUBSCRIPTION_VERSION,
+    },
+    otel::delivery_context_with,
+};
+use http::{HeaderMap, HeaderValue};
+use opentelemetry::{
+    trace::{SpanKind, TraceContextExt, TracerProvider as _},
+    Context, Key, Value,
+};
+use opentelemetry_sdk::{
+    export::trace::SpanData,
+    trace::{Span, SpanProcessor, TracerProvider},
+};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Default, Clone)]
+struct Recorder(Arc<Mutex<Vec<SpanData>>>);
+
+impl SpanProcessor for Recorder {
+    fn on_start(&self, _span: &mut Span, _cx: &Context) {}
+
+    fn on_end(&self, span: SpanData) {
+        self.0.lock().unwrap().push(span);
+    }
+
+    fn force_flush(&self) -> opentelemetry::trace::TraceResult<()> {
+        Ok(())
+    }
+
+    fn shutdown(&self) -> opentelemetry::trace::TraceResult<()> {
+        Ok(())
+    }
+}
+
+fn meta() -> EventMeta {
+    let timestamp = Utc::now().to_rfc3339();
+    let mut headers = HeaderMap::new();
+    for (name, value) in [
+        (MESSAGE_ID, "e76c6bd4-55c9-4987-8304-da1588d8988b"),
+        (MESSAGE_TIMESTAMP, &timestamp),
+        (MESSAGE_SIGNATURE, "sha256=00"),
+        (MESSAGE_TYPE, "notification"),
+        (SUBSCRIPTION_TYPE, "channel.follow"),
+        (SUBSCRIPTION_VERSION, "2"),
+    ] {
+        headers.insert(name, HeaderValue::from_str(value).unwrap());
+    }
+    read_eventsub_headers_untyped(&headers).unwrap().meta()
+}
+
+#[test]
+fn delivery_span() {
+    let recorder = Recorder::default();
+    let provider = TracerProvider::builder()
+        .with_span_processor(recorder.clone())
+        .build();
+    let tracer = provider.tracer("test");
+
+    // an unrelated trace is current - the delivery still starts a new one
+    let outer = Context::new().with_span(opentelemetry::trace::Tracer::start(&tracer, "outer"));
+    let _guard = outer.clone().attach();
+
+    let context = delivery_context_with(&tracer, &meta());
+    assert!(context.has_active_span());
+    let trace_id = context.span().span_context().trace_id();
+    assert_ne!(trace_id, outer.span().span_context().trace_id());
+    drop(context);
+
+    let spans = recorder.0.lock().unwrap();
+    assert_eq!(spans.len(), 1);
+    let span = &spans[0];
+    assert_eq!(span.name, "eventsub channel.follow");
+    assert_eq!(span.span_kind, SpanKind::Server);
+    assert_eq!(span.span_context.trace_id(), trace_id);
+    let attribute = |key: &'static str| {
+        span.attributes
+            .iter()
+            .find(|kv| kv.key == Key::from_static_str(key))
+            .map(|kv| kv.value.clone())
+    };
+    assert_eq!(
+        attribute("eventsub.subscription.type"),
+        Some(Value::from("channel.follow"))
+    );
+    assert_eq!(
+        attribute("eventsub.subscription.version"),
+        Some(Value::from("2"))
+    );
+    assert_eq!(
+        attribute("eventsub.message.id"),
+        Some(Value::from("e76c6bd4-55c9-4987-8304-da1588d8988b"))
+    );
+    assert_eq!(
+        attribute("eventsub.message.type"),
+        Some(Value::from("notification"))
+    );
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "memory-store")]
+
+use eventsub_common::store::EventIdStore;
+use std::time::Duration;
+
+const ID: &str = "a6e2dcd1-3e18-8d6c-7a0f-6fc8f7fd5a51";
+
+#[tokio::test(start_paused = true)]
+async fn lazy_expiry() {
+    let store = EventIdStore::with_ttl(Duration::from_secs(60));
+    assert!(store.check_and_insert(ID));
+    assert!(!store.check_and_insert(ID));
+
+    tokio::time::advance(Duration::from_secs(61)).await;
+    assert!(!store.contains(ID));
+    // still stored until it's accessed or purged
+    assert_eq!(store.len(), 1);
+    assert!(store.check_and_insert(ID));
+    assert!(!store.check_and_insert(ID));
+}
+
+#[tokio::test(start_paused = true)]
+async fn sweeper_purges_expired() {
+    let store = EventIdStore::with_ttl(Duration::from_secs(60));
+    let sweeper = store.spawn_sweeper(Duration::from_secs(30));
+    assert!(store.check_and_insert(ID));
+
+    tokio::time::sleep(Duration::from_secs(31)).await;
+    assert_eq!(store.len(), 1);
+
+    tokio::time::sleep(Duration::from_secs(30)).await;
+    assert!(store.is_empty());
+
+    // a stopped sweeper doesn't purge anymore
+    sweeper.stop();
+    assert!(store.check_and_insert(ID));
+    tokio::time::sleep(Duration::from_secs(120)).await;
+    assert_eq!(store.len(), 1);
+    assert_eq!(store.purge_expired(), 1);
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "deserialize")]
+
+use eventsub_common::{subscription::build_create_subscription, Revocation};
+use serde_json::json;
+
+#[test]
+fn resubscribe_from_revocation() {
+    let revocation: Revocation = serde_json::from_value(json!({
+        "subscription": {
+            "id": "f1c2a387-161a-49f9-a165-0f21d7a4e1c4",
+            "status": "notification_failures_exceeded",
+            "type": "channel.channel_points_custom_reward_redemption.add",
+            "version": "1",
+            "condition": { "broadcaster_user_id": "1337", "reward_id": "" },
+            "transport": { "method": "webhook", "callback": "https://example.com/old" },
+            "created_at": "2019-11-16T10:11:12.634234626Z",
+            "cost": 0
+        }
+    }))
+    .unwrap();
+
+    let request = build_create_subscription(
+        &revocation.subscription,
+        "https://example.com/eventsub",
+        "a fresh secret",
+    );
+    assert_eq!(
+        serde_json::to_value(&request).unwrap(),
+        json!({
+            "type": "channel.channel_points_custom_reward_redemption.add",
+            "version": "1",
+            "condition": { "broadcaster_user_id": "1337", "reward_id": "" },
+            "transport": {
+                "method": "webhook",
+                "callback": "https://example.com/eventsub",
+                "secret": "a fresh secret"
+            }
+        })
+    );
+    assert!(!format!("{request:?}").contains("a fresh secret"));
+}
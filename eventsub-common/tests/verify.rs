@@ -0,0 +1,270 @@
+#![cfg(feature = "deserialize")]
+
+use chrono::{DateTime, Duration, Utc};
+use eventsub_common::{
+    headers::{
+        InvalidHeaders, MESSAGE_ID, MESSAGE_SIGNATURE, MESSAGE_TIMESTAMP, MESSAGE_TYPE,
+        SUBSCRIPTION_TYPE, SUBSCRIPTION_VERSION,
+    },
+    types::channel::{
+        ChannelPointsCustomRewardRedemptionAddV1, ChannelPointsCustomRewardRedemptionUpdateV1,
+    },
+    verify::{
+        sign, verify_and_decode_at, verify_and_decode_raw_at, verify_only_at, verify_raw_at,
+        VerifyError,
+    },
+    EventsubPayload, MessageType,
+};
+use http::{HeaderMap, HeaderValue};
+
+const SECRET: &[u8] = b"secretsecret";
+const BODY: &str = r#"{
+    "challenge": "pogchamp-kappa-360noscope-vohiyo",
+    "subscription": {
+        "id": "f1c2a387-161a-49f9-a165-0f21d7a4e1c4",
+        "status": "webhook_callback_verification_pending",
+        "type": "channel.channel_points_custom_reward_redemption.add",
+        "version": "1",
+        "condition": { "broadcaster_user_id": "1337" },
+        "transport": { "method": "webhook", "callback": "https://example.com/eventsub" },
+        "created_at": "2019-11-16T10:11:12.634234626Z",
+        "cost": 0
+    }
+}"#;
+
+fn headers(timestamp: DateTime<Utc>, body: &[u8]) -> HeaderMap {
+    let id = "e76c6bd4-55c9-4987-8304-da1588d8988b";
+    let timestamp = timestamp.to_rfc3339();
+    let signature = sign(SECRET, id, &timestamp, body);
+
+    let mut map = HeaderMap::new();
+    for (name, value) in [
+        (MESSAGE_ID, id),
+        (MESSAGE_TIMESTAMP, &timestamp),
+        (MESSAGE_SIGNATURE, &signature),
+        (MESSAGE_TYPE, "webhook_callback_verification"),
+        (
+            SUBSCRIPTION_TYPE,
+            "channel.channel_points_custom_reward_redemption.add",
+        ),
+        (SUBSCRIPTION_VERSION, "1"),
+    ] {
+        map.insert(name, HeaderValue::from_str(value).unwrap());
+    }
+    map
+}
+
+#[test]
+fn verifies_and_decodes() {
+    let now = Utc::now();
+    let headers = headers(now, BODY.as_bytes());
+
+    let payload = verify_and_decode_at::<ChannelPointsCustomRewardRedemptionAddV1, _>(
+        &headers,
+        BODY.as_bytes(),
+        SECRET,
+        now,
+    )
+    .unwrap();
+    let EventsubPayload::Verification(verification) = payload else {
+        panic!("expected a verification, got {payload:?}");
+    };
+    assert_eq!(verification.challenge, "pogchamp-kappa-360noscope-vohiyo");
+}
+
+#[test]
+fn verifies_without_decoding() {
+    let now = Utc::now();
+    let mut headers = headers(now, BODY.as_bytes());
+    // any subscription is accepted
+    headers.insert(
+        SUBSCRIPTION_TYPE,
+        HeaderValue::from_static("channel.follow"),
+    );
+
+    let verified = verify_only_at(&headers, BODY, SECRET, now).unwrap();
+    assert_eq!(verified.message_type, MessageType::Verification);
+    assert_eq!(verified.body, BODY);
+
+    let error = verify_only_at(&headers, "{}", SECRET, now).unwrap_err();
+    assert!(matches!(error, VerifyError::SignatureMismatch));
+}
+
+#[test]
+fn verifies_raw_then_typed() {
+    let now = Utc::now();
+    let headers = headers(now, BODY.as_bytes());
+
+    let verified = verify_raw_at(&headers, BODY.as_bytes(), SECRET, now).unwrap();
+    assert_eq!(
+        verified.meta.message_id,
+        "e76c6bd4-55c9-4987-8304-da1588d8988b"
+    );
+    assert_eq!(verified.meta.message_type, MessageType::Verification);
+    assert!(verified.is::<ChannelPointsCustomRewardRedemptionAddV1>());
+    assert!(!verified.is::<ChannelPointsCustomRewardRedemptionUpdateV1>());
+
+    let error = verified
+        .clone()
+        .into_typed::<ChannelPointsCustomRewardRedemptionUpdateV1>()
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        VerifyError::Headers(InvalidHeaders::WrongSubscriptionType(
+            "channel.channel_points_custom_reward_redemption.update"
+        ))
+    ));
+
+    let payload = verified
+        .into_typed::<ChannelPointsCustomRewardRedemptionAddV1>()
+        .unwrap();
+    assert!(matches!(payload, EventsubPayload::Verification(_)));
+
+    let error = verify_raw_at(&headers, b"{}", SECRET, now).unwrap_err();
+    assert!(matches!(error, VerifyError::SignatureMismatch));
+}
+
+#[test]
+fn rejects_modified_body() {
+    let now = Utc::now();
+    let headers = headers(now, BODY.as_bytes());
+    let body = BODY.replace("pogchamp", "kappa");
+
+    let error = verify_and_decode_at::<ChannelPointsCustomRewardRedemptionAddV1, _>(
+        &headers,
+        body.as_bytes(),
+        SECRET,
+        now,
+    )
+    .unwrap_err();
+    assert!(matches!(error, VerifyError::SignatureMismatch));
+}
+
+#[test]
+fn rejects_reserialized_body() {
+    let now = Utc::now();
+    let headers = headers(now, BODY.as_bytes());
+
+    let verified = verify_and_decode_raw_at::<ChannelPointsCustomRewardRedemptionAddV1, _, _>(
+        &headers,
+        BODY.as_bytes(),
+        SECRET,
+        now,
+    )
+    .unwrap();
+    assert_eq!(verified.body, BODY.as_bytes());
+    assert_eq!(verified.meta.message_type, MessageType::Verification);
+    assert!(matches!(verified.payload, EventsubPayload::Verification(_)));
+
+    // the same JSON in a canonical form has different bytes, so the signature doesn't match
+    let canonical =
+        serde_json::to_vec(&serde_json::from_str::<serde_json::Value>(BODY).unwrap()).unwrap();
+    assert_ne!(canonical, BODY.as_bytes());
+    let error = verify_and_decode_raw_at::<ChannelPointsCustomRewardRedemptionAddV1, _, _>(
+        &headers, &canonical, SECRET, now,
+    )
+    .unwrap_err();
+    assert!(matches!(error, VerifyError::SignatureMismatch));
+}
+
+#[test]
+fn signature_hex_case() {
+    let now = Utc::now();
+    let mut headers = headers(now, BODY.as_bytes());
+    let signature = headers[MESSAGE_SIGNATURE].to_str().unwrap().to_owned();
+    let hex = &signature["sha256=".len()..];
+    let mixed: String = hex
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if i % 2 == 0 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    assert_ne!(mixed, hex);
+
+    for hex in [hex.to_owned(), hex.to_ascii_uppercase(), mixed] {
+        headers.insert(
+            MESSAGE_SIGNATURE,
+            HeaderValue::from_str(&format!("sha256={hex}")).unwrap(),
+        );
+        let verified = verify_only_at(&headers, BODY, SECRET, now).unwrap();
+        assert_eq!(verified.message_type, MessageType::Verification, "{hex}");
+        verify_and_decode_at::<ChannelPointsCustomRewardRedemptionAddV1, _>(
+            &headers,
+            BODY.as_bytes(),
+            SECRET,
+            now,
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn rejects_old_message() {
+    let sent = Utc::now();
+    let headers = headers(sent, BODY.as_bytes());
+
+    let error = verify_and_decode_at::<ChannelPointsCustomRewardRedemptionAddV1, _>(
+        &headers,
+        BODY.as_bytes(),
+        SECRET,
+        sent + Duration::minutes(11),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        error,
+        VerifyError::Headers(InvalidHeaders::MessageTooOld)
+    ));
+}
+
+#[test]
+fn signature_format() {
+    // Computed independently as HMAC-SHA256(secret, id + timestamp + body).
+    let signature = sign(
+        b"5f5f121fc807a21bab4209b2f34e90932778f12c099ca3ca17ee00afd0b328ba",
+        "e76c6bd4-55c9-4987-8304-da1588d8988b",
+        "2019-11-16T10:11:12.634234626Z",
+        br#"{"challenge":"pogchamp-kappa-360noscope-vohiyo"}"#,
+    );
+    assert_eq!(
+        signature,
+        "sha256=3a059da461e1eec8c171660e21ed23da2c1050c6bb4e637a6629f1584667f801"
+    );
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn zeroizing_secrets() {
+    use eventsub_common::{
+        headers::read_eventsub_headers_untyped_at,
+        secret::{
+            secrecy::{SecretSlice, SecretString},
+            zeroize::Zeroizing,
+            HmacKey,
+        },
+        verify::verify_signature,
+    };
+
+    let now = Utc::now();
+    let headers = headers(now, BODY.as_bytes());
+    let parsed = read_eventsub_headers_untyped_at(&headers, now).unwrap();
+    let verify = |secret: &dyn HmacKey| verify_signature(secret, &parsed, BODY.as_bytes());
+
+    let slice = SecretSlice::from(SECRET.to_vec());
+    let string = SecretString::from(std::str::from_utf8(SECRET).unwrap());
+    let zeroizing = Zeroizing::new(SECRET.to_vec());
+    assert!(verify(&slice).is_ok());
+    assert!(verify(&string).is_ok());
+    assert!(verify(&zeroizing).is_ok());
+
+    // the secret isn't printed
+    for debug in [format!("{slice:?}"), format!("{string:?}")] {
+        assert!(!debug.contains("secretsecret"), "{debug}");
+    }
+    let error = verify(&SecretSlice::from(b"wrong".to_vec())).unwrap_err();
+    assert!(!format!("{error} {error:?}").contains("wrong"));
+}
@@ -0,0 +1,108 @@
+//! Signed deliveries for any subscription type, shared by the test matrices of both frameworks.
+//!
+//! Both crates include this file, so it only uses `eventsub_common`, `chrono` and `serde_json`.
+
+use chrono::{Duration, SecondsFormat, Utc};
+use eventsub_common::{
+    fixture::Fixture,
+    headers::{
+        MESSAGE_ID, MESSAGE_RETRY, MESSAGE_TIMESTAMP, MESSAGE_TYPE, SUBSCRIPTION_TYPE,
+        SUBSCRIPTION_VERSION,
+    },
+    types::EventSubscription,
+    MessageType,
+};
+use serde_json::{json, Value};
+
+pub const SUBSCRIPTION_ID: &str = "2d7bb1c5-5d1d-4e0e-9c6f-4f1a7c2c3a10";
+pub const CHALLENGE: &str = "pogchamp-kappa-360noscope-vohiyo";
+
+/// The condition of a subscription and the event its notifications carry.
+pub struct Case {
+    pub condition: Value,
+    pub event: Value,
+}
+
+/// Build a delivery of `message_type` for `P` with the condition and event of `case`,
+/// sent now and signed with `secret`.
+pub fn delivery<P: EventSubscription>(
+    message_type: MessageType,
+    case: &Case,
+    secret: &[u8],
+) -> Fixture {
+    let status = match message_type {
+        MessageType::Verification => "webhook_callback_verification_pending",
+        MessageType::Notification => "enabled",
+        MessageType::Revocation => "authorization_revoked",
+    };
+    let subscription = json!({
+        "id": SUBSCRIPTION_ID,
+        "status": status,
+        "type": P::EVENT_TYPE.to_str(),
+        "version": P::VERSION,
+        "condition": case.condition,
+        "transport": {
+            "method": "webhook",
+            "callback": "https://example.com/eventsub",
+        },
+        "created_at": "2024-11-11T18:24:39.125012394Z",
+        "cost": 0,
+    });
+    let body = match message_type {
+        MessageType::Verification => json!({
+            "challenge": CHALLENGE,
+            "subscription": subscription,
+        }),
+        MessageType::Notification => json!({
+            "subscription": subscription,
+            "event": case.event,
+        }),
+        MessageType::Revocation => json!({ "subscription": subscription }),
+    };
+
+    let mut fixture = Fixture::default();
+    fixture.set_header("Content-Type", "application/json");
+    fixture.set_header(
+        MESSAGE_ID,
+        format!("{}-{}", P::EVENT_TYPE.to_str(), message_type.as_str()),
+    );
+    fixture.set_header(MESSAGE_RETRY, "0");
+    fixture.set_header(MESSAGE_TYPE, message_type.as_str());
+    fixture.set_header(SUBSCRIPTION_TYPE, P::EVENT_TYPE.to_str());
+    fixture.set_header(SUBSCRIPTION_VERSION, P::VERSION);
+    fixture.set_body(body.to_string());
+    fixture.refresh(secret);
+    fixture
+}
+
+/// A redemption of a reward by a user, the event of both redemption subscriptions.
+pub fn redemption() -> Value {
+    json!({
+        "id": "f1f3c6a5-1bd4-4b7c-9bb7-7d1b2c8f4e2d",
+        "broadcaster_user_id": "53946574",
+        "broadcaster_user_login": "testBroadcaster",
+        "broadcaster_user_name": "testBroadcaster",
+        "user_id": "81723905",
+        "user_login": "testFromUser",
+        "user_name": "testFromUser",
+        "user_input": "",
+        "status": "unfulfilled",
+        "reward": {
+            "id": "9c3d2e8b-7c2a-4f6e-8d2b-1a5e7f9b3c4d",
+            "title": "Test Reward",
+            "cost": 150,
+            "prompt": "",
+        },
+        "redeemed_at": "2024-11-11T18:25:02.310938745Z",
+    })
+}
+
+/// Move the timestamp `minutes` into the past and sign the delivery again.
+pub fn age(fixture: &mut Fixture, minutes: i64, secret: &[u8]) {
+    let timestamp = Utc::now() - Duration::minutes(minutes);
+    fixture.set_header(
+        MESSAGE_TIMESTAMP,
+        timestamp.to_rfc3339_opts(SecondsFormat::Nanos, true),
+    );
+    fixture.sign(secret);
+}
@@ -0,0 +1,18 @@
+//! Event processing shared by the actix-web and axum tests.
+//!
+//! Both crates include this file, so it only uses `eventsub_common`.
+
+use eventsub_common::EventsubPayload;
+
+/// Describe a payload independent of its type and the framework it was extracted by.
+pub fn process<P>(payload: &EventsubPayload<P>) -> String {
+    match payload {
+        EventsubPayload::Verification(verification) => verification.challenge.clone(),
+        EventsubPayload::Notification(notification) => {
+            format!("notification for {}", notification.subscription.id)
+        }
+        EventsubPayload::Revocation(revocation) => {
+            format!("revocation of {}", revocation.subscription.id)
+        }
+    }
+}